@@ -1,7 +1,6 @@
 use std::{
     collections::BTreeMap,
     env,
-    ffi::OsString,
     fmt::Write as FmtWrite,
     fs,
     io,
@@ -29,13 +28,18 @@ const CAN_WRITE_TO_SRC: bool = option_env!("DOCS_RS").is_none();
 const ASSETS_DIR: &str = "assets";
 const ASM_DIR: &str = "asm";
 const ASM_CONTRACTS_DIR: &str = "contracts";
+const ASM_MODULES_DIR: &str = "modules";
 const ASM_NOTE_SCRIPTS_DIR: &str = "note_scripts";
 const LENDING_ERRORS_FILE: &str = "src/errors/lending_errors.rs";
 
+/// Contracts in compilation order, each one linked into the following ones
+const CONTRACT_ORDER: &[&str] = &["price_oracle", "lending_pool", "user_lending"];
+
 // MAIN
 // ================================================================================================
 
 /// Compiles MASM code during build time.
+/// - Compiles shared modules in asm/modules directory, statically linked into the contracts
 /// - Compiles contracts in asm/contracts directory into MASL library files
 /// - Compiles note scripts in asm/note_scripts directory into MASB files
 /// - Generates error constants from MASM code
@@ -57,6 +61,7 @@ fn main() -> Result<()> {
         // Set source and target directories
         let source_dir = dst.join(ASM_DIR);
         let contracts_dir = source_dir.join(ASM_CONTRACTS_DIR);
+        let modules_dir = source_dir.join(ASM_MODULES_DIR);
         let note_scripts_dir = source_dir.join(ASM_NOTE_SCRIPTS_DIR);
 
         let target_dir = Path::new(&build_dir).join(ASSETS_DIR);
@@ -65,7 +70,8 @@ fn main() -> Result<()> {
 
         // Compile contracts if they exist
         if contracts_dir.exists() {
-            let assembler = compile_contracts(&contracts_dir, &target_contracts_dir)?;
+            let modules = compile_modules(&modules_dir)?;
+            let assembler = compile_contracts(&contracts_dir, &target_contracts_dir, modules)?;
 
             // Compile note scripts if they exist
             if note_scripts_dir.exists() {
@@ -75,7 +81,7 @@ fn main() -> Result<()> {
 
         // Generate error constants from MASM if contracts exist
         if contracts_dir.exists() && CAN_WRITE_TO_SRC {
-            generate_error_constants(&contracts_dir, LENDING_ERRORS_FILE)?;
+            generate_error_constants(&source_dir, LENDING_ERRORS_FILE)?;
         }
     } else {
         println!("cargo:warning=No src/asm directory found, skipping MASM compilation");
@@ -91,16 +97,62 @@ fn create_assembler() -> Result<Assembler> {
     Ok(TransactionKernel::assembler().with_debug_mode(true))
 }
 
+/// Compiles the shared MASM modules into a single library
+///
+/// The modules are linked statically, so their procedures are copied into every contract using
+/// them and do not need to be deployed separately.
+fn compile_modules(source_dir: &Path) -> Result<Option<Library>, Report> {
+    let source_manager = Arc::new(DefaultSourceManager::default());
+    let mut modules = Vec::new();
+
+    for masm_file_path in get_masm_files(source_dir).into_diagnostic()? {
+        let name = masm_file_path.file_stem().unwrap().to_str().unwrap().to_string();
+        let code = fs::read_to_string(&masm_file_path).into_diagnostic()?;
+
+        let module = Module::parser(ModuleKind::Library).parse_str(
+            LibraryPath::new(format!("lending::{}", name)).into_diagnostic()?,
+            code,
+            &source_manager,
+        )?;
+        modules.push(module);
+
+        println!("cargo:warning=Compiled lending module: {}", name);
+    }
+
+    if modules.is_empty() {
+        return Ok(None);
+    }
+
+    // Decorators of modules calling each other are lost when the library is linked statically
+    // into a contract, so the modules are assembled without debug information
+    Ok(Some(create_assembler()?.with_debug_mode(false).assemble_library(modules)?))
+}
+
 /// Compiles contract MASM files into MASL libraries
+///
+/// Contracts are compiled in [`CONTRACT_ORDER`], followed by any other contract in name order, and
+/// each one is linked into the following ones, so a contract can reference the procedures of the
+/// contracts compiled before it (e.g. `lending_pool` invoking `price_oracle` procedures on a
+/// foreign account).
 fn compile_contracts(
     source_dir: &Path,
     target_dir: &Path,
+    modules: Option<Library>,
 ) -> Result<Assembler, Report> {
     fs::create_dir_all(target_dir).into_diagnostic()?;
 
     let mut assembler = create_assembler()?;
+    if let Some(modules) = modules {
+        assembler = assembler.with_static_library(modules)?;
+    }
 
-    for masm_file_path in get_masm_files(source_dir).into_diagnostic()? {
+    let mut masm_files = get_masm_files(source_dir).into_diagnostic()?;
+    masm_files.sort_by_key(|path| {
+        let name = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or_default();
+        CONTRACT_ORDER.iter().position(|contract| *contract == name).unwrap_or(CONTRACT_ORDER.len())
+    });
+
+    for masm_file_path in masm_files {
         let name = masm_file_path
             .file_stem()
             .unwrap()
@@ -234,7 +286,9 @@ fn extract_errors(
 fn generate_error_file(errors: BTreeMap<String, String>) -> Result<String> {
     let mut output = String::new();
 
-    writeln!(output, "use miden_lib::errors::MasmError;\n").unwrap();
+    if !errors.is_empty() {
+        writeln!(output, "use miden_lib::errors::MasmError;\n").unwrap();
+    }
     writeln!(
         output,
         "// This file is generated by build.rs, do not modify manually."
@@ -242,7 +296,7 @@ fn generate_error_file(errors: BTreeMap<String, String>) -> Result<String> {
     .unwrap();
     writeln!(
         output,
-        "// It extracts error constants from MASM files in the contracts directory.\n"
+        "// It extracts error constants from MASM files in the contracts and modules directories.\n"
     )
     .unwrap();
 
@@ -293,7 +347,7 @@ fn copy_directory<T: AsRef<Path>, R: AsRef<Path>>(src: T, dst: R) {
     }
 }
 
-/// Returns a vector with paths to all MASM files in the specified directory
+/// Returns a vector with paths to all MASM files in the specified directory, sorted by name
 fn get_masm_files<P: AsRef<Path>>(dir_path: P) -> io::Result<Vec<PathBuf>> {
     let mut files = Vec::new();
 
@@ -308,6 +362,7 @@ fn get_masm_files<P: AsRef<Path>>(dir_path: P) -> io::Result<Vec<PathBuf>> {
         }
     }
 
+    files.sort();
     Ok(files)
 }
 
@@ -316,7 +371,7 @@ fn is_masm_file(path: &Path) -> io::Result<bool> {
     if let Some(extension) = path.extension() {
         let extension = extension
             .to_str()
-            .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "invalid UTF-8 filename"))?
+            .ok_or_else(|| io::Error::other("invalid UTF-8 filename"))?
             .to_lowercase();
         Ok(extension == "masm")
     } else {
@@ -1,8 +1,15 @@
 // Account management module
 
-use crate::miden_client::{LendingClient, AccountStorageMode, AccountId};
+use crate::components::{
+    lending_pool::LendingPoolAccount, price_oracle::PriceOracleAccount,
+    user_lending::UserLendingAccount,
+};
+use crate::miden_client::{
+    AccountId, AccountStorageMode, LendingBackend, LendingClient, TransactionScriptBuilder,
+};
 use anyhow::{Context, Result};
-use std::fs;
+use miden_lib::account::wallets::BasicWallet;
+use serde::Serialize;
 use std::path::Path;
 
 // This module handles:
@@ -11,8 +18,8 @@ use std::path::Path;
 // - Deploying price oracle accounts
 // - Managing account state
 
-pub struct AccountManager {
-    client: LendingClient,
+pub struct AccountManager<B: LendingBackend = LendingClient> {
+    client: B,
 }
 
 impl AccountManager {
@@ -20,109 +27,145 @@ impl AccountManager {
         let client = LendingClient::new(rpc_endpoint, store_path.to_path_buf()).await?;
         Ok(Self { client })
     }
+}
 
-    /// Create a user lending account
-    pub async fn create_user_account(&mut self, storage_mode: &str) -> Result<String> {
-        // Load user lending account code
-        let account_code = fs::read_to_string("../accounts/user_lending.masm")
-            .context("Failed to read user lending account code")?;
+impl<B: LendingBackend> AccountManager<B> {
+    /// Create an account manager on top of an existing backend
+    pub fn from_client(client: B) -> Self {
+        Self { client }
+    }
 
+    /// Create a user lending account
+    ///
+    /// The account holds its position with `pool_id`.
+    pub async fn create_user_account(
+        &mut self,
+        storage_mode: &str,
+        pool_id: &AccountId,
+    ) -> Result<AccountId> {
         let mode = match storage_mode.to_lowercase().as_str() {
             "public" => AccountStorageMode::Public,
             "private" => AccountStorageMode::Private,
             _ => AccountStorageMode::Private,
         };
 
-        let account_id = self.client.create_account(&account_code, mode).await?;
-
-        Ok(format!("User account created: {}", account_id.to_hex()))
+        // The basic wallet holds the user's assets and sends them with request notes
+        let components = vec![BasicWallet.into(), UserLendingAccount::new(pool_id.try_into()?).into()];
+        self.client.create_account(components, mode).await
     }
 
     /// Deploy the lending pool account
-    pub async fn deploy_lending_pool(&mut self) -> Result<String> {
-        // Load lending pool account code
-        let account_code = fs::read_to_string("../accounts/lending_pool.masm")
-            .context("Failed to read lending pool account code")?;
+    ///
+    /// The pool values positions with the prices of `oracle_id`, which must be deployed first.
+    /// The USDC, DAI and WETH reserves hold the assets of `faucet_ids`, in that order.
+    pub async fn deploy_lending_pool(
+        &mut self,
+        oracle_id: &AccountId,
+        faucet_ids: &[AccountId; 3],
+    ) -> Result<AccountId> {
+        let [usdc, dai, weth] = faucet_ids;
+        let [usdc, dai, weth] = [usdc.try_into()?, dai.try_into()?, weth.try_into()?];
+        if usdc == dai || usdc == weth || dai == weth {
+            anyhow::bail!("USDC, DAI and WETH must be issued by different faucets");
+        }
+        let pool = LendingPoolAccount::new(oracle_id.try_into()?, [usdc, dai, weth]);
+
+        // Start accruing interest on the listed reserves, which also commits the pool
+        let script = pool
+            .reserves()
+            .iter()
+            .fold(TransactionScriptBuilder::new(), |script, reserve| {
+                script
+                    .push(reserve.asset_id)
+                    .call_procedure("lending_pool", "update_reserve_state")
+            })
+            .build();
 
         // Lending pool should be public
-        let account_id = self.client
-            .create_account(&account_code, AccountStorageMode::Public)
+        let pool_id = self
+            .client
+            .create_account(vec![pool.into()], AccountStorageMode::Public)
             .await?;
 
-        Ok(format!("Lending pool deployed: {}", account_id.to_hex()))
+        self.commit_account(&pool_id, &script).await?;
+        Ok(pool_id)
     }
 
     /// Deploy the price oracle account
-    pub async fn deploy_price_oracle(&mut self) -> Result<String> {
-        // Load price oracle account code
-        let account_code = fs::read_to_string("../accounts/price_oracle.masm")
-            .context("Failed to read price oracle account code")?;
+    pub async fn deploy_price_oracle(&mut self) -> Result<AccountId> {
+        let oracle = PriceOracleAccount::new();
+
+        // Add the price feeds with their initial prices, which also commits the oracle
+        let script = oracle
+            .feeds()
+            .iter()
+            .fold(TransactionScriptBuilder::new(), |script, (asset_id, price)| {
+                script
+                    .push(*price)
+                    .push(*asset_id)
+                    .call_procedure("price_oracle", "add_price_feed")
+            })
+            .build();
 
         // Oracle should be public
-        let account_id = self.client
-            .create_account(&account_code, AccountStorageMode::Public)
+        let oracle_id = self
+            .client
+            .create_account(vec![oracle.into()], AccountStorageMode::Public)
             .await?;
 
-        Ok(format!("Price oracle deployed: {}", account_id.to_hex()))
+        self.commit_account(&oracle_id, &script).await?;
+        Ok(oracle_id)
     }
 
-    /// Get account information
-    pub async fn get_account_info(&self, account_id: &AccountId) -> Result<AccountInfo> {
-        let account = self.client.get_account(account_id).await?;
+    /// Commits a new account on-chain with its first transaction
+    ///
+    /// The pool reads the oracle and user accounts read the pool with foreign procedure
+    /// invocation, which only works for accounts already on-chain. Transactions must change the
+    /// account's state, so the script cannot be empty.
+    async fn commit_account(&mut self, account_id: &AccountId, script: &str) -> Result<()> {
+        self.client
+            .execute_transaction(script, account_id)
+            .await
+            .with_context(|| format!("Failed to commit account {}", account_id.to_hex()))?;
+
+        Ok(())
+    }
+}
 
-        // Parse storage to extract account data
-        // For user accounts: storage[0-2] = collateral, storage[3-5] = debt
-        let collateral_usdc = if account.storage.len() > 0 {
-            account.storage[0]
-        } else {
-            0
-        };
-        let collateral_dai = if account.storage.len() > 1 {
-            account.storage[1]
-        } else {
-            0
-        };
-        let collateral_weth = if account.storage.len() > 2 {
-            account.storage[2]
-        } else {
-            0
-        };
+/// Position of a user account in the lending pool
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountInfo {
+    pub account_id: String,
+    /// Position in each listed reserve, in listing order
+    pub reserves: Vec<ReservePosition>,
+    /// Health factor at the oracle's spot prices, in basis points
+    pub health_factor: u64,
+}
 
-        let debt_usdc = if account.storage.len() > 3 {
-            account.storage[3]
-        } else {
-            0
-        };
-        let debt_dai = if account.storage.len() > 4 {
-            account.storage[4]
-        } else {
-            0
-        };
-        let debt_weth = if account.storage.len() > 5 {
-            account.storage[5]
-        } else {
-            0
-        };
+impl AccountInfo {
+    /// Position in a reserve, if the asset is listed
+    pub fn reserve(&self, asset_id: u64) -> Option<&ReservePosition> {
+        self.reserves.iter().find(|reserve| reserve.asset_id == asset_id)
+    }
+
+    /// Collateral supplied in an asset
+    pub fn collateral(&self, asset_id: u64) -> u64 {
+        self.reserve(asset_id).map_or(0, |reserve| reserve.collateral)
+    }
 
-        Ok(AccountInfo {
-            account_id: account_id.to_hex(),
-            collateral_usdc,
-            collateral_dai,
-            collateral_weth,
-            debt_usdc,
-            debt_dai,
-            debt_weth,
-        })
+    /// Debt in an asset, scaled by the borrow index
+    pub fn scaled_debt(&self, asset_id: u64) -> u64 {
+        self.reserve(asset_id).map_or(0, |reserve| reserve.scaled_debt)
     }
 }
 
-#[derive(Debug)]
-pub struct AccountInfo {
-    pub account_id: String,
-    pub collateral_usdc: u64,
-    pub collateral_dai: u64,
-    pub collateral_weth: u64,
-    pub debt_usdc: u64,
-    pub debt_dai: u64,
-    pub debt_weth: u64,
+/// Balances of a user account in one reserve of the lending pool
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct ReservePosition {
+    pub asset_id: u64,
+    /// Supplied balance, scaled by the liquidity index
+    pub scaled_supply: u64,
+    pub collateral: u64,
+    /// Debt, scaled by the borrow index
+    pub scaled_debt: u64,
 }
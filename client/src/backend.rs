@@ -0,0 +1,131 @@
+// Backend abstraction for the lending client
+// TransactionBuilder and AccountManager run on any implementation of this trait, so local,
+// mock chain and node-backed clients can be swapped freely
+
+use anyhow::Result;
+use miden_objects::{
+    account::AccountComponent, block::BlockNumber, note::Note as MidenNote, vm::AdviceMap, Felt, Word,
+};
+use std::future::Future;
+
+use crate::miden_client::{Account, AccountId, AccountStorageMode, TransactionResult};
+
+/// Chain access required by the lending protocol
+///
+/// Implemented by [`LendingClient`](crate::miden_client::LendingClient) for a Miden node and by
+/// `MockLendingChain` (with the `testing` feature) for in-process tests. Applications embedding
+/// the library can provide their own implementation, e.g. for a custom RPC transport, using
+/// [`build_account`](crate::miden_client::build_account) and
+/// [`compile_tx_script`](crate::miden_client::compile_tx_script) to stay compatible with the
+/// lending contracts.
+pub trait LendingBackend {
+    /// Create a new account with the given components
+    fn create_account(
+        &mut self,
+        components: Vec<AccountComponent>,
+        storage_mode: AccountStorageMode,
+    ) -> impl Future<Output = Result<AccountId>>;
+
+    /// Execute a transaction script against an account and apply its state changes
+    fn execute_transaction(
+        &mut self,
+        tx_script: &str,
+        account_id: &AccountId,
+    ) -> impl Future<Output = Result<TransactionResult>> {
+        self.execute_transaction_with_foreign_accounts(tx_script, AdviceMap::default(), account_id, &[])
+    }
+
+    /// Execute a transaction script with the given advice map entries
+    ///
+    /// The entries of `advice_map` are available to the script, e.g. the details of the
+    /// recipients of the notes it creates, which are then output in full.
+    fn execute_transaction_with_advice(
+        &mut self,
+        tx_script: &str,
+        advice_map: AdviceMap,
+        account_id: &AccountId,
+    ) -> impl Future<Output = Result<TransactionResult>> {
+        self.execute_transaction_with_foreign_accounts(tx_script, advice_map, account_id, &[])
+    }
+
+    /// Execute a transaction script that reads the given foreign accounts with foreign procedure
+    /// invocation
+    ///
+    /// Foreign accounts must be public and committed on-chain.
+    fn execute_transaction_with_foreign_accounts(
+        &mut self,
+        tx_script: &str,
+        advice_map: AdviceMap,
+        account_id: &AccountId,
+        foreign_account_ids: &[AccountId],
+    ) -> impl Future<Output = Result<TransactionResult>>;
+
+    /// Execute a transaction creating notes from an account
+    ///
+    /// The notes are public, so the account they are addressed to can consume them once the
+    /// transaction is committed.
+    fn send_notes(
+        &mut self,
+        notes: Vec<MidenNote>,
+        account_id: &AccountId,
+    ) -> impl Future<Output = Result<TransactionResult>>;
+
+    /// Execute a transaction consuming notes against an account
+    ///
+    /// The entries of `advice_map` are available to the note scripts, e.g. signatures verified by
+    /// the procedures they call.
+    fn consume_notes(
+        &mut self,
+        notes: Vec<MidenNote>,
+        advice_map: AdviceMap,
+        account_id: &AccountId,
+    ) -> impl Future<Output = Result<TransactionResult>> {
+        self.consume_notes_with_foreign_accounts(notes, advice_map, account_id, &[])
+    }
+
+    /// Execute a transaction consuming notes whose scripts read the given foreign accounts with
+    /// foreign procedure invocation
+    fn consume_notes_with_foreign_accounts(
+        &mut self,
+        notes: Vec<MidenNote>,
+        advice_map: AdviceMap,
+        account_id: &AccountId,
+        foreign_account_ids: &[AccountId],
+    ) -> impl Future<Output = Result<TransactionResult>>;
+
+    /// Execute a read-only script against an account and return the resulting stack
+    fn execute_view_script(
+        &self,
+        tx_script: &str,
+        account_id: &AccountId,
+    ) -> impl Future<Output = Result<Vec<u64>>> {
+        self.execute_view_script_with_foreign_accounts(tx_script, account_id, &[])
+    }
+
+    /// Execute a read-only script that reads the given foreign accounts with foreign procedure
+    /// invocation
+    fn execute_view_script_with_foreign_accounts(
+        &self,
+        tx_script: &str,
+        account_id: &AccountId,
+        foreign_account_ids: &[AccountId],
+    ) -> impl Future<Output = Result<Vec<u64>>>;
+
+    /// Get the number of the latest block, which transactions are executed against
+    fn get_block_number(&self) -> impl Future<Output = Result<BlockNumber>>;
+
+    /// Get the current state of an account
+    fn get_account(&self, account_id: &AccountId) -> impl Future<Output = Result<Account>>;
+
+    /// Sign a message with the key of an account
+    ///
+    /// Returns the signature in the form `rpo_falcon512::verify` reads from the advice stack.
+    fn sign_message(
+        &self,
+        account_id: &AccountId,
+        message: Word,
+    ) -> impl Future<Output = Result<Vec<Felt>>>;
+
+    /// Get the P2ID notes addressed to an account that have not been consumed yet
+    fn get_notes(&self, account_id: &AccountId) -> impl Future<Output = Result<Vec<MidenNote>>>;
+}
@@ -1,10 +1,17 @@
 use miden_objects::{
     account::{
-        Account, AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageSlot,
+        AccountBuilder, AccountComponent, AccountId, AccountStorageMode, AccountType,
+        StorageMap, StorageSlot,
     },
     utils::sync::LazyLock,
     AccountError, Felt, FieldElement, Word,
 };
+use serde::Serialize;
+
+use crate::fixed_point::{bps_to_wad, mul_div, wad_mul, WAD};
+
+#[cfg(any(feature = "testing", test))]
+use miden_objects::account::Account;
 
 /// Compiled MASM library for lending pool
 static LENDING_POOL_LIBRARY_BYTES: &[u8] =
@@ -16,79 +23,471 @@ static LENDING_POOL_LIBRARY: LazyLock<miden_objects::assembly::Library> =
             .expect("failed to deserialize lending pool library")
     });
 
-fn lending_pool_library() -> miden_objects::assembly::Library {
+/// Returns the compiled contract library
+pub fn lending_pool_library() -> miden_objects::assembly::Library {
     LENDING_POOL_LIBRARY.clone()
 }
 
-/// LendingPool Account Component
+/// Storage maps of the pool component read by `get_health_factor`
+const RESERVES_SLOT: u8 = 0;
+const RESERVE_LIST_SLOT: u8 = 2;
+const POSITIONS_SLOT: u8 = 4;
+
+/// Position balances valued by `get_health_factor`
+const POSITION_COLLATERAL: u64 = 1;
+const POSITION_DEBT: u64 = 2;
+
+/// Number of fields of a reserve in the reserve map
+const NUM_RESERVE_FIELDS: u64 = 20;
+
+/// Reserve map fields initialized when a reserve is listed
+const RESERVE_LISTED: u64 = 0;
+const RESERVE_DECIMALS: u64 = 1;
+const LIQUIDITY_INDEX: u64 = 6;
+const BORROW_INDEX: u64 = 7;
+const BASE_BORROW_RATE: u64 = 9;
+const RATE_SLOPE1: u64 = 10;
+const RATE_SLOPE2: u64 = 11;
+const OPTIMAL_UTILIZATION: u64 = 12;
+const RESERVE_FACTOR: u64 = 13;
+const LTV: u64 = 14;
+const LIQUIDATION_THRESHOLD: u64 = 15;
+const LIQUIDATION_BONUS: u64 = 16;
+const FAUCET_ID_PREFIX: u64 = 18;
+const FAUCET_ID_SUFFIX: u64 = 19;
+
+/// Basis point precision of rates and strategy parameters (10000 = 100%)
+pub const RATE_PRECISION: u64 = 10000;
+
+/// Upper bound of the base rate and each slope accepted by the pool
+pub const MAX_RATE: u64 = 10000;
+
+/// Upper bound of the liquidation bonus accepted by the pool (200%)
+pub const MAX_LIQUIDATION_BONUS: u64 = 2 * RATE_PRECISION;
+
+/// Most decimals a reserve's asset can have, keeping its unit and values in a field element
+pub const MAX_DECIMALS: u32 = 18;
+
+/// Share of a borrower's debt in an asset one liquidation can cover, in basis points
+pub const CLOSE_FACTOR: u64 = 5000;
+
+/// Initial liquidity and borrow index (1.0 as a wad)
+const INITIAL_INDEX: u64 = WAD;
+
+/// Bound liquidity and borrow indices saturate at (18.0 as a wad), keeping them in a field element
 ///
-/// Manages liquidity pools for multiple assets (USDC, DAI, WETH).
-/// Storage slots contain reserve data including total liquidity, borrowed amounts,
-/// and interest rates for each asset.
-pub struct LendingPoolAccount {
-    /// Reserve data for each asset stored as Word
-    /// Each reserve uses multiple storage slots for:
-    /// - total_liquidity
-    /// - total_borrowed
-    /// - liquidity_rate
-    /// - borrow_rate
-    /// - last_update_timestamp
-    /// - liquidity_index
-    /// - borrow_index
-    usdc_reserve: Vec<Word>,
-    dai_reserve: Vec<Word>,
-    weth_reserve: Vec<Word>,
+/// Once a reserve's borrow index reaches it, debts stop growing and suppliers stop earning.
+pub const MAX_INDEX: u64 = 18 * WAD;
+
+/// Returns the utilization of a reserve as a wad, as computed by the pool
+///
+/// Utilization is capped at 100%, which the total borrowed can outgrow as debts compound.
+pub fn utilization(total_borrowed: u64, total_liquidity: u64) -> Option<u64> {
+    if total_liquidity == 0 {
+        Some(0)
+    } else if total_borrowed >= total_liquidity {
+        Some(WAD)
+    } else {
+        mul_div(total_borrowed, WAD, total_liquidity)
+    }
 }
 
-impl LendingPoolAccount {
-    /// Create a new lending pool with empty reserves
-    pub fn new() -> Self {
-        // Initialize with empty reserves
+/// Two-slope interest rate strategy of a reserve, in basis points
+///
+/// Below the optimal utilization the borrow rate rises from the base rate by up to `slope1`;
+/// above it, the remaining utilization adds up to `slope2` on top. Suppliers earn the borrow
+/// rate weighted by utilization, minus the reserve factor. The resulting rates are yearly wads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct RateStrategy {
+    pub base_rate: u64,
+    pub slope1: u64,
+    pub slope2: u64,
+    pub optimal_utilization: u64,
+    pub reserve_factor: u64,
+}
+
+impl RateStrategy {
+    /// Stablecoin curve: flat until 90% utilization, then steep
+    pub fn stablecoin() -> Self {
         Self {
-            usdc_reserve: vec![Word::default(); 7],
-            dai_reserve: vec![Word::default(); 6],
-            weth_reserve: vec![Word::default(); 6],
+            base_rate: 0,
+            slope1: 400,
+            slope2: 6000,
+            optimal_utilization: 9000,
+            reserve_factor: 1000,
         }
     }
 
-    /// Create with custom initial reserve values
-    pub fn with_reserves(
-        usdc_reserve: Vec<Word>,
-        dai_reserve: Vec<Word>,
-        weth_reserve: Vec<Word>,
-    ) -> Self {
+    /// Volatile asset curve: lower optimal utilization and a larger reserve factor
+    pub fn volatile() -> Self {
         Self {
-            usdc_reserve,
-            dai_reserve,
-            weth_reserve,
+            base_rate: 0,
+            slope1: 700,
+            slope2: 8000,
+            optimal_utilization: 8000,
+            reserve_factor: 1500,
         }
     }
+
+    /// Checks the bounds enforced by the pool
+    pub fn validate(&self) -> anyhow::Result<()> {
+        for (name, rate) in
+            [("base rate", self.base_rate), ("slope 1", self.slope1), ("slope 2", self.slope2)]
+        {
+            if rate > MAX_RATE {
+                anyhow::bail!("The {} must be at most {} bps, got {}", name, MAX_RATE, rate);
+            }
+        }
+        if self.optimal_utilization == 0 || self.optimal_utilization >= RATE_PRECISION {
+            anyhow::bail!(
+                "The optimal utilization must be between 0 and {} bps, got {}",
+                RATE_PRECISION,
+                self.optimal_utilization
+            );
+        }
+        if self.reserve_factor > RATE_PRECISION {
+            anyhow::bail!(
+                "The reserve factor must be at most {} bps, got {}",
+                RATE_PRECISION,
+                self.reserve_factor
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Returns the borrow and supply rates at a utilization, as computed by the pool
+    ///
+    /// Utilization and rates are wads, so the results match the pool's to the last unit. Returns
+    /// `None` where the pool would fail on an overflow.
+    pub fn rates_at(&self, utilization: u64) -> Option<(u64, u64)> {
+        let base_rate = bps_to_wad(self.base_rate)?;
+        let borrow_rate = if utilization < bps_to_wad(self.optimal_utilization)? {
+            base_rate + mul_div(utilization, self.slope1, self.optimal_utilization)?
+        } else {
+            let excess = utilization - bps_to_wad(self.optimal_utilization)?;
+            base_rate
+                + mul_div(excess, self.slope2, RATE_PRECISION - self.optimal_utilization)?
+                + bps_to_wad(self.slope1)?
+        };
+        let supply_rate = mul_div(
+            wad_mul(utilization, borrow_rate)?,
+            RATE_PRECISION - self.reserve_factor,
+            RATE_PRECISION,
+        )?;
+
+        Some((borrow_rate, supply_rate))
+    }
+}
+
+/// Risk parameters of an asset used as collateral, in basis points
+///
+/// Borrows are capped at `ltv` of the collateral value, while positions become liquidatable once
+/// their debt exceeds `liquidation_threshold` of it. Liquidators receive `liquidation_bonus` of
+/// the covered debt's value in collateral (10500 = 5% bonus).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct RiskParameters {
+    pub ltv: u64,
+    pub liquidation_threshold: u64,
+    pub liquidation_bonus: u64,
 }
 
-impl Default for LendingPoolAccount {
-    fn default() -> Self {
-        Self::new()
+impl RiskParameters {
+    /// Stablecoin parameters: high LTV and a small bonus
+    pub fn stablecoin() -> Self {
+        Self {
+            ltv: 8000,
+            liquidation_threshold: 8500,
+            liquidation_bonus: 10400,
+        }
+    }
+
+    /// Volatile asset parameters: more room between LTV and threshold and a larger bonus
+    pub fn volatile() -> Self {
+        Self {
+            ltv: 7500,
+            liquidation_threshold: 8250,
+            liquidation_bonus: 10500,
+        }
+    }
+
+    /// Checks the bounds enforced by the pool
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.ltv > self.liquidation_threshold {
+            anyhow::bail!(
+                "The LTV ({} bps) cannot exceed the liquidation threshold ({} bps)",
+                self.ltv,
+                self.liquidation_threshold
+            );
+        }
+        if self.liquidation_threshold > RATE_PRECISION {
+            anyhow::bail!(
+                "The liquidation threshold must be at most {} bps, got {}",
+                RATE_PRECISION,
+                self.liquidation_threshold
+            );
+        }
+        if self.liquidation_bonus < RATE_PRECISION || self.liquidation_bonus > MAX_LIQUIDATION_BONUS {
+            anyhow::bail!(
+                "The liquidation bonus must be between {} and {} bps, got {}",
+                RATE_PRECISION,
+                MAX_LIQUIDATION_BONUS,
+                self.liquidation_bonus
+            );
+        }
+        if self.liquidation_threshold * self.liquidation_bonus > RATE_PRECISION * RATE_PRECISION {
+            anyhow::bail!(
+                "A {} bps liquidation bonus would seize more collateral than backs debt at a {} bps threshold",
+                self.liquidation_bonus,
+                self.liquidation_threshold
+            );
+        }
+
+        Ok(())
     }
 }
 
-impl From<LendingPoolAccount> for AccountComponent {
-    fn from(pool: LendingPoolAccount) -> Self {
-        let mut storage_slots = Vec::new();
+/// Oracle price positions are valued at when they are liquidated
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PriceSource {
+    /// Latest reported price
+    #[default]
+    Spot,
+    /// Time-weighted average price over the oracle's TWAP window
+    Twap,
+}
+
+impl From<PriceSource> for u64 {
+    fn from(source: PriceSource) -> Self {
+        match source {
+            PriceSource::Spot => 0,
+            PriceSource::Twap => 1,
+        }
+    }
+}
 
-        // Add USDC reserve slots (0-6)
-        for word in pool.usdc_reserve {
-            storage_slots.push(StorageSlot::Value(word));
+impl TryFrom<u64> for PriceSource {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u64) -> anyhow::Result<Self> {
+        match value {
+            0 => Ok(PriceSource::Spot),
+            1 => Ok(PriceSource::Twap),
+            _ => anyhow::bail!("Unknown price source {}", value),
         }
+    }
+}
+
+impl std::str::FromStr for PriceSource {
+    type Err = anyhow::Error;
+
+    fn from_str(source: &str) -> anyhow::Result<Self> {
+        match source {
+            "spot" => Ok(PriceSource::Spot),
+            "twap" => Ok(PriceSource::Twap),
+            _ => anyhow::bail!("Unknown price source '{}', expected 'spot' or 'twap'", source),
+        }
+    }
+}
 
-        // Add DAI reserve slots (7-12)
-        for word in pool.dai_reserve {
-            storage_slots.push(StorageSlot::Value(word));
+impl std::fmt::Display for PriceSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PriceSource::Spot => write!(f, "spot"),
+            PriceSource::Twap => write!(f, "twap"),
         }
+    }
+}
+
+/// Configuration of an asset listed in the lending pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ReserveConfig {
+    /// Asset ID as used by the price oracle
+    pub asset_id: u64,
+    /// Fungible faucet issuing the asset the reserve holds, serialized as hex
+    #[serde(serialize_with = "serialize_account_id")]
+    pub faucet_id: AccountId,
+    /// Number of decimals of the asset's amounts
+    pub decimals: u32,
+    /// Interest rate strategy of the reserve
+    pub strategy: RateStrategy,
+    /// Risk parameters of the asset as collateral
+    pub risk: RiskParameters,
+}
+
+impl ReserveConfig {
+    pub fn new(
+        asset_id: u64,
+        faucet_id: AccountId,
+        decimals: u32,
+        strategy: RateStrategy,
+        risk: RiskParameters,
+    ) -> Self {
+        Self { asset_id, faucet_id, decimals, strategy, risk }
+    }
 
-        // Add WETH reserve slots (13-18)
-        for word in pool.weth_reserve {
-            storage_slots.push(StorageSlot::Value(word));
+    /// Checks the bounds enforced by the pool
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.decimals > MAX_DECIMALS {
+            anyhow::bail!("The asset can have at most {} decimals, got {}", MAX_DECIMALS, self.decimals);
         }
+        self.strategy.validate()?;
+        self.risk.validate()
+    }
+}
+
+fn serialize_account_id<S: serde::Serializer>(account_id: &AccountId, serializer: S) -> Result<S::Ok, S::Error> {
+    crate::miden_client::AccountId::from(*account_id).serialize(serializer)
+}
+
+/// LendingPool Account Component
+///
+/// Manages liquidity pools for any number of assets.
+/// Reserve data (liquidity, borrowed amounts, rates and indices) is stored in a single storage
+/// map keyed by asset ID and field, so further assets can be listed after deployment with the
+/// admin `add_reserve` procedure.
+///
+/// Every reserve holds the asset of its own fungible faucet, which positions are supplied, borrowed
+/// and repaid in.
+///
+/// Every reserve has its own interest rate strategy, tunable with the admin `set_rate_strategy`
+/// procedure. The reserve factor share of the borrow interest accrues to the treasury, and is
+/// paid out with the admin `claim_reserves` procedure.
+///
+/// Liquidations value positions at the oracle's spot prices, or at its time-weighted average
+/// prices once selected with the admin `set_liquidation_price_source` procedure.
+///
+/// Storage slot 0: reserve map, `[asset_id, field, 0, 0] -> [value, 0, 0, 0]`
+/// Storage slot 1: number of listed reserves
+/// Storage slot 2: listed assets in listing order, `[index, 0, 0, 0] -> [asset_id, 0, 0, 0]`
+/// Storage slot 3: liquidation price source
+/// Storage slot 4: account positions, `[asset_id, kind, account_id_suffix, account_id_prefix] ->
+/// [value, 0, 0, 0]`, with supplied balances (kind 0) scaled by the liquidity index, collateral
+/// (kind 1) and debts (kind 2) scaled by the borrow index
+/// Storage slot 5: price oracle account ID
+/// Storage slot 6: reserve backed by each faucet, `[faucet_id_prefix, faucet_id_suffix, 0, 0] ->
+/// [asset_id, 1, 0, 0]`
+pub struct LendingPoolAccount {
+    /// Reserves listed at deployment
+    reserves: Vec<ReserveConfig>,
+
+    /// Price oracle account ID reference, read to value positions
+    oracle_account_id: AccountId,
+}
+
+impl LendingPoolAccount {
+    /// Create a new lending pool listing USDC, DAI and WETH with empty reserves
+    ///
+    /// `faucet_ids` are the faucets issuing USDC, DAI and WETH, in that order.
+    pub fn new(oracle_account_id: AccountId, faucet_ids: [AccountId; 3]) -> Self {
+        let [usdc, dai, weth] = faucet_ids;
+        Self::with_reserves(
+            oracle_account_id,
+            vec![
+                ReserveConfig::new(1, usdc, 6, RateStrategy::stablecoin(), RiskParameters::stablecoin()),
+                ReserveConfig::new(2, dai, 8, RateStrategy::stablecoin(), RiskParameters::stablecoin()),
+                ReserveConfig::new(3, weth, 8, RateStrategy::volatile(), RiskParameters::volatile()),
+            ],
+        )
+    }
+
+    /// Create a lending pool listing the given reserves
+    pub fn with_reserves(oracle_account_id: AccountId, reserves: Vec<ReserveConfig>) -> Self {
+        Self { reserves, oracle_account_id }
+    }
+
+    /// Reserves listed at deployment
+    pub fn reserves(&self) -> &[ReserveConfig] {
+        &self.reserves
+    }
+}
+
+/// Returns a single-value storage word `[value, 0, 0, 0]`
+fn value_word(value: u64) -> Word {
+    Word::new([Felt::new(value), Felt::ZERO, Felt::ZERO, Felt::ZERO])
+}
+
+/// Returns the reserve map key of a field of an asset
+fn reserve_key(asset_id: u64, field: u64) -> Word {
+    Word::new([Felt::new(asset_id), Felt::new(field), Felt::ZERO, Felt::ZERO])
+}
+
+/// Returns the position map key of a balance of an account in a reserve
+fn position_key(account_id: AccountId, asset_id: u64, kind: u64) -> Word {
+    Word::new([Felt::new(asset_id), Felt::new(kind), account_id.suffix(), account_id.prefix().as_felt()])
+}
+
+/// Storage map entries `get_health_factor` reads to value an account's position, by slot of the
+/// pool component
+///
+/// These are the fields and list entries of every listed reserve, and the account's collateral and
+/// debt in each of them, whether it holds any or not. Other accounts' positions are not read.
+pub fn health_factor_map_keys(slots: &[StorageSlot], account_id: AccountId) -> Vec<(u8, Vec<Word>)> {
+    let asset_ids = super::listed_assets(slots, RESERVE_LIST_SLOT);
+
+    let reserve_keys = asset_ids
+        .iter()
+        .flat_map(|asset_id| (0..NUM_RESERVE_FIELDS).map(|field| reserve_key(*asset_id, field)))
+        .collect();
+    let list_keys = (0..asset_ids.len() as u64).map(value_word).collect();
+    let position_keys = asset_ids
+        .iter()
+        .flat_map(|asset_id| {
+            [POSITION_COLLATERAL, POSITION_DEBT].map(|kind| position_key(account_id, *asset_id, kind))
+        })
+        .collect();
+
+    vec![
+        (RESERVES_SLOT, reserve_keys),
+        (RESERVE_LIST_SLOT, list_keys),
+        (POSITIONS_SLOT, position_keys),
+    ]
+}
+
+impl From<LendingPoolAccount> for AccountComponent {
+    fn from(pool: LendingPoolAccount) -> Self {
+        let mut reserves = StorageMap::new();
+        let mut reserve_list = StorageMap::new();
+        let mut faucet_reserves = StorageMap::new();
+
+        for (index, reserve) in pool.reserves.iter().enumerate() {
+            let fields = [
+                (RESERVE_LISTED, 1),
+                (RESERVE_DECIMALS, reserve.decimals as u64),
+                (FAUCET_ID_PREFIX, reserve.faucet_id.prefix().as_u64()),
+                (FAUCET_ID_SUFFIX, reserve.faucet_id.suffix().as_int()),
+                (LIQUIDITY_INDEX, INITIAL_INDEX),
+                (BORROW_INDEX, INITIAL_INDEX),
+                (BASE_BORROW_RATE, reserve.strategy.base_rate),
+                (RATE_SLOPE1, reserve.strategy.slope1),
+                (RATE_SLOPE2, reserve.strategy.slope2),
+                (OPTIMAL_UTILIZATION, reserve.strategy.optimal_utilization),
+                (RESERVE_FACTOR, reserve.strategy.reserve_factor),
+                (LTV, reserve.risk.ltv),
+                (LIQUIDATION_THRESHOLD, reserve.risk.liquidation_threshold),
+                (LIQUIDATION_BONUS, reserve.risk.liquidation_bonus),
+            ];
+            for (field, value) in fields {
+                reserves.insert(reserve_key(reserve.asset_id, field), value_word(value));
+            }
+
+            reserve_list.insert(value_word(index as u64), value_word(reserve.asset_id));
+            faucet_reserves.insert(
+                super::account_id_word(reserve.faucet_id),
+                Word::new([Felt::new(reserve.asset_id), Felt::ONE, Felt::ZERO, Felt::ZERO]),
+            );
+        }
+
+        let storage_slots = vec![
+            StorageSlot::Map(reserves),
+            StorageSlot::Value(value_word(pool.reserves.len() as u64)),
+            StorageSlot::Map(reserve_list),
+            StorageSlot::Value(value_word(PriceSource::default().into())),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Value(super::account_id_word(pool.oracle_account_id)),
+            StorageSlot::Map(faucet_reserves),
+        ];
 
         AccountComponent::new(lending_pool_library(), storage_slots)
             .expect("lending pool component should be valid")
@@ -100,21 +499,27 @@ impl From<LendingPoolAccount> for AccountComponent {
 ///
 /// # Arguments
 /// * `init_seed` - Random seed for account ID generation
+/// * `oracle_account_id` - The price oracle account ID positions are valued with
+/// * `faucet_ids` - The faucets issuing USDC, DAI and WETH
 /// * `account_storage_mode` - Public or Private storage mode
 pub fn create_lending_pool_account_builder(
     init_seed: [u8; 32],
+    oracle_account_id: AccountId,
+    faucet_ids: [AccountId; 3],
     account_storage_mode: AccountStorageMode,
 ) -> Result<AccountBuilder, AccountError> {
     Ok(AccountBuilder::new(init_seed)
         .account_type(AccountType::RegularAccountUpdatableCode)
         .storage_mode(account_storage_mode)
-        .with_component(LendingPoolAccount::new()))
+        .with_component(LendingPoolAccount::new(oracle_account_id, faucet_ids)))
 }
 
 /// Creates a lending pool account with authentication
 ///
 /// # Arguments
 /// * `init_seed` - Random seed for account ID generation
+/// * `oracle_account_id` - The price oracle account ID
+/// * `faucet_ids` - The faucets issuing USDC, DAI and WETH
 /// * `account_storage_mode` - Public or Private storage mode
 /// * `auth_scheme` - Authentication scheme (e.g., RpoFalcon512)
 ///
@@ -122,6 +527,8 @@ pub fn create_lending_pool_account_builder(
 #[cfg(any(feature = "testing", test))]
 pub fn create_lending_pool_account(
     init_seed: [u8; 32],
+    oracle_account_id: AccountId,
+    faucet_ids: [AccountId; 3],
     account_storage_mode: AccountStorageMode,
     auth_scheme: miden_lib::AuthScheme,
 ) -> Result<(Account, Word), AccountError> {
@@ -129,15 +536,20 @@ pub fn create_lending_pool_account(
 
     let auth_component: AuthRpoFalcon512Acl = match auth_scheme {
         miden_lib::AuthScheme::RpoFalcon512 { pub_key } => {
+            let trigger_procedures =
+                super::signed_procedure_roots(&LendingPoolAccount::new(oracle_account_id, faucet_ids).into());
             Ok(AuthRpoFalcon512Acl::new(
                 pub_key,
-                AuthRpoFalcon512AclConfig::new(),
+                AuthRpoFalcon512AclConfig::new()
+                    .with_auth_trigger_procedures(trigger_procedures)
+                    .with_allow_unauthorized_input_notes(true)
+                    .with_allow_unauthorized_output_notes(true),
             )?)
         }
         _ => Err(AccountError::other("unsupported auth scheme")),
     }?;
 
-    let (account, account_seed) = create_lending_pool_account_builder(init_seed, account_storage_mode)?
+    let (account, account_seed) = create_lending_pool_account_builder(init_seed, oracle_account_id, faucet_ids, account_storage_mode)?
         .with_auth_component(auth_component)
         .build()?;
 
@@ -0,0 +1,74 @@
+use miden_objects::{
+    account::AccountId,
+    asset::FungibleAsset,
+    block::BlockNumber,
+    note::{
+        Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata, NoteRecipient, NoteScript,
+        NoteTag, NoteType,
+    },
+    utils::{sync::LazyLock, Deserializable},
+    vm::Program,
+    Felt, FieldElement, NoteError, Word,
+};
+
+use super::position_note::payout_inputs;
+
+/// Compiled liquidation note script
+static LIQUIDATION_NOTE_SCRIPT_BYTES: &[u8] =
+    include_bytes!(concat!(env!("OUT_DIR"), "/assets/note_scripts/liquidation_note.masb"));
+
+static LIQUIDATION_NOTE_SCRIPT: LazyLock<NoteScript> = LazyLock::new(|| {
+    let program = Program::read_from_bytes(LIQUIDATION_NOTE_SCRIPT_BYTES)
+        .expect("failed to deserialize liquidation note script");
+    NoteScript::new(program)
+});
+
+/// Returns the script of liquidation notes
+pub fn liquidation_note_script() -> NoteScript {
+    LIQUIDATION_NOTE_SCRIPT.clone()
+}
+
+/// Creates a note requesting the liquidation of a borrower's position in a lending pool
+///
+/// The note carries `debt`, which covers the borrower's debt in `debt_asset_id` when the pool
+/// consumes the note. The pool pays the seized collateral out to `liquidator` in a P2ID note with
+/// the [`payout_recipient`](super::position_note::payout_recipient), and fails unless the position
+/// is liquidatable. No other account than the pool can consume the note.
+///
+/// # Arguments
+/// * `liquidator` - Account receiving the collateral, which must sign the transaction creating the
+///   note
+/// * `pool_id` - Lending pool consuming the note
+/// * `serial_num` - Serial number of the note, which must be unique
+/// * `reclaim_block_height` - Block from which `liquidator` can consume the note back, taking back
+///   `debt`
+#[allow(clippy::too_many_arguments)]
+pub fn create_liquidation_note(
+    liquidator: AccountId,
+    pool_id: AccountId,
+    borrower: AccountId,
+    collateral_asset_id: u64,
+    debt_asset_id: u64,
+    debt: FungibleAsset,
+    serial_num: Word,
+    reclaim_block_height: BlockNumber,
+) -> Result<Note, NoteError> {
+    let mut inputs =
+        vec![Felt::new(debt.amount()), Felt::new(debt_asset_id), Felt::new(collateral_asset_id)];
+    inputs.extend(payout_inputs(liquidator, serial_num)?);
+    inputs.extend([borrower.prefix().as_felt(), borrower.suffix()]);
+    inputs.push(Felt::from(reclaim_block_height));
+    inputs.extend([pool_id.prefix().as_felt(), pool_id.suffix()]);
+
+    let recipient =
+        NoteRecipient::new(serial_num, liquidation_note_script(), NoteInputs::new(inputs)?);
+    let metadata = NoteMetadata::new(
+        liquidator,
+        NoteType::Public,
+        NoteTag::from_account_id(pool_id),
+        NoteExecutionHint::always(),
+        Felt::ZERO,
+    )?;
+
+    Ok(Note::new(NoteAssets::new(vec![debt.into()])?, metadata, recipient))
+}
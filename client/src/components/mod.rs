@@ -4,6 +4,92 @@
 pub mod lending_pool;
 pub mod user_lending;
 pub mod price_oracle;
+pub mod price_note;
+pub mod position_note;
+pub mod liquidation_note;
+
+use miden_objects::{
+    account::{AccountCode, AccountComponent, AccountId, StorageSlot},
+    Felt, FieldElement, Word,
+};
+
+/// Procedures of the lending contracts that require the account's signature
+///
+/// Calling any of these triggers the account's auth component, so transactions using them must
+/// be signed with the account's key: the pool's admin procedures and the health factor update of
+/// user accounts. The price oracle is its own auth component and checks its admin procedures
+/// itself.
+///
+/// Position procedures of the pool are only invoked by the notes it consumes, see
+/// [`UNSIGNED_NOTE_COMPONENTS`].
+pub const SIGNED_PROCEDURES: &[&str] = &[
+    "lending::lending_pool::add_reserve",
+    "lending::lending_pool::set_rate_strategy",
+    "lending::lending_pool::claim_reserves",
+    "lending::lending_pool::set_liquidation_price_source",
+    "lending::lending_pool::update_reserve_state",
+    "lending::user_lending::calculate_health_factor",
+];
+
+/// Returns the MAST roots of the procedures exported by a component that require a signature
+pub fn signed_procedure_roots(component: &AccountComponent) -> Vec<Word> {
+    SIGNED_PROCEDURES
+        .iter()
+        .filter_map(|name| component.get_procedure_root_by_name(*name))
+        .collect()
+}
+
+/// Components whose accounts consume notes, and send the notes created by them, without a signature
+///
+/// The pool's position procedures only run in the request notes it consumes, which are signed by
+/// the accounts they apply to, and the pool only sends payouts from them. Anyone can therefore have
+/// the pool consume request notes, and its key is only needed for its admin procedures. User
+/// accounts hold a wallet, so consuming notes stays signed with their key.
+pub const UNSIGNED_NOTE_COMPONENTS: &[&str] = &["lending::lending_pool"];
+
+/// Returns whether accounts with a component consume notes without a signature
+pub fn allows_unsigned_notes(component: &AccountComponent) -> bool {
+    component
+        .library()
+        .module_infos()
+        .any(|module| UNSIGNED_NOTE_COMPONENTS.contains(&module.path().to_string().as_str()))
+}
+
+/// Storage map entries the lending contracts read from an account with foreign procedure
+/// invocation, by slot of its lending component
+///
+/// The pool is read by user accounts valuing `reader`'s position, and the oracle by the pool
+/// pricing assets. Both are told apart by their code. Returns `None` for other accounts.
+pub fn foreign_map_keys(
+    code: &AccountCode,
+    slots: &[StorageSlot],
+    reader: AccountId,
+) -> Option<Vec<(u8, Vec<Word>)>> {
+    let exports = |library: miden_objects::assembly::Library, name: &str| {
+        library.get_procedure_root_by_name(name).is_some_and(|root| code.has_procedure(root))
+    };
+
+    if exports(lending_pool::lending_pool_library(), "lending::lending_pool::get_health_factor") {
+        Some(lending_pool::health_factor_map_keys(slots, reader))
+    } else if exports(price_oracle::price_oracle_library(), "lending::price_oracle::get_asset_price") {
+        Some(price_oracle::price_map_keys(slots))
+    } else {
+        None
+    }
+}
+
+/// Returns the assets of a list map, `[index, 0, 0, 0] -> [asset_id, 0, 0, 0]`
+fn listed_assets(slots: &[StorageSlot], list_slot: u8) -> Vec<u64> {
+    match slots.get(list_slot as usize) {
+        Some(StorageSlot::Map(list)) => list.entries().map(|(_, value)| value[0].as_int()).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Account IDs referenced by the account code are stored as [prefix, suffix, 0, 0]
+pub(crate) fn account_id_word(account_id: AccountId) -> Word {
+    Word::new([account_id.prefix().as_felt(), account_id.suffix(), Felt::ZERO, Felt::ZERO])
+}
 
 #[cfg(any(feature = "testing", test))]
 pub mod testing {
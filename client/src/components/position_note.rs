@@ -0,0 +1,128 @@
+use miden_lib::note::utils::build_p2id_recipient;
+use miden_objects::{
+    account::AccountId,
+    asset::FungibleAsset,
+    block::BlockNumber,
+    note::{
+        Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata, NoteRecipient, NoteScript,
+        NoteTag, NoteType,
+    },
+    utils::{sync::LazyLock, Deserializable},
+    vm::Program,
+    Felt, FieldElement, NoteError, Word,
+};
+
+/// Compiled position note script
+static POSITION_NOTE_SCRIPT_BYTES: &[u8] =
+    include_bytes!(concat!(env!("OUT_DIR"), "/assets/note_scripts/position_note.masb"));
+
+static POSITION_NOTE_SCRIPT: LazyLock<NoteScript> = LazyLock::new(|| {
+    let program = Program::read_from_bytes(POSITION_NOTE_SCRIPT_BYTES)
+        .expect("failed to deserialize position note script");
+    NoteScript::new(program)
+});
+
+/// Returns the script of position notes
+pub fn position_note_script() -> NoteScript {
+    POSITION_NOTE_SCRIPT.clone()
+}
+
+/// Operation a position note requests from the lending pool
+///
+/// Discriminants are the operation codes the note script dispatches on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PositionOperation {
+    Deposit = 0,
+    Withdraw = 1,
+    SupplyCollateral = 2,
+    WithdrawCollateral = 3,
+    Borrow = 4,
+    Repay = 5,
+}
+
+impl PositionOperation {
+    /// Lending pool procedure applying the operation
+    pub fn procedure(&self) -> &'static str {
+        match self {
+            Self::Deposit => "deposit",
+            Self::Withdraw => "withdraw",
+            Self::SupplyCollateral => "supply_collateral",
+            Self::WithdrawCollateral => "withdraw_collateral",
+            Self::Borrow => "borrow",
+            Self::Repay => "repay",
+        }
+    }
+
+    /// Whether the pool pays the amount out to the sender, instead of receiving it with the note
+    pub fn pays_out(&self) -> bool {
+        matches!(self, Self::Withdraw | Self::WithdrawCollateral | Self::Borrow)
+    }
+}
+
+impl From<PositionOperation> for Felt {
+    fn from(operation: PositionOperation) -> Self {
+        Felt::new(operation as u64)
+    }
+}
+
+/// Returns the recipient of the P2ID note a request pays assets out to its sender in
+///
+/// The payout note shares the serial number of the request note.
+pub fn payout_recipient(sender: AccountId, serial_num: Word) -> Result<NoteRecipient, NoteError> {
+    build_p2id_recipient(sender, serial_num)
+}
+
+/// Returns the note inputs `[payout_tag, PAYOUT_RECIPIENT]` of a request paying out to its sender
+pub(crate) fn payout_inputs(sender: AccountId, serial_num: Word) -> Result<Vec<Felt>, NoteError> {
+    let recipient = payout_recipient(sender, serial_num)?.digest();
+
+    let mut inputs = vec![Felt::from(NoteTag::from_account_id(sender).as_u32())];
+    inputs.extend(recipient.iter());
+    Ok(inputs)
+}
+
+/// Creates a note requesting an operation on the sender's position in a lending pool
+///
+/// The note is public and tagged for the pool, which applies the operation to the position of
+/// `sender` when it consumes the note. No other account than the pool can consume it. Deposits,
+/// collateral and repayments carry `asset`, while the pool pays withdrawals, collateral
+/// withdrawals and borrows out to `sender` in a P2ID note with the [`payout_recipient`].
+///
+/// # Arguments
+/// * `sender` - Account holding the position, which must sign the transaction creating the note
+/// * `pool_id` - Lending pool consuming the note
+/// * `asset_id` - Reserve of the operation, whose faucet issues `asset`
+/// * `serial_num` - Serial number of the note, which must be unique
+/// * `reclaim_block_height` - Block from which `sender` can consume the note back, taking back
+///   the asset it carries
+pub fn create_position_note(
+    sender: AccountId,
+    pool_id: AccountId,
+    operation: PositionOperation,
+    asset_id: u64,
+    asset: FungibleAsset,
+    serial_num: Word,
+    reclaim_block_height: BlockNumber,
+) -> Result<Note, NoteError> {
+    let mut inputs = vec![operation.into(), Felt::new(asset_id), Felt::new(asset.amount())];
+    inputs.extend(payout_inputs(sender, serial_num)?);
+    inputs.push(Felt::from(reclaim_block_height));
+    inputs.extend([pool_id.prefix().as_felt(), pool_id.suffix()]);
+
+    let assets = if operation.pays_out() {
+        NoteAssets::default()
+    } else {
+        NoteAssets::new(vec![asset.into()])?
+    };
+
+    let recipient = NoteRecipient::new(serial_num, position_note_script(), NoteInputs::new(inputs)?);
+    let metadata = NoteMetadata::new(
+        sender,
+        NoteType::Public,
+        NoteTag::from_account_id(pool_id),
+        NoteExecutionHint::always(),
+        Felt::ZERO,
+    )?;
+
+    Ok(Note::new(assets, metadata, recipient))
+}
@@ -0,0 +1,176 @@
+use miden_objects::{
+    account::AccountId,
+    note::{
+        Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata, NoteRecipient, NoteScript,
+        NoteTag, NoteType,
+    },
+    utils::{
+        sync::LazyLock, ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable,
+    },
+    vm::{AdviceMap, Program},
+    Felt, FieldElement, Hasher, NoteError, Word,
+};
+
+/// Compiled price note script
+static PRICE_NOTE_SCRIPT_BYTES: &[u8] =
+    include_bytes!(concat!(env!("OUT_DIR"), "/assets/note_scripts/price_note.masb"));
+
+static PRICE_NOTE_SCRIPT: LazyLock<NoteScript> = LazyLock::new(|| {
+    let program = Program::read_from_bytes(PRICE_NOTE_SCRIPT_BYTES)
+        .expect("failed to deserialize price note script");
+    NoteScript::new(program)
+});
+
+/// Returns the script of price notes
+pub fn price_note_script() -> NoteScript {
+    PRICE_NOTE_SCRIPT.clone()
+}
+
+/// Returns the message an updater signs to submit a price to an oracle
+///
+/// The message binds the price to the oracle, so the signature cannot be replayed on another one.
+pub fn price_message(oracle_id: AccountId, asset_id: u64, price: u64, timestamp: u64) -> Word {
+    Hasher::merge(&[
+        Word::new([Felt::new(asset_id), Felt::new(price), Felt::new(timestamp), Felt::ZERO]),
+        Word::new([oracle_id.suffix(), oracle_id.prefix().as_felt(), Felt::ZERO, Felt::ZERO]),
+    ])
+}
+
+/// Builder of price notes
+///
+/// An updater signs the [`message`](Self::message) of a price off-chain with its account key, and
+/// the note is built with the resulting signature. Any account can then relay the note to the
+/// oracle, which submits the price once it has verified the signature.
+#[derive(Debug, Clone)]
+pub struct PriceNoteBuilder {
+    oracle_id: AccountId,
+    asset_id: u64,
+    price: u64,
+    timestamp: u64,
+}
+
+impl PriceNoteBuilder {
+    /// Create a builder for the price of an asset at a timestamp, in seconds
+    pub fn new(oracle_id: AccountId, asset_id: u64, price: u64, timestamp: u64) -> Self {
+        Self {
+            oracle_id,
+            asset_id,
+            price,
+            timestamp,
+        }
+    }
+
+    /// Message the updater signs
+    pub fn message(&self) -> Word {
+        price_message(self.oracle_id, self.asset_id, self.price, self.timestamp)
+    }
+
+    /// Builds the note with the signature of the updater over [`message`](Self::message)
+    ///
+    /// # Arguments
+    /// * `sender` - Account publishing the note, usually the updater's
+    /// * `updater_key` - Public key of the updater registered in the oracle
+    /// * `signature` - Signature in the form `rpo_falcon512::verify` reads from the advice stack
+    ///
+    /// The message is used as serial number, so each signed price makes a single note, which
+    /// can only be consumed once.
+    pub fn build(
+        self,
+        sender: AccountId,
+        updater_key: Word,
+        signature: Vec<Felt>,
+    ) -> Result<PriceNote, NoteError> {
+        let mut inputs = updater_key.as_elements().to_vec();
+        inputs.extend([Felt::new(self.price), Felt::new(self.asset_id), Felt::new(self.timestamp)]);
+
+        let recipient =
+            NoteRecipient::new(self.message(), price_note_script(), NoteInputs::new(inputs)?);
+        let metadata = NoteMetadata::new(
+            sender,
+            NoteType::Public,
+            NoteTag::from_account_id(self.oracle_id),
+            NoteExecutionHint::always(),
+            Felt::ZERO,
+        )?;
+
+        Ok(PriceNote {
+            oracle_id: self.oracle_id,
+            note: Note::new(NoteAssets::default(), metadata, recipient),
+            signature,
+        })
+    }
+}
+
+/// Note submitting a price signed by an oracle updater
+///
+/// The signature does not fit in the note inputs, so it is kept alongside the note and provided
+/// to the oracle through the advice map of the transaction consuming the note.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PriceNote {
+    oracle_id: AccountId,
+    note: Note,
+    signature: Vec<Felt>,
+}
+
+impl PriceNote {
+    /// Oracle the price is submitted to
+    pub fn oracle_id(&self) -> AccountId {
+        self.oracle_id
+    }
+
+    /// The note consumed by the oracle
+    pub fn note(&self) -> &Note {
+        &self.note
+    }
+
+    /// Public key of the updater that signed the price
+    pub fn updater_key(&self) -> Word {
+        let inputs = self.note.inputs().values();
+        Word::new([inputs[0], inputs[1], inputs[2], inputs[3]])
+    }
+
+    /// Signed price (8 decimals)
+    pub fn price(&self) -> u64 {
+        self.note.inputs().values()[4].as_int()
+    }
+
+    /// Asset the price is for
+    pub fn asset_id(&self) -> u64 {
+        self.note.inputs().values()[5].as_int()
+    }
+
+    /// Time the updater signed the price at, in seconds
+    pub fn timestamp(&self) -> u64 {
+        self.note.inputs().values()[6].as_int()
+    }
+
+    /// Advice map the consuming transaction needs, with the signature at hash(MSG, UPDATER_KEY)
+    ///
+    /// Values of the advice map are pushed onto the advice stack last element first, so the
+    /// signature is stored in reverse.
+    pub fn advice_map(&self) -> AdviceMap {
+        let message = price_message(self.oracle_id, self.asset_id(), self.price(), self.timestamp());
+        let signature_key = Hasher::merge(&[message, self.updater_key()]);
+        let signature: Vec<Felt> = self.signature.iter().rev().copied().collect();
+
+        AdviceMap::from_iter([(signature_key, signature)])
+    }
+}
+
+impl Serializable for PriceNote {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        self.oracle_id.write_into(target);
+        self.note.write_into(target);
+        self.signature.write_into(target);
+    }
+}
+
+impl Deserializable for PriceNote {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        Ok(Self {
+            oracle_id: AccountId::read_from(source)?,
+            note: Note::read_from(source)?,
+            signature: Vec::<Felt>::read_from(source)?,
+        })
+    }
+}
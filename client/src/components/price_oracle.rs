@@ -1,11 +1,16 @@
 use miden_objects::{
     account::{
-        Account, AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageSlot,
+        AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageMap,
+        StorageSlot,
     },
+    crypto::dsa::rpo_falcon512::PublicKey,
     utils::sync::LazyLock,
     AccountError, Felt, FieldElement, Word,
 };
 
+#[cfg(any(feature = "testing", test))]
+use miden_objects::account::Account;
+
 /// Compiled MASM library for price oracle
 static PRICE_ORACLE_LIBRARY_BYTES: &[u8] =
     include_bytes!(concat!(env!("OUT_DIR"), "/assets/contracts/price_oracle.masl"));
@@ -16,55 +21,142 @@ static PRICE_ORACLE_LIBRARY: LazyLock<miden_objects::assembly::Library> =
             .expect("failed to deserialize price oracle library")
     });
 
-fn price_oracle_library() -> miden_objects::assembly::Library {
+/// Returns the compiled contract library
+pub fn price_oracle_library() -> miden_objects::assembly::Library {
     PRICE_ORACLE_LIBRARY.clone()
 }
 
+/// Storage maps of the oracle component read by the procedures the pool invokes
+const FEEDS_SLOT: u8 = 1;
+const FEED_LIST_SLOT: u8 = 3;
+const OBSERVATIONS_SLOT: u8 = 9;
+
+/// Price feed fields read by `get_asset_price`, `get_asset_twap` and `has_price_feed`
+const FEED_READ_FIELDS: [u64; 7] = [
+    0, // listed
+    1, // price
+    2, // timestamp
+    3, // maximum age
+    5, // observation count
+    6, // TWAP window
+    9, // paused
+];
+
+/// Storage map entries the pool reads to price assets, by slot of the oracle component
+///
+/// These are the fields `get_asset_price`, `get_asset_twap` and `has_price_feed` read from every
+/// price feed, whether they are set or not, and the feed's observations. Updater keys and
+/// submissions are not read.
+pub fn price_map_keys(slots: &[StorageSlot]) -> Vec<(u8, Vec<Word>)> {
+    let asset_ids = super::listed_assets(slots, FEED_LIST_SLOT);
+    let key = |asset_id: u64, index: u64| {
+        Word::new([Felt::new(asset_id), Felt::new(index), Felt::ZERO, Felt::ZERO])
+    };
+
+    let feed_keys = asset_ids
+        .iter()
+        .flat_map(|asset_id| FEED_READ_FIELDS.map(|field| key(*asset_id, field)))
+        .collect();
+    let observation_keys = asset_ids
+        .iter()
+        .flat_map(|asset_id| {
+            (0..PriceOracleAccount::OBSERVATION_BUFFER_SIZE).map(move |position| key(*asset_id, position))
+        })
+        .collect();
+
+    vec![(FEEDS_SLOT, feed_keys), (OBSERVATIONS_SLOT, observation_keys)]
+}
+
 /// PriceOracle Account Component
 ///
-/// Maintains price feeds for supported assets.
-/// Storage slots contain price data for USDC, DAI, and WETH.
+/// Maintains price feeds for any number of assets.
+/// Feed data (price, settings and circuit breaker state) is stored in a single storage map keyed
+/// by asset ID and field, so feeds for further assets can be added after deployment with the
+/// admin `add_price_feed` procedure.
+///
+/// The component is its own auth component: prices can only be submitted in transactions signed
+/// by an authorized updater, and the updater set is managed with the admin key. The reported price
+/// of an asset is the median of the fresh submissions, once they reach the asset's quorum. A feed's
+/// initial price is the admin's submission, until the first updater submits a price for the asset.
+///
+/// The latest reported prices of each asset are kept as observations, averaged over time by
+/// `get_asset_twap`. An asset's circuit breaker pauses its feed when the median moves too far
+/// within an interval, until the admin calls `resume_price_feed`.
+///
+/// Storage slot 0: admin public key, signing the admin procedures
+/// Storage slot 1: feed map, `[asset_id, field, 0, 0] -> [value, 0, 0, 0]`
+/// Storage slot 2: number of price feeds
+/// Storage slot 3: assets with a price feed in the order they were added,
+/// `[index, 0, 0, 0] -> [asset_id, 0, 0, 0]`
+/// Storage slot 4: map of the authorized updater keys
+/// Storage slot 5: key that must sign the current transaction, empty between transactions
+/// Storage slot 6: number of registered updaters
+/// Storage slot 7: map of the updater keys by index
+/// Storage slot 8: map of the latest submission of each updater for each asset, and of the admin's
+/// initial submission at index [`ADMIN_SUBMISSION`](Self::ADMIN_SUBMISSION)
+/// Storage slot 9: map of the latest observations of each asset
 pub struct PriceOracleAccount {
-    /// Asset prices stored as Word
-    /// Storage slot 0: USDC price (8 decimals)
-    /// Storage slot 1: DAI price (8 decimals)
-    /// Storage slot 2: WETH price (8 decimals)
-    prices: Vec<Word>,
-
-    /// Last update timestamps
-    /// Storage slot 3: last update timestamp
-    last_update: Word,
+    /// Admin public key
+    admin_key: Word,
+
+    /// Price feeds added when the oracle is deployed, as (asset ID, initial price)
+    feeds: Vec<(u64, u64)>,
 }
 
 impl PriceOracleAccount {
-    /// Create a new price oracle with default prices
-    /// USDC: $1.00, DAI: $1.00, WETH: $2500.00 (all with 8 decimals)
+    /// Default maximum price age of new feeds (1 hour)
+    pub const DEFAULT_MAX_PRICE_AGE: u64 = 3600;
+
+    /// Default number of fresh submissions needed to report a price
+    pub const DEFAULT_QUORUM: u64 = 1;
+
+    /// Maximum number of registered updaters
+    pub const MAX_UPDATERS: u64 = 16;
+
+    /// Index of the admin's initial submission, after the last updater index
+    pub const ADMIN_SUBMISSION: u64 = Self::MAX_UPDATERS;
+
+    /// Number of observations kept per asset
+    pub const OBSERVATION_BUFFER_SIZE: u64 = 8;
+
+    /// Default TWAP window of new feeds (30 minutes)
+    pub const DEFAULT_TWAP_WINDOW: u64 = 1800;
+
+    /// Default circuit breaker of new feeds: disabled, measuring deviations over an hour once
+    /// enabled
+    pub const DEFAULT_CIRCUIT_BREAKER: (u64, u64) = (0, 3600);
+
+    /// Create a new price oracle with feeds for USDC, DAI, WETH and WBTC
+    /// USDC: $1.00, DAI: $1.00, WETH: $2500.00, WBTC: $45000.00 (all with 8 decimals)
     pub fn new() -> Self {
-        let default_prices = vec![
-            Word::new([Felt::new(100000000), Felt::ZERO, Felt::ZERO, Felt::ZERO]), // USDC: $1.00
-            Word::new([Felt::new(100000000), Felt::ZERO, Felt::ZERO, Felt::ZERO]), // DAI: $1.00
-            Word::new([Felt::new(250000000000), Felt::ZERO, Felt::ZERO, Felt::ZERO]), // WETH: $2500.00
-        ];
+        Self::with_feeds(vec![
+            (1, 100000000),     // USDC: $1.00
+            (2, 100000000),     // DAI: $1.00
+            (3, 250000000000),  // WETH: $2500.00
+            (4, 4500000000000), // WBTC: $45000.00
+        ])
+    }
 
-        Self {
-            prices: default_prices,
-            last_update: Word::default(),
-        }
+    /// Create a price oracle with feeds for the given assets, as (asset ID, initial price)
+    pub fn with_feeds(feeds: Vec<(u64, u64)>) -> Self {
+        Self { admin_key: Word::default(), feeds }
     }
 
-    /// Create with custom prices
-    pub fn with_prices(prices: Vec<Word>) -> Self {
-        Self {
-            prices,
-            last_update: Word::default(),
-        }
+    /// Set the key managing the updater set and the price feeds
+    ///
+    /// Accounts created with [`build_account`](crate::miden_client::build_account) get the
+    /// account's key as admin key.
+    pub fn with_admin_key(mut self, admin_key: PublicKey) -> Self {
+        self.admin_key = admin_key.into();
+        self
     }
 
-    /// Set a specific asset price
-    pub fn set_price(&mut self, asset_index: usize, price: u64) {
-        if asset_index < self.prices.len() {
-            self.prices[asset_index] = Word::new([Felt::new(price), Felt::ZERO, Felt::ZERO, Felt::ZERO]);
-        }
+    /// Price feeds added when the oracle is deployed, as (asset ID, initial price)
+    ///
+    /// Feeds are added by the deployment transaction, which submits their initial price as the
+    /// admin at the time of its block.
+    pub fn feeds(&self) -> &[(u64, u64)] {
+        &self.feeds
     }
 }
 
@@ -76,15 +168,21 @@ impl Default for PriceOracleAccount {
 
 impl From<PriceOracleAccount> for AccountComponent {
     fn from(oracle: PriceOracleAccount) -> Self {
-        let mut storage_slots = Vec::new();
-
-        // Add price slots
-        for price_word in oracle.prices {
-            storage_slots.push(StorageSlot::Value(price_word));
-        }
-
-        // Add last update timestamp
-        storage_slots.push(StorageSlot::Value(oracle.last_update));
+        let storage_slots = vec![
+            StorageSlot::Value(oracle.admin_key),
+            // No price feeds until the deployment transaction adds them
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Map(StorageMap::new()),
+            // No updaters are authorized initially, and no transaction is being signed
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Map(StorageMap::new()),
+            // No submissions or observations yet
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Map(StorageMap::new()),
+        ];
 
         AccountComponent::new(price_oracle_library(), storage_slots)
             .expect("price oracle component should be valid")
@@ -94,17 +192,21 @@ impl From<PriceOracleAccount> for AccountComponent {
 
 /// Creates a price oracle account builder
 ///
+/// The oracle authenticates its own transactions, so it is added as the account's auth component.
+///
 /// # Arguments
 /// * `init_seed` - Random seed for account ID generation
 /// * `account_storage_mode` - Public or Private storage mode
+/// * `admin_key` - Key managing the updater set
 pub fn create_price_oracle_account_builder(
     init_seed: [u8; 32],
     account_storage_mode: AccountStorageMode,
+    admin_key: PublicKey,
 ) -> Result<AccountBuilder, AccountError> {
     Ok(AccountBuilder::new(init_seed)
         .account_type(AccountType::RegularAccountUpdatableCode)
         .storage_mode(account_storage_mode)
-        .with_component(PriceOracleAccount::new()))
+        .with_auth_component(PriceOracleAccount::new().with_admin_key(admin_key)))
 }
 
 /// Creates a price oracle account with authentication
@@ -121,21 +223,13 @@ pub fn create_price_oracle_account(
     account_storage_mode: AccountStorageMode,
     auth_scheme: miden_lib::AuthScheme,
 ) -> Result<(Account, Word), AccountError> {
-    use miden_lib::account::auth::{AuthRpoFalcon512Acl, AuthRpoFalcon512AclConfig};
-
-    let auth_component: AuthRpoFalcon512Acl = match auth_scheme {
-        miden_lib::AuthScheme::RpoFalcon512 { pub_key } => {
-            Ok(AuthRpoFalcon512Acl::new(
-                pub_key,
-                AuthRpoFalcon512AclConfig::new(),
-            )?)
-        }
+    let admin_key = match auth_scheme {
+        miden_lib::AuthScheme::RpoFalcon512 { pub_key } => Ok(pub_key),
         _ => Err(AccountError::other("unsupported auth scheme")),
     }?;
 
-    let (account, account_seed) = create_price_oracle_account_builder(init_seed, account_storage_mode)?
-        .with_auth_component(auth_component)
-        .build()?;
+    let (account, account_seed) =
+        create_price_oracle_account_builder(init_seed, account_storage_mode, admin_key)?.build()?;
 
     Ok((account, account_seed))
 }
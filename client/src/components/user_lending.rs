@@ -1,11 +1,18 @@
+use miden_lib::account::wallets::BasicWallet;
 use miden_objects::{
     account::{
-        Account, AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageSlot,
+        AccountBuilder, AccountComponent, AccountId, AccountStorageMode, AccountType,
+        StorageSlot,
     },
     utils::sync::LazyLock,
-    AccountError, Felt, FieldElement, Word,
+    AccountError, Word,
 };
 
+use super::account_id_word;
+
+#[cfg(any(feature = "testing", test))]
+use miden_objects::account::Account;
+
 /// Compiled MASM library for user lending account
 static USER_LENDING_LIBRARY_BYTES: &[u8] =
     include_bytes!(concat!(env!("OUT_DIR"), "/assets/contracts/user_lending.masl"));
@@ -16,58 +23,35 @@ static USER_LENDING_LIBRARY: LazyLock<miden_objects::assembly::Library> =
             .expect("failed to deserialize user lending library")
     });
 
-fn user_lending_library() -> miden_objects::assembly::Library {
+/// Returns the compiled contract library
+pub fn user_lending_library() -> miden_objects::assembly::Library {
     USER_LENDING_LIBRARY.clone()
 }
 
 /// UserLending Account Component
 ///
-/// Tracks individual user positions including collateral and debt.
-/// Storage slots contain user-specific data for all supported assets.
+/// References the lending pool holding the user's position. The pool keeps the position's
+/// supplied balances, collateral and debt, so the account only caches its health factor.
 pub struct UserLendingAccount {
-    /// User collateral amounts for each asset
-    /// Storage slots 0-2: collateral (USDC, DAI, WETH)
-    collateral: Word,
-
-    /// User debt amounts for each asset
-    /// Storage slots 3-5: debt (USDC, DAI, WETH)
-    debt: Word,
-
-    /// User's lending pool account ID reference
-    /// Storage slot 6: pool account ID
-    pool_account_id: Word,
+    /// User's lending pool account ID reference, read for the position's health factor
+    /// Storage slot 1: pool account ID (slot 0 holds the health factor)
+    pool_account_id: AccountId,
 }
 
 impl UserLendingAccount {
-    /// Create a new user lending account with zero balances
-    pub fn new(pool_account_id: Word) -> Self {
-        Self {
-            collateral: Word::default(),
-            debt: Word::default(),
-            pool_account_id,
-        }
-    }
-
-    /// Create with initial collateral and debt
-    pub fn with_positions(
-        collateral: Word,
-        debt: Word,
-        pool_account_id: Word,
-    ) -> Self {
-        Self {
-            collateral,
-            debt,
-            pool_account_id,
-        }
+    /// Create a new user lending account
+    pub fn new(pool_account_id: AccountId) -> Self {
+        Self { pool_account_id }
     }
 }
 
 impl From<UserLendingAccount> for AccountComponent {
     fn from(user: UserLendingAccount) -> Self {
         let storage_slots = vec![
-            StorageSlot::Value(user.collateral),
-            StorageSlot::Value(user.debt),
-            StorageSlot::Value(user.pool_account_id),
+            // Health factor (0), written by the account code
+            StorageSlot::Value(Word::default()),
+            // Pool account reference (1)
+            StorageSlot::Value(account_id_word(user.pool_account_id)),
         ];
 
         AccountComponent::new(user_lending_library(), storage_slots)
@@ -78,18 +62,22 @@ impl From<UserLendingAccount> for AccountComponent {
 
 /// Creates a user lending account builder
 ///
+/// The account also exposes the basic wallet, which receives assets paid out by the pool and
+/// moves assets into the request notes sent to it.
+///
 /// # Arguments
 /// * `init_seed` - Random seed for account ID generation
 /// * `pool_account_id` - The lending pool account ID this user will interact with
 /// * `account_storage_mode` - Public or Private storage mode
 pub fn create_user_lending_account_builder(
     init_seed: [u8; 32],
-    pool_account_id: Word,
+    pool_account_id: AccountId,
     account_storage_mode: AccountStorageMode,
 ) -> Result<AccountBuilder, AccountError> {
     Ok(AccountBuilder::new(init_seed)
         .account_type(AccountType::RegularAccountUpdatableCode)
         .storage_mode(account_storage_mode)
+        .with_component(BasicWallet)
         .with_component(UserLendingAccount::new(pool_account_id)))
 }
 
@@ -105,7 +93,7 @@ pub fn create_user_lending_account_builder(
 #[cfg(any(feature = "testing", test))]
 pub fn create_user_lending_account(
     init_seed: [u8; 32],
-    pool_account_id: Word,
+    pool_account_id: AccountId,
     account_storage_mode: AccountStorageMode,
     auth_scheme: miden_lib::AuthScheme,
 ) -> Result<(Account, Word), AccountError> {
@@ -113,9 +101,11 @@ pub fn create_user_lending_account(
 
     let auth_component: AuthRpoFalcon512Acl = match auth_scheme {
         miden_lib::AuthScheme::RpoFalcon512 { pub_key } => {
+            let trigger_procedures =
+                super::signed_procedure_roots(&UserLendingAccount::new(pool_account_id).into());
             Ok(AuthRpoFalcon512Acl::new(
                 pub_key,
-                AuthRpoFalcon512AclConfig::new(),
+                AuthRpoFalcon512AclConfig::new().with_auth_trigger_procedures(trigger_procedures),
             )?)
         }
         _ => Err(AccountError::other("unsupported auth scheme")),
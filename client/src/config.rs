@@ -1,59 +1,474 @@
 // Configuration module for the Miden lending protocol client
 
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
+/// Profile used when none has been selected
+pub const DEFAULT_PROFILE: &str = "local";
+
+/// Deployment manifest file of the default profile, written to the config directory
+pub const DEPLOYMENT_INFO_FILE: &str = "deployment_info.json";
+
+/// File holding all profiles, in the config directory
+pub const CONFIG_FILE: &str = "config.json";
+
+/// Directory of the config file, `~/.miden-lending`
+///
+/// Relative storage paths and the deployment manifests are resolved against it, so commands find
+/// them from any working directory.
+pub fn default_config_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".miden-lending")
+}
+
+/// Settings of a single network profile
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
+    /// Name of the profile, taken from its key in the config file
+    #[serde(skip)]
+    pub profile: String,
     pub rpc_endpoint: String,
     pub lending_pool_account_id: Option<String>,
     pub price_oracle_account_id: Option<String>,
     pub user_account_id: Option<String>,
+    /// Local store directory, relative to the config directory unless absolute
     pub storage_path: PathBuf,
+    /// User accounts created by this client, scanned when listing positions
+    #[serde(default)]
+    pub known_user_accounts: Vec<String>,
+    /// Account receiving the reserves claimed from the pool
+    #[serde(default)]
+    pub treasury_account_id: Option<String>,
+    /// Assets supported on this network, keyed by symbol
+    #[serde(default = "default_assets")]
+    pub assets: BTreeMap<String, DeployedAsset>,
+    /// Directory of the config file this profile was loaded from
+    #[serde(skip, default = "default_config_dir")]
+    pub config_dir: PathBuf,
 }
 
 impl Default for Config {
     fn default() -> Self {
+        Self::new(DEFAULT_PROFILE, "http://localhost:57291", PathBuf::from("store"))
+    }
+}
+
+impl Config {
+    /// Create a profile without any deployed accounts
+    pub fn new(profile: &str, rpc_endpoint: &str, storage_path: PathBuf) -> Self {
         Self {
-            rpc_endpoint: "http://localhost:57291".to_string(),
+            profile: profile.to_string(),
+            rpc_endpoint: rpc_endpoint.to_string(),
             lending_pool_account_id: None,
             price_oracle_account_id: None,
             user_account_id: None,
-            storage_path: PathBuf::from(".miden-lending"),
+            storage_path,
+            known_user_accounts: Vec::new(),
+            treasury_account_id: None,
+            assets: default_assets(),
+            config_dir: default_config_dir(),
+        }
+    }
+
+    /// Store directory of a profile without an explicit storage path
+    pub fn default_storage_path(profile: &str) -> PathBuf {
+        if profile == DEFAULT_PROFILE {
+            PathBuf::from("store")
+        } else {
+            PathBuf::from("store").join(profile)
+        }
+    }
+
+    /// Load the active profile
+    pub fn load() -> anyhow::Result<Self> {
+        Self::load_profile(None)
+    }
+
+    /// Load a profile by name, or the active profile if no name is given
+    pub fn load_profile(profile: Option<&str>) -> anyhow::Result<Self> {
+        Self::load_profile_from(&default_config_dir(), profile)
+    }
+
+    /// Load a profile from the config file in `config_dir`, or its active profile if no name is
+    /// given
+    pub fn load_profile_from(config_dir: &Path, profile: Option<&str>) -> anyhow::Result<Self> {
+        let profiles = Profiles::load_from(config_dir)?;
+        let name = profile.unwrap_or(&profiles.active_profile);
+
+        profiles.get(name)
+    }
+
+    /// Save this profile, leaving the other profiles untouched
+    pub fn save(&self) -> anyhow::Result<()> {
+        let mut profiles = Profiles::load_from(&self.config_dir)?;
+        profiles.profiles.insert(self.profile.clone(), self.clone());
+        profiles.save()
+    }
+
+    /// Local store directory, resolved against the config directory
+    pub fn storage_dir(&self) -> PathBuf {
+        self.config_dir.join(&self.storage_path)
+    }
+
+    /// User accounts whose positions are listed: the accounts created by this client and the
+    /// active user account, without duplicates
+    pub fn scanned_user_accounts(&self) -> Vec<String> {
+        let mut user_ids = self.known_user_accounts.clone();
+        if let Some(user_id) = &self.user_account_id {
+            if !user_ids.contains(user_id) {
+                user_ids.push(user_id.clone());
+            }
         }
+
+        user_ids
+    }
+
+    /// Name of the network recorded in the deployment manifest
+    pub fn network_name(&self) -> String {
+        self.profile.clone()
+    }
+
+    /// Path of the deployment manifest of this profile, in the config directory
+    pub fn deployment_info_path(&self) -> PathBuf {
+        if self.profile == DEFAULT_PROFILE {
+            self.config_dir.join(DEPLOYMENT_INFO_FILE)
+        } else {
+            self.config_dir.join(format!("deployment_info.{}.json", self.profile))
+        }
+    }
+
+    /// Returns the configured lending pool ID, falling back to the deployment manifest
+    pub fn lending_pool_id(&self) -> Option<String> {
+        self.lending_pool_account_id.clone().or_else(|| {
+            DeploymentInfo::load(&self.deployment_info_path())
+                .ok()
+                .flatten()
+                .map(|info| info.lending_pool_id)
+        })
+    }
+
+    /// Returns the configured price oracle ID, falling back to the deployment manifest
+    pub fn price_oracle_id(&self) -> Option<String> {
+        self.price_oracle_account_id.clone().or_else(|| {
+            DeploymentInfo::load(&self.deployment_info_path())
+                .ok()
+                .flatten()
+                .map(|info| info.price_oracle_id)
+        })
     }
 }
 
-impl Config {
+/// All network profiles, stored in `~/.miden-lending/config.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Profiles {
+    pub active_profile: String,
+    pub profiles: BTreeMap<String, Config>,
+    /// Directory of the config file
+    #[serde(skip, default = "default_config_dir")]
+    pub config_dir: PathBuf,
+}
+
+impl Default for Profiles {
+    fn default() -> Self {
+        Self {
+            active_profile: DEFAULT_PROFILE.to_string(),
+            profiles: BTreeMap::from([(DEFAULT_PROFILE.to_string(), Config::default())]),
+            config_dir: default_config_dir(),
+        }
+    }
+}
+
+impl Profiles {
+    /// Load the profiles from `~/.miden-lending/config.json`
     pub fn load() -> anyhow::Result<Self> {
-        let config_path = Self::get_config_path();
+        Self::load_from(&default_config_dir())
+    }
+
+    /// Load the profiles from the config file in `config_dir`
+    ///
+    /// A missing config file holds only the default profile.
+    pub fn load_from(config_dir: &Path) -> anyhow::Result<Self> {
+        let config_path = config_dir.join(CONFIG_FILE);
 
-        if config_path.exists() {
-            let content = std::fs::read_to_string(&config_path)?;
-            let config: Config = serde_json::from_str(&content)?;
-            Ok(config)
+        if !config_path.exists() {
+            let mut profiles = Self::default();
+            profiles.set_config_dir(config_dir);
+            return Ok(profiles);
+        }
+
+        let content = std::fs::read_to_string(&config_path)?;
+        let value: serde_json::Value = serde_json::from_str(&content)?;
+
+        // Config files written before profiles existed hold a single profile
+        let mut profiles = if value.get("profiles").is_some() {
+            serde_json::from_value::<Profiles>(value)?
         } else {
-            Ok(Self::default())
+            let config: Config = serde_json::from_value(value)?;
+            Self {
+                active_profile: DEFAULT_PROFILE.to_string(),
+                profiles: BTreeMap::from([(DEFAULT_PROFILE.to_string(), config)]),
+                config_dir: config_dir.to_path_buf(),
+            }
+        };
+
+        for (name, config) in profiles.profiles.iter_mut() {
+            config.profile = name.clone();
         }
+        profiles.set_config_dir(config_dir);
+
+        Ok(profiles)
     }
 
+    /// Save the profiles to the config file they were loaded from
     pub fn save(&self) -> anyhow::Result<()> {
-        let config_path = Self::get_config_path();
+        std::fs::create_dir_all(&self.config_dir)?;
+
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(self.config_dir.join(CONFIG_FILE), content)?;
+        Ok(())
+    }
+
+    /// Get a profile by name
+    pub fn get(&self, name: &str) -> anyhow::Result<Config> {
+        self.profiles.get(name).cloned().ok_or_else(|| {
+            anyhow::anyhow!("Unknown profile `{}`, add it with `profile add`", name)
+        })
+    }
 
-        // Ensure parent directory exists
-        if let Some(parent) = config_path.parent() {
-            std::fs::create_dir_all(parent)?;
+    /// Get a profile by name, or a new one without any deployed accounts if it does not exist
+    pub fn get_or_new(&self, name: &str) -> Config {
+        self.get(name).unwrap_or_else(|_| {
+            let mut config = Config::new(name, "", Config::default_storage_path(name));
+            config.config_dir = self.config_dir.clone();
+            config
+        })
+    }
+
+    /// Add a new profile
+    pub fn add(&mut self, mut config: Config) -> anyhow::Result<()> {
+        if self.profiles.contains_key(&config.profile) {
+            anyhow::bail!("Profile `{}` already exists", config.profile);
+        }
+
+        config.config_dir = self.config_dir.clone();
+        self.profiles.insert(config.profile.clone(), config);
+        Ok(())
+    }
+
+    /// Make a profile the active one
+    pub fn switch(&mut self, name: &str) -> anyhow::Result<()> {
+        if !self.profiles.contains_key(name) {
+            anyhow::bail!("Unknown profile `{}`", name);
+        }
+
+        self.active_profile = name.to_string();
+        Ok(())
+    }
+
+    /// Points the profiles, and the paths they resolve, at a config directory
+    fn set_config_dir(&mut self, config_dir: &Path) {
+        self.config_dir = config_dir.to_path_buf();
+        for config in self.profiles.values_mut() {
+            config.config_dir = config_dir.to_path_buf();
         }
+    }
+}
+
+/// Deployment manifest describing the protocol accounts on a network
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeploymentInfo {
+    pub network: String,
+    pub timestamp: String,
+    pub lending_pool_id: String,
+    pub price_oracle_id: String,
+    pub assets: BTreeMap<String, DeployedAsset>,
+}
+
+/// Asset entry of the deployment manifest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeployedAsset {
+    pub id: u32,
+    pub price: String,
+    pub decimals: u32,
+    /// Faucet issuing the asset (hex account ID), once the asset is listed with one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub faucet_id: Option<String>,
+}
+
+impl DeploymentInfo {
+    /// Create a manifest for a new deployment of the given assets
+    pub fn new(network: &str, assets: BTreeMap<String, DeployedAsset>) -> Self {
+        Self {
+            network: network.to_string(),
+            timestamp: String::new(),
+            lending_pool_id: String::new(),
+            price_oracle_id: String::new(),
+            assets,
+        }
+    }
+
+    /// Load a manifest, returning `None` if it does not exist
+    pub fn load(path: &Path) -> anyhow::Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = std::fs::read_to_string(path)?;
+        Ok(Some(serde_json::from_str(&content)?))
+    }
+
+    /// Save the manifest, stamping it with the current time
+    pub fn save(&mut self, path: &Path) -> anyhow::Result<()> {
+        self.timestamp = chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
 
         let content = serde_json::to_string_pretty(self)?;
-        std::fs::write(&config_path, content)?;
+        std::fs::write(path, content)?;
         Ok(())
     }
+}
+
+/// Assets listed by the price oracle's initial prices
+fn default_assets() -> BTreeMap<String, DeployedAsset> {
+    [("USDC", 1, "1.00", 6), ("DAI", 2, "1.00", 8), ("WETH", 3, "2500.00", 8)]
+        .into_iter()
+        .map(|(name, id, price, decimals)| {
+            (name.to_string(), DeployedAsset { id, price: price.to_string(), decimals, faucet_id: None })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Empty config directory under the system temp directory, removed when dropped
+    struct TempConfigDir(PathBuf);
+
+    impl TempConfigDir {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir()
+                .join(format!("miden-lending-config-{}-{}", name, std::process::id()));
+            let _ = std::fs::remove_dir_all(&dir);
+            std::fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+    }
+
+    impl Drop for TempConfigDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn test_legacy_config_round_trips_as_default_profile() {
+        let dir = TempConfigDir::new("legacy");
+        let legacy = serde_json::json!({
+            "rpc_endpoint": "http://localhost:57291",
+            "lending_pool_account_id": "0xpool",
+            "price_oracle_account_id": "0xoracle",
+            "user_account_id": null,
+            "storage_path": "store"
+        });
+        std::fs::write(dir.0.join(CONFIG_FILE), legacy.to_string()).unwrap();
+
+        let profiles = Profiles::load_from(&dir.0).unwrap();
+        assert_eq!(profiles.active_profile, DEFAULT_PROFILE);
+        assert_eq!(profiles.profiles.len(), 1);
+
+        let config = profiles.get(DEFAULT_PROFILE).unwrap();
+        assert_eq!(config.profile, DEFAULT_PROFILE);
+        assert_eq!(config.lending_pool_account_id.as_deref(), Some("0xpool"));
+        assert_eq!(config.assets.len(), default_assets().len());
+
+        // Saving writes the profiles format, which loads back the same profile
+        config.save().unwrap();
+        let content = std::fs::read_to_string(dir.0.join(CONFIG_FILE)).unwrap();
+        assert!(serde_json::from_str::<serde_json::Value>(&content).unwrap()["profiles"].is_object());
+
+        let reloaded = Config::load_profile_from(&dir.0, None).unwrap();
+        assert_eq!(reloaded.profile, DEFAULT_PROFILE);
+        assert_eq!(reloaded.price_oracle_account_id.as_deref(), Some("0xoracle"));
+        assert_eq!(reloaded.storage_path, PathBuf::from("store"));
+    }
+
+    #[test]
+    fn test_add_and_switch_profiles() {
+        let dir = TempConfigDir::new("add-switch");
+        let mut profiles = Profiles::load_from(&dir.0).unwrap();
+
+        let storage_path = Config::default_storage_path("testnet");
+        profiles.add(Config::new("testnet", "https://rpc.testnet", storage_path)).unwrap();
+        let err = profiles.add(Config::new("testnet", "https://other", PathBuf::from("other"))).unwrap_err();
+        assert!(err.to_string().contains("already exists"), "got: {}", err);
+
+        assert!(profiles.switch("devnet").is_err());
+        profiles.switch("testnet").unwrap();
+        profiles.save().unwrap();
+
+        let profiles = Profiles::load_from(&dir.0).unwrap();
+        assert_eq!(profiles.active_profile, "testnet");
+        assert_eq!(profiles.get("testnet").unwrap().rpc_endpoint, "https://rpc.testnet");
+        assert!(profiles.get(DEFAULT_PROFILE).is_ok());
+        assert!(profiles.get("devnet").is_err());
+    }
+
+    #[test]
+    fn test_profile_resolution() {
+        let dir = TempConfigDir::new("resolution");
+        let mut profiles = Profiles::load_from(&dir.0).unwrap();
+        profiles.add(Config::new("testnet", "https://rpc.testnet", PathBuf::from("testnet"))).unwrap();
+        profiles.save().unwrap();
+
+        // Without --profile the active profile is used, otherwise the named one
+        assert_eq!(Config::load_profile_from(&dir.0, None).unwrap().profile, DEFAULT_PROFILE);
+        assert_eq!(Config::load_profile_from(&dir.0, Some("testnet")).unwrap().profile, "testnet");
+        assert!(Config::load_profile_from(&dir.0, Some("devnet")).is_err());
+
+        // Init creates a profile that does not exist yet, in the same config directory
+        let profiles = Profiles::load_from(&dir.0).unwrap();
+        let devnet = profiles.get_or_new("devnet");
+        assert_eq!(devnet.storage_dir(), dir.0.join("store").join("devnet"));
+        devnet.save().unwrap();
+        assert!(Config::load_profile_from(&dir.0, Some("devnet")).is_ok());
+        assert_eq!(profiles.get_or_new("testnet").rpc_endpoint, "https://rpc.testnet");
+    }
+
+    #[test]
+    fn test_scanned_user_accounts() {
+        let mut config = Config::default();
+        assert!(config.scanned_user_accounts().is_empty());
+
+        config.known_user_accounts = vec!["0xa".to_string(), "0xb".to_string()];
+        config.user_account_id = Some("0xb".to_string());
+        assert_eq!(config.scanned_user_accounts(), ["0xa", "0xb"]);
+
+        // An active account imported from elsewhere is scanned too
+        config.user_account_id = Some("0xc".to_string());
+        assert_eq!(config.scanned_user_accounts(), ["0xa", "0xb", "0xc"]);
+    }
+
+    #[test]
+    fn test_paths_resolve_against_config_dir() {
+        let dir = TempConfigDir::new("paths");
+        let mut profiles = Profiles::load_from(&dir.0).unwrap();
+        let absolute = std::env::temp_dir().join("miden-lending-absolute-store");
+        profiles.add(Config::new("testnet", "https://rpc.testnet", absolute.clone())).unwrap();
+
+        let local = profiles.get(DEFAULT_PROFILE).unwrap();
+        assert_eq!(local.storage_dir(), dir.0.join("store"));
+        assert_eq!(local.deployment_info_path(), dir.0.join(DEPLOYMENT_INFO_FILE));
+
+        let testnet = profiles.get("testnet").unwrap();
+        assert_eq!(testnet.storage_dir(), absolute);
+        assert_eq!(testnet.deployment_info_path(), dir.0.join("deployment_info.testnet.json"));
 
-    fn get_config_path() -> PathBuf {
-        dirs::home_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join(".miden-lending")
-            .join("config.json")
+        // The manifest is found through the config directory, whatever the working directory
+        let mut info = DeploymentInfo::new("local", default_assets());
+        info.lending_pool_id = "0xpool".to_string();
+        info.save(&local.deployment_info_path()).unwrap();
+        assert_eq!(local.lending_pool_id().as_deref(), Some("0xpool"));
     }
 }
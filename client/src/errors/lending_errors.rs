@@ -1,5 +1,93 @@
 use miden_lib::errors::MasmError;
 
 // This file is generated by build.rs, do not modify manually.
-// It extracts error constants from MASM files in the contracts directory.
+// It extracts error constants from MASM files in the contracts and modules directories.
 
+/// Error Message: "fixed-point division by zero"
+pub const ERR_FIXED_POINT_DIVISION_BY_ZERO: MasmError = MasmError::from_static_str("fixed-point division by zero");
+/// Error Message: "fixed-point result does not fit in a field element"
+pub const ERR_FIXED_POINT_OVERFLOW: MasmError = MasmError::from_static_str("fixed-point result does not fit in a field element");
+/// Error Message: "borrow exceeds the loan-to-value limit"
+pub const ERR_LENDING_POOL_BORROW_EXCEEDS_LTV: MasmError = MasmError::from_static_str("borrow exceeds the loan-to-value limit");
+/// Error Message: "claim exceeds the reserves accrued to the treasury"
+pub const ERR_LENDING_POOL_CLAIM_EXCEEDS_TREASURY: MasmError = MasmError::from_static_str("claim exceeds the reserves accrued to the treasury");
+/// Error Message: "faucet already backs a listed reserve"
+pub const ERR_LENDING_POOL_FAUCET_ALREADY_LISTED: MasmError = MasmError::from_static_str("faucet already backs a listed reserve");
+/// Error Message: "health factor would fall below 1.0"
+pub const ERR_LENDING_POOL_HEALTH_FACTOR_TOO_LOW: MasmError = MasmError::from_static_str("health factor would fall below 1.0");
+/// Error Message: "withdrawal exceeds the collateral"
+pub const ERR_LENDING_POOL_INSUFFICIENT_COLLATERAL: MasmError = MasmError::from_static_str("withdrawal exceeds the collateral");
+/// Error Message: "amount exceeds available liquidity"
+pub const ERR_LENDING_POOL_INSUFFICIENT_LIQUIDITY: MasmError = MasmError::from_static_str("amount exceeds available liquidity");
+/// Error Message: "withdrawal exceeds supplied balance"
+pub const ERR_LENDING_POOL_INSUFFICIENT_SUPPLY: MasmError = MasmError::from_static_str("withdrawal exceeds supplied balance");
+/// Error Message: "reserve asset has more than 18 decimals"
+pub const ERR_LENDING_POOL_INVALID_DECIMALS: MasmError = MasmError::from_static_str("reserve asset has more than 18 decimals");
+/// Error Message: "reserve asset must be issued by a fungible faucet"
+pub const ERR_LENDING_POOL_INVALID_FAUCET: MasmError = MasmError::from_static_str("reserve asset must be issued by a fungible faucet");
+/// Error Message: "invalid price source"
+pub const ERR_LENDING_POOL_INVALID_PRICE_SOURCE: MasmError = MasmError::from_static_str("invalid price source");
+/// Error Message: "invalid interest rate strategy"
+pub const ERR_LENDING_POOL_INVALID_RATE_STRATEGY: MasmError = MasmError::from_static_str("invalid interest rate strategy");
+/// Error Message: "invalid collateral risk parameters"
+pub const ERR_LENDING_POOL_INVALID_RISK_PARAMETERS: MasmError = MasmError::from_static_str("invalid collateral risk parameters");
+/// Error Message: "account has no debt in the reserve"
+pub const ERR_LENDING_POOL_NO_DEBT: MasmError = MasmError::from_static_str("account has no debt in the reserve");
+/// Error Message: "oracle has no price feed for the asset"
+pub const ERR_LENDING_POOL_NO_PRICE_FEED: MasmError = MasmError::from_static_str("oracle has no price feed for the asset");
+/// Error Message: "position is not liquidatable"
+pub const ERR_LENDING_POOL_POSITION_HEALTHY: MasmError = MasmError::from_static_str("position is not liquidatable");
+/// Error Message: "repayment exceeds the debt"
+pub const ERR_LENDING_POOL_REPAY_EXCEEDS_DEBT: MasmError = MasmError::from_static_str("repayment exceeds the debt");
+/// Error Message: "reserve is already listed"
+pub const ERR_LENDING_POOL_RESERVE_ALREADY_LISTED: MasmError = MasmError::from_static_str("reserve is already listed");
+/// Error Message: "liquidation collateral and debt must be different assets"
+pub const ERR_LENDING_POOL_SAME_LIQUIDATION_ASSET: MasmError = MasmError::from_static_str("liquidation collateral and debt must be different assets");
+/// Error Message: "asset is not listed in the lending pool"
+pub const ERR_LENDING_POOL_UNKNOWN_ASSET: MasmError = MasmError::from_static_str("asset is not listed in the lending pool");
+/// Error Message: "note must carry exactly the amount of the reserve's asset"
+pub const ERR_LENDING_POOL_WRONG_NOTE_ASSET: MasmError = MasmError::from_static_str("note must carry exactly the amount of the reserve's asset");
+/// Error Message: "liquidation note expects exactly 13 note inputs"
+pub const ERR_LIQUIDATION_NOTE_WRONG_NUMBER_OF_INPUTS: MasmError = MasmError::from_static_str("liquidation note expects exactly 13 note inputs");
+/// Error Message: "unknown position note operation"
+pub const ERR_POSITION_NOTE_UNKNOWN_OPERATION: MasmError = MasmError::from_static_str("unknown position note operation");
+/// Error Message: "position note expects exactly 11 note inputs"
+pub const ERR_POSITION_NOTE_WRONG_NUMBER_OF_INPUTS: MasmError = MasmError::from_static_str("position note expects exactly 11 note inputs");
+/// Error Message: "price note expects exactly 7 note inputs"
+pub const ERR_PRICE_NOTE_WRONG_NUMBER_OF_INPUTS: MasmError = MasmError::from_static_str("price note expects exactly 7 note inputs");
+/// Error Message: "asset already has a price feed"
+pub const ERR_PRICE_ORACLE_FEED_ALREADY_LISTED: MasmError = MasmError::from_static_str("asset already has a price feed");
+/// Error Message: "signed price is timestamped in the future"
+pub const ERR_PRICE_ORACLE_FUTURE_PRICE: MasmError = MasmError::from_static_str("signed price is timestamped in the future");
+/// Error Message: "invalid circuit breaker parameters"
+pub const ERR_PRICE_ORACLE_INVALID_CIRCUIT_BREAKER: MasmError = MasmError::from_static_str("invalid circuit breaker parameters");
+/// Error Message: "invalid price quorum"
+pub const ERR_PRICE_ORACLE_INVALID_QUORUM: MasmError = MasmError::from_static_str("invalid price quorum");
+/// Error Message: "invalid TWAP window"
+pub const ERR_PRICE_ORACLE_INVALID_TWAP_WINDOW: MasmError = MasmError::from_static_str("invalid TWAP window");
+/// Error Message: "transaction requires signatures from several keys"
+pub const ERR_PRICE_ORACLE_MULTIPLE_SIGNERS: MasmError = MasmError::from_static_str("transaction requires signatures from several keys");
+/// Error Message: "signed price is not newer than the updater's latest submission"
+pub const ERR_PRICE_ORACLE_OUTDATED_PRICE: MasmError = MasmError::from_static_str("signed price is not newer than the updater's latest submission");
+/// Error Message: "price feed is paused by its circuit breaker"
+pub const ERR_PRICE_ORACLE_PAUSED: MasmError = MasmError::from_static_str("price feed is paused by its circuit breaker");
+/// Error Message: "price is older than its maximum age"
+pub const ERR_PRICE_ORACLE_STALE_PRICE: MasmError = MasmError::from_static_str("price is older than its maximum age");
+/// Error Message: "updater set is full"
+pub const ERR_PRICE_ORACLE_TOO_MANY_UPDATERS: MasmError = MasmError::from_static_str("updater set is full");
+/// Error Message: "unauthorized updater"
+pub const ERR_PRICE_ORACLE_UNAUTHORIZED_UPDATER: MasmError = MasmError::from_static_str("unauthorized updater");
+/// Error Message: "asset has no price feed in the oracle"
+pub const ERR_PRICE_ORACLE_UNKNOWN_ASSET: MasmError = MasmError::from_static_str("asset has no price feed in the oracle");
+/// Error Message: "updater is already registered"
+pub const ERR_PRICE_ORACLE_UPDATER_ALREADY_REGISTERED: MasmError = MasmError::from_static_str("updater is already registered");
+/// Error Message: "price must be greater than zero"
+pub const ERR_PRICE_ORACLE_ZERO_PRICE: MasmError = MasmError::from_static_str("price must be greater than zero");
+/// Error Message: "request note cannot be reclaimed before its reclaim block height"
+pub const ERR_RECLAIM_HEIGHT_NOT_REACHED: MasmError = MasmError::from_static_str("request note cannot be reclaimed before its reclaim block height");
+/// Error Message: "request note can only be consumed by its lending pool or reclaimed by its sender"
+pub const ERR_RECLAIM_WRONG_POOL: MasmError = MasmError::from_static_str("request note can only be consumed by its lending pool or reclaimed by its sender");
+/// Error Message: "arithmetic overflow"
+pub const ERR_SAFE_MATH_OVERFLOW: MasmError = MasmError::from_static_str("arithmetic overflow");
+/// Error Message: "arithmetic underflow"
+pub const ERR_SAFE_MATH_UNDERFLOW: MasmError = MasmError::from_static_str("arithmetic underflow");
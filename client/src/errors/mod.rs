@@ -1,8 +1,56 @@
 // Error module for Miden Lending Protocol
-// This module contains error constants extracted from MASM code
+// This module contains error constants extracted from MASM code, and the errors callers can match
+// on when a contract rejects a transaction
 
-#[cfg(any(feature = "testing", test))]
 pub mod lending_errors;
 
-#[cfg(any(feature = "testing", test))]
 pub use lending_errors::*;
+
+use miden_objects::Felt;
+use miden_processor::ExecutionError;
+use miden_tx::TransactionExecutorError;
+
+/// Errors raised by the lending contracts
+///
+/// Transactions failing on one of these assertions return it as the context of their error, so it
+/// can be matched with [`anyhow::Error::downcast_ref`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum LendingError {
+    /// The key submitting an oracle price is not an authorized updater
+    #[error("price updater is not authorized by the oracle")]
+    UnauthorizedPriceUpdater,
+    /// The account repaying debt has none in the reserve
+    #[error("account has no debt in the reserve")]
+    NoDebt,
+}
+
+impl LendingError {
+    /// Maps the code of a failed contract assertion to its error
+    pub fn from_code(code: Felt) -> Option<Self> {
+        if code == ERR_PRICE_ORACLE_UNAUTHORIZED_UPDATER.code() {
+            Some(Self::UnauthorizedPriceUpdater)
+        } else if code == ERR_LENDING_POOL_NO_DEBT.code() {
+            Some(Self::NoDebt)
+        } else {
+            None
+        }
+    }
+
+    /// Finds the contract error behind a failed transaction, if any
+    pub fn from_transaction_error(err: &anyhow::Error) -> Option<Self> {
+        err.chain().find_map(|source| match source.downcast_ref::<TransactionExecutorError>()? {
+            TransactionExecutorError::TransactionProgramExecutionFailed(
+                ExecutionError::FailedAssertion { err_code, .. },
+            ) => Self::from_code(*err_code),
+            _ => None,
+        })
+    }
+}
+
+/// Adds the contract error behind a failed transaction to its context
+pub(crate) fn with_lending_error(err: anyhow::Error) -> anyhow::Error {
+    match LendingError::from_transaction_error(&err) {
+        Some(lending_error) => err.context(lending_error),
+        None => err,
+    }
+}
@@ -0,0 +1,176 @@
+// Wad fixed-point arithmetic
+// Mirrors the `lending::fixed_point` MASM module, so rates and indices computed off-chain match
+// the contracts to the last unit
+
+/// 1.0 as a wad: fixed-point numbers with 18 decimals
+pub const WAD: u64 = 1_000_000_000_000_000_000;
+
+/// 1 basis point as a wad
+pub const BPS_TO_WAD: u64 = 100_000_000_000_000;
+
+/// Largest value fitting in a field element with the checks the contracts perform
+const MAX_FELT: u64 = 0xFFFF_FFFE_FFFF_FFFF;
+
+/// Narrows a 128-bit result, returning `None` if the contracts would reject it
+fn to_felt(value: u128) -> Option<u64> {
+    u64::try_from(value).ok().filter(|value| *value <= MAX_FELT)
+}
+
+/// Narrows a 128-bit result, saturating at `cap`
+fn to_felt_capped(value: u128, cap: u64) -> u64 {
+    to_felt(value).map_or(cap, |value| value.min(cap))
+}
+
+/// Computes `a * b / c` on 128 bits, rounding down
+pub fn mul_div(a: u64, b: u64, c: u64) -> Option<u64> {
+    if c == 0 {
+        return None;
+    }
+    to_felt(a as u128 * b as u128 / c as u128)
+}
+
+/// Computes `a * b / c` on 128 bits, rounding up
+pub fn mul_div_up(a: u64, b: u64, c: u64) -> Option<u64> {
+    let quotient = mul_div(a, b, c)?;
+    // The division was exact if multiplying back gives `a * b`
+    if quotient as u128 * c as u128 == a as u128 * b as u128 {
+        Some(quotient)
+    } else {
+        to_felt(quotient as u128 + 1)
+    }
+}
+
+/// Computes `a * b / c` on 128 bits, rounding down and saturating at `cap`
+///
+/// The contracts only take divisors fitting in 32 bits.
+pub fn mul_div_capped(a: u64, b: u64, c: u32, cap: u64) -> Option<u64> {
+    if c == 0 {
+        return None;
+    }
+    Some(to_felt_capped(a as u128 * b as u128 / c as u128, cap))
+}
+
+/// Multiplies two wads, rounding down
+pub fn wad_mul(a: u64, b: u64) -> Option<u64> {
+    to_felt(a as u128 * b as u128 / WAD as u128)
+}
+
+/// Multiplies two wads, rounding down and saturating at `cap`
+pub fn wad_mul_capped(a: u64, b: u64, cap: u64) -> u64 {
+    to_felt_capped(a as u128 * b as u128 / WAD as u128, cap)
+}
+
+/// Divides two wads, rounding down
+pub fn wad_div(a: u64, b: u64) -> Option<u64> {
+    mul_div(a, WAD, b)
+}
+
+/// Divides two wads, rounding up
+pub fn wad_div_up(a: u64, b: u64) -> Option<u64> {
+    let quotient = wad_div(a, b)?;
+    // The division was exact if multiplying back gives `a`
+    if wad_mul(quotient, b)? < a {
+        to_felt(quotient as u128 + 1)
+    } else {
+        Some(quotient)
+    }
+}
+
+/// Raises a wad to an integer power by repeated squaring, rounding down after every product
+pub fn wad_pow(base: u64, exponent: u32) -> Option<u64> {
+    let mut base = base;
+    let mut exponent = exponent;
+    let mut result = WAD;
+
+    while exponent != 0 {
+        if exponent & 1 == 1 {
+            result = wad_mul(result, base)?;
+        }
+        exponent >>= 1;
+        // The last square would go unused
+        if exponent != 0 {
+            base = wad_mul(base, base)?;
+        }
+    }
+
+    Some(result)
+}
+
+/// Raises a wad of at least 1.0 to an integer power like [`wad_pow`], saturating at `cap`
+pub fn wad_pow_capped(base: u64, exponent: u32, cap: u64) -> u64 {
+    let mut base = base;
+    let mut exponent = exponent;
+    let mut result = WAD;
+
+    while exponent != 0 {
+        if exponent & 1 == 1 {
+            result = wad_mul_capped(result, base, cap);
+        }
+        exponent >>= 1;
+        // The last square would go unused
+        if exponent != 0 {
+            base = wad_mul_capped(base, base, cap);
+        }
+    }
+
+    result
+}
+
+/// Converts basis points into a wad
+pub fn bps_to_wad(bps: u64) -> Option<u64> {
+    to_felt(bps as u128 * BPS_TO_WAD as u128)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mul_div_keeps_128_bit_intermediates() {
+        // WAD * WAD overflows u64, but the quotient fits
+        assert_eq!(mul_div(WAD, WAD, 3 * WAD), Some(WAD / 3));
+        assert_eq!(mul_div(WAD, 20, 1), None);
+        assert_eq!(mul_div(1, 1, 0), None);
+    }
+
+    #[test]
+    fn test_mul_div_up_rounds_up_inexact_quotients() {
+        assert_eq!(mul_div_up(WAD, WAD, 4 * WAD), Some(WAD / 4));
+        assert_eq!(mul_div_up(WAD, WAD, 3 * WAD), Some(WAD / 3 + 1));
+        assert_eq!(mul_div_up(1, 1, 0), None);
+    }
+
+    #[test]
+    fn test_wad_div_up_rounds_up_inexact_quotients() {
+        assert_eq!(wad_div_up(WAD, 4 * WAD), Some(WAD / 4));
+        assert_eq!(wad_div_up(1, 3 * WAD), Some(1));
+        assert_eq!(wad_div(1, 3 * WAD), Some(0));
+    }
+
+    #[test]
+    fn test_wad_pow_compounds() {
+        assert_eq!(wad_pow(2 * WAD, 0), Some(WAD));
+        assert_eq!(wad_pow(2 * WAD, 4), Some(16 * WAD));
+        assert_eq!(wad_pow(2 * WAD, 5), None);
+
+        // 10% a year, compounded every second
+        let per_second = WAD + bps_to_wad(1000).unwrap() / 31_536_000;
+        assert_eq!(wad_pow(per_second, 31_536_000), Some(1_105_170_917_878_694_255));
+    }
+
+    #[test]
+    fn test_capped_operations_saturate() {
+        assert_eq!(wad_mul_capped(3 * WAD, 4 * WAD, 15 * WAD), 12 * WAD);
+        assert_eq!(wad_mul_capped(5 * WAD, 5 * WAD, 15 * WAD), 15 * WAD);
+        // Products past a field element saturate instead of failing
+        assert_eq!(wad_mul_capped(u64::MAX / 2, u64::MAX / 2, 18 * WAD), 18 * WAD);
+        assert_eq!(mul_div_capped(3 * WAD, u32::MAX as u64, 1, 18 * WAD), Some(18 * WAD));
+        assert_eq!(mul_div_capped(1, 1, 0, WAD), None);
+
+        let per_second = WAD + bps_to_wad(1000).unwrap() / 31_536_000;
+        assert_eq!(wad_pow_capped(per_second, 31_536_000, 18 * WAD), 1_105_170_917_878_694_255);
+        assert_eq!(wad_pow_capped(2 * WAD, 4, 18 * WAD), 16 * WAD);
+        assert_eq!(wad_pow_capped(2 * WAD, 5, 18 * WAD), 18 * WAD);
+        assert_eq!(wad_pow_capped(2 * WAD, 64, 18 * WAD), 18 * WAD);
+    }
+}
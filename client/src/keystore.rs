@@ -0,0 +1,514 @@
+// Encrypted file keystore for lending account keys
+// Secret keys are encrypted with ChaCha20-Poly1305 under a key derived from the keystore
+// password with Argon2id, and stored one file per account under `<storage_path>/keys`, readable by
+// the owner only
+
+use anyhow::{anyhow, Context, Result};
+use argon2::Argon2;
+use chacha20poly1305::{aead::Aead, ChaCha20Poly1305, Key, KeyInit, Nonce};
+use miden_objects::{
+    account::AuthSecretKey,
+    crypto::rand::RpoRandomCoin,
+    utils::{Deserializable, Serializable},
+    Felt, Word,
+};
+use miden_tx::{
+    auth::{signatures::get_falcon_signature, SigningInputs, TransactionAuthenticator},
+    AuthenticationError,
+};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::miden_client::AccountId;
+
+/// Environment variable holding the keystore password
+pub const KEYSTORE_PASSWORD_ENV: &str = "MIDEN_LENDING_KEYSTORE_PASSWORD";
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// Directory of encrypted account keys
+///
+/// Public keys are stored in the clear so keys can be listed and matched against signature
+/// requests without the password; the password is only needed to add or use a secret key.
+pub struct FileKeyStore {
+    dir: PathBuf,
+    password: Option<String>,
+}
+
+/// Encrypted key as stored on disk
+#[derive(Serialize, Deserialize)]
+struct KeyFile {
+    account_id: String,
+    /// Commitment to the public key, as checked by the account's auth component
+    public_key: String,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// Summary of a stored key
+#[derive(Debug, Clone, Serialize)]
+pub struct KeyInfo {
+    pub account_id: String,
+    pub public_key: String,
+}
+
+impl FileKeyStore {
+    const KEYS_DIR: &'static str = "keys";
+
+    /// Open the keystore under `storage_path`, unlocked with the given password
+    pub fn open(storage_path: &Path, password: Option<String>) -> Result<Self> {
+        let dir = storage_path.join(Self::KEYS_DIR);
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create keystore at {:?}", dir))?;
+        restrict_permissions(&dir, 0o700)
+            .with_context(|| format!("Failed to restrict access to keystore at {:?}", dir))?;
+
+        Ok(Self { dir, password })
+    }
+
+    /// Open the keystore under `storage_path`, reading the password from the environment
+    pub fn from_env(storage_path: &Path) -> Result<Self> {
+        Self::open(storage_path, std::env::var(KEYSTORE_PASSWORD_ENV).ok())
+    }
+
+    /// Encrypt and store the secret key of a new account
+    ///
+    /// Fails if a key is already stored for the account, since it may be the only copy of it.
+    pub fn add_key(&self, account_id: &AccountId, secret_key: &AuthSecretKey) -> Result<()> {
+        self.write_key(account_id, secret_key, false)
+    }
+
+    /// Encrypt and store the secret key of an account, replacing an existing key only if `force`
+    /// is set
+    fn write_key(&self, account_id: &AccountId, secret_key: &AuthSecretKey, force: bool) -> Result<()> {
+        let path = self.key_path(account_id);
+        if !force && path.exists() {
+            anyhow::bail!("A key is already stored for account {}", account_id.to_hex());
+        }
+
+        let mut salt = [0u8; SALT_LEN];
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut salt);
+        rand::thread_rng().fill_bytes(&mut nonce);
+
+        let cipher = self.cipher(&salt)?;
+        let ciphertext = cipher
+            .encrypt(Nonce::from_slice(&nonce), secret_key.to_bytes().as_slice())
+            .map_err(|_| anyhow!("Failed to encrypt key"))?;
+
+        let key_file = KeyFile {
+            account_id: account_id.to_hex(),
+            public_key: hex::encode(public_key_commitment(secret_key).as_bytes()),
+            salt: hex::encode(salt),
+            nonce: hex::encode(nonce),
+            ciphertext: hex::encode(ciphertext),
+        };
+
+        // Write the new key next to the old one, so a failed write leaves the old key in place
+        let tmp_path = path.with_extension("key.tmp");
+        write_private(&tmp_path, serde_json::to_string_pretty(&key_file)?.as_bytes())
+            .context("Failed to write key file")?;
+        fs::rename(&tmp_path, &path).context("Failed to write key file")?;
+
+        tracing::debug!("Stored key for account {}", account_id.to_hex());
+        Ok(())
+    }
+
+    /// Decrypt the secret key of an account
+    pub fn get_key(&self, account_id: &AccountId) -> Result<AuthSecretKey> {
+        let key_file = self.read_key_file(&self.key_path(account_id)).with_context(|| {
+            format!("No key stored for account {}", account_id.to_hex())
+        })?;
+
+        self.decrypt(&key_file)
+    }
+
+    /// List the stored keys, skipping corrupt key files
+    pub fn list_keys(&self) -> Result<Vec<KeyInfo>> {
+        let mut keys: Vec<_> = self
+            .key_files()?
+            .into_iter()
+            .map(|key_file| KeyInfo {
+                account_id: key_file.account_id,
+                public_key: key_file.public_key,
+            })
+            .collect();
+
+        keys.sort_by(|a, b| a.account_id.cmp(&b.account_id));
+        Ok(keys)
+    }
+
+    /// Export the secret key of an account as unencrypted hex
+    pub fn export_key(&self, account_id: &AccountId) -> Result<String> {
+        Ok(hex::encode(self.get_key(account_id)?.to_bytes()))
+    }
+
+    /// Export the secret key of an account as unencrypted hex to a new file, readable and
+    /// writable by the owner only
+    ///
+    /// Fails if the file already exists, since it may hold another key.
+    pub fn export_key_to_file(&self, account_id: &AccountId, path: &Path) -> Result<()> {
+        let secret_key = self.export_key(account_id)?;
+        create_private(path, secret_key.as_bytes()).map_err(|err| match err.kind() {
+            std::io::ErrorKind::AlreadyExists => anyhow!("{:?} already exists", path),
+            _ => anyhow::Error::new(err).context(format!("Failed to write {:?}", path)),
+        })
+    }
+
+    /// Import a secret key exported with [`export_key`](Self::export_key)
+    ///
+    /// The key must match `account_public_key`, the public key commitment of the account's auth
+    /// component. A key already stored for the account is only replaced if `force` is set.
+    pub fn import_key(
+        &self,
+        account_id: &AccountId,
+        secret_key_hex: &str,
+        account_public_key: Word,
+        force: bool,
+    ) -> Result<()> {
+        let bytes = hex::decode(secret_key_hex.trim()).context("Key is not valid hex")?;
+        let secret_key = AuthSecretKey::read_from_bytes(&bytes)
+            .map_err(|err| anyhow!("Key is not a valid secret key: {}", err))?;
+
+        if public_key_commitment(&secret_key) != account_public_key {
+            anyhow::bail!("Key does not match the public key of account {}", account_id.to_hex());
+        }
+
+        self.write_key(account_id, &secret_key, force)
+    }
+
+    /// Find and decrypt the secret key matching a public key commitment
+    fn find_key(&self, public_key: Word) -> Result<Option<AuthSecretKey>> {
+        let public_key = hex::encode(public_key.as_bytes());
+
+        match self.key_files()?.into_iter().find(|key_file| key_file.public_key == public_key) {
+            Some(key_file) => self.decrypt(&key_file).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Reads the stored key files, skipping corrupt ones so they do not lock out the other keys
+    fn key_files(&self) -> Result<Vec<KeyFile>> {
+        let mut key_files = Vec::new();
+
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "key") {
+                match self.read_key_file(&path) {
+                    Ok(key_file) => key_files.push(key_file),
+                    Err(err) => tracing::warn!("Skipping key file: {:#}", err),
+                }
+            }
+        }
+
+        Ok(key_files)
+    }
+
+    fn key_path(&self, account_id: &AccountId) -> PathBuf {
+        self.dir.join(format!("{}.key", account_id.to_hex()))
+    }
+
+    fn read_key_file(&self, path: &Path) -> Result<KeyFile> {
+        let content = fs::read_to_string(path)?;
+        serde_json::from_str(&content).with_context(|| format!("Corrupt key file {:?}", path))
+    }
+
+    fn decrypt(&self, key_file: &KeyFile) -> Result<AuthSecretKey> {
+        let salt = hex::decode(&key_file.salt).context("Corrupt key file")?;
+        let nonce = hex::decode(&key_file.nonce).context("Corrupt key file")?;
+        let ciphertext = hex::decode(&key_file.ciphertext).context("Corrupt key file")?;
+
+        let plaintext = self
+            .cipher(&salt)?
+            .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+            .map_err(|_| anyhow!("Failed to decrypt key of {}: wrong password?", key_file.account_id))?;
+
+        AuthSecretKey::read_from_bytes(&plaintext)
+            .map_err(|err| anyhow!("Corrupt key of {}: {}", key_file.account_id, err))
+    }
+
+    /// Derives the encryption key from the password and a per-key salt
+    fn cipher(&self, salt: &[u8]) -> Result<ChaCha20Poly1305> {
+        let password = self.password.as_deref().with_context(|| {
+            format!("Keystore is locked, set {} to unlock it", KEYSTORE_PASSWORD_ENV)
+        })?;
+
+        let mut key = [0u8; 32];
+        Argon2::default()
+            .hash_password_into(password.as_bytes(), salt, &mut key)
+            .map_err(|err| anyhow!("Failed to derive keystore key: {}", err))?;
+
+        Ok(ChaCha20Poly1305::new(Key::from_slice(&key)))
+    }
+}
+
+impl TransactionAuthenticator for FileKeyStore {
+    /// Signs with the stored key matching the public key requested by the auth component
+    async fn get_signature(
+        &self,
+        pub_key: Word,
+        signing_inputs: &SigningInputs,
+    ) -> Result<Vec<Felt>, AuthenticationError> {
+        let secret_key = self
+            .find_key(pub_key)
+            .map_err(|err| AuthenticationError::other(err.to_string()))?
+            .ok_or_else(|| {
+                AuthenticationError::UnknownPublicKey(format!(
+                    "public key {} is not in the keystore",
+                    pub_key
+                ))
+            })?;
+
+        let mut seed = [Felt::new(0); 4];
+        seed.iter_mut().for_each(|felt| *felt = Felt::new(rand::thread_rng().next_u64() >> 1));
+        let mut rng = RpoRandomCoin::new(Word::new(seed));
+
+        match secret_key {
+            AuthSecretKey::RpoFalcon512(key) => {
+                get_falcon_signature(&key, signing_inputs.to_commitment(), &mut rng)
+            },
+        }
+    }
+}
+
+/// Writes a file readable and writable by the owner only
+fn write_private(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let mut options = private_options();
+    options.create(true).truncate(true);
+    write_file(options, path, contents)?;
+
+    // The mode only applies to new files
+    restrict_permissions(path, 0o600)
+}
+
+/// Creates a file readable and writable by the owner only, failing if it already exists
+fn create_private(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let mut options = private_options();
+    options.create_new(true);
+    write_file(options, path, contents)
+}
+
+/// Returns options opening a file for writing, which creates it readable and writable by the
+/// owner only
+fn private_options() -> fs::OpenOptions {
+    let mut options = fs::OpenOptions::new();
+    options.write(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options
+}
+
+/// Writes the contents of a file opened with the given options
+fn write_file(options: fs::OpenOptions, path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut file = options.open(path)?;
+    file.write_all(contents)?;
+    file.sync_all()
+}
+
+/// Restricts the permissions of a file or directory to the given mode
+#[cfg(unix)]
+fn restrict_permissions(path: &Path, mode: u32) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::set_permissions(path, fs::Permissions::from_mode(mode))
+}
+
+#[cfg(not(unix))]
+fn restrict_permissions(_path: &Path, _mode: u32) -> std::io::Result<()> {
+    Ok(())
+}
+
+/// Returns the public key commitment of a secret key
+pub fn public_key_commitment(secret_key: &AuthSecretKey) -> Word {
+    match secret_key {
+        AuthSecretKey::RpoFalcon512(key) => key.public_key().into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use miden_objects::crypto::dsa::rpo_falcon512::SecretKey;
+
+    /// Keystore in an empty directory under the system temp directory, removed when dropped
+    struct TempKeyStore {
+        dir: PathBuf,
+        keystore: FileKeyStore,
+    }
+
+    impl TempKeyStore {
+        fn new(name: &str, password: &str) -> Self {
+            let dir = std::env::temp_dir()
+                .join(format!("miden-lending-keystore-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            let keystore = FileKeyStore::open(&dir, Some(password.to_string())).unwrap();
+            Self { dir, keystore }
+        }
+
+        fn reopen(&self, password: Option<&str>) -> FileKeyStore {
+            FileKeyStore::open(&self.dir, password.map(str::to_string)).unwrap()
+        }
+    }
+
+    impl Drop for TempKeyStore {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    fn account_id(byte: u8) -> AccountId {
+        AccountId(vec![byte; 15])
+    }
+
+    fn secret_key() -> AuthSecretKey {
+        AuthSecretKey::RpoFalcon512(SecretKey::new())
+    }
+
+    #[test]
+    fn test_keys_round_trip_encrypted() {
+        let store = TempKeyStore::new("round-trip", "password");
+        let key = secret_key();
+        store.keystore.add_key(&account_id(1), &key).unwrap();
+
+        let path = store.keystore.key_path(&account_id(1));
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(!content.contains(&hex::encode(key.to_bytes())), "key must be stored encrypted");
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+
+        let decrypted = store.reopen(Some("password")).get_key(&account_id(1)).unwrap();
+        assert_eq!(decrypted.to_bytes(), key.to_bytes());
+
+        let keys = store.keystore.list_keys().unwrap();
+        assert_eq!(keys.len(), 1);
+        assert_eq!(keys[0].public_key, hex::encode(public_key_commitment(&key).as_bytes()));
+    }
+
+    #[test]
+    fn test_wrong_password_fails() {
+        let store = TempKeyStore::new("wrong-password", "password");
+        store.keystore.add_key(&account_id(1), &secret_key()).unwrap();
+
+        let err = store.reopen(Some("other")).get_key(&account_id(1)).unwrap_err();
+        assert!(err.to_string().contains("wrong password"), "got: {:#}", err);
+
+        let err = store.reopen(None).get_key(&account_id(1)).unwrap_err();
+        assert!(err.to_string().contains("locked"), "got: {:#}", err);
+
+        // Keys are listed without the password
+        assert_eq!(store.reopen(None).list_keys().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_keys_are_not_overwritten() {
+        let store = TempKeyStore::new("overwrite", "password");
+        let key = secret_key();
+        store.keystore.add_key(&account_id(1), &key).unwrap();
+
+        let err = store.keystore.add_key(&account_id(1), &secret_key()).unwrap_err();
+        assert!(err.to_string().contains("already stored"), "got: {:#}", err);
+        assert_eq!(store.keystore.get_key(&account_id(1)).unwrap().to_bytes(), key.to_bytes());
+    }
+
+    #[test]
+    fn test_export_and_import() {
+        let source = TempKeyStore::new("export", "password");
+        let target = TempKeyStore::new("import", "other password");
+        let key = secret_key();
+        let public_key = public_key_commitment(&key);
+        source.keystore.add_key(&account_id(1), &key).unwrap();
+
+        let exported = source.keystore.export_key(&account_id(1)).unwrap();
+
+        // The key must match the account's public key
+        let other_public_key = public_key_commitment(&secret_key());
+        let err = target
+            .keystore
+            .import_key(&account_id(1), &exported, other_public_key, false)
+            .unwrap_err();
+        assert!(err.to_string().contains("does not match"), "got: {:#}", err);
+
+        target.keystore.import_key(&account_id(1), &exported, public_key, false).unwrap();
+        assert_eq!(target.keystore.get_key(&account_id(1)).unwrap().to_bytes(), key.to_bytes());
+
+        // An existing key is only replaced when forced
+        let err = target
+            .keystore
+            .import_key(&account_id(1), &exported, public_key, false)
+            .unwrap_err();
+        assert!(err.to_string().contains("already stored"), "got: {:#}", err);
+        target.keystore.import_key(&account_id(1), &exported, public_key, true).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_exported_key_files_are_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let store = TempKeyStore::new("export-file", "password");
+        store.keystore.add_key(&account_id(1), &secret_key()).unwrap();
+
+        let path = store.dir.join("exported.hex");
+        store.keystore.export_key_to_file(&account_id(1), &path).unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        assert_eq!(fs::read_to_string(&path).unwrap(), store.keystore.export_key(&account_id(1)).unwrap());
+
+        // An existing file is not overwritten
+        fs::write(&path, "other key").unwrap();
+        let err = store.keystore.export_key_to_file(&account_id(1), &path).unwrap_err();
+        assert!(err.to_string().contains("already exists"), "got: {:#}", err);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "other key");
+    }
+
+    #[test]
+    fn test_corrupt_key_files_are_skipped() {
+        let store = TempKeyStore::new("corrupt", "password");
+        store.keystore.add_key(&account_id(1), &secret_key()).unwrap();
+        fs::write(store.keystore.key_path(&account_id(2)), "not a key file").unwrap();
+
+        let keys = store.keystore.list_keys().unwrap();
+        assert_eq!(keys.len(), 1);
+        assert_eq!(keys[0].account_id, account_id(1).to_hex());
+    }
+
+    #[tokio::test]
+    async fn test_signs_with_the_matching_key() {
+        let store = TempKeyStore::new("signing", "password");
+        let key = SecretKey::new();
+        let public_key: Word = key.public_key().into();
+        store.keystore.add_key(&account_id(1), &AuthSecretKey::RpoFalcon512(key.clone())).unwrap();
+        store.keystore.add_key(&account_id(2), &secret_key()).unwrap();
+
+        let message = Word::from([1u32, 2, 3, 4]);
+        let signature = store
+            .keystore
+            .get_signature(public_key, &SigningInputs::Blind(message))
+            .await
+            .unwrap();
+
+        // Signatures end with the expanded public key of the signing key, before the challenge
+        let mut rng = RpoRandomCoin::new(Word::default());
+        let expected = get_falcon_signature(&key, message, &mut rng).unwrap();
+        assert_eq!(signature.len(), expected.len());
+        let key_range = signature.len() - 514..signature.len() - 2;
+        assert_eq!(signature[key_range.clone()], expected[key_range]);
+
+        let unknown = public_key_commitment(&secret_key());
+        let err = store
+            .keystore
+            .get_signature(unknown, &SigningInputs::Blind(message))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AuthenticationError::UnknownPublicKey(_)), "got: {:?}", err);
+    }
+}
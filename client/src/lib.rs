@@ -6,7 +6,11 @@ pub mod accounts;
 pub mod transactions;
 pub mod utils;
 pub mod miden_client;
+pub mod backend;
+pub mod keystore;
 pub mod components;
-
-#[cfg(any(feature = "testing", test))]
+pub mod fixed_point;
 pub mod errors;
+
+#[cfg(feature = "testing")]
+pub mod mock_chain;
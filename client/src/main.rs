@@ -4,22 +4,45 @@
 // This client provides an interface to interact with the Miden lending protocol.
 // It handles account creation, transaction building, and protocol interactions.
 
-use anyhow::Result;
-use clap::{Parser, Subcommand};
+use anyhow::{Context, Result};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+use std::path::PathBuf;
+use miden_objects::{
+    account::AccountId as MidenAccountId,
+    utils::{Deserializable, Serializable},
+};
 use tracing::{info, Level};
-use tracing_subscriber;
 
-mod config;
-mod accounts;
-mod transactions;
-mod utils;
-mod miden_client;
+use miden_lending_client::{
+    accounts,
+    components::{
+        lending_pool::{PriceSource, RateStrategy, ReserveConfig, RiskParameters},
+        price_note::PriceNote,
+    },
+    keystore::FileKeyStore,
+    config::{self, DeployedAsset, DeploymentInfo},
+    miden_client::{AccountId, LendingBackend, LendingClient},
+    transactions::{AssetPrice, TransactionBuilder},
+    utils::{
+        asset_id_to_name, basis_points_to_percentage, format_health_factor, format_index,
+        format_price, wad_to_percentage,
+    },
+};
 
 /// Miden Lending Protocol CLI
 #[derive(Parser)]
 #[command(name = "miden-lending")]
 #[command(about = "Miden Lending Protocol Client", long_about = None)]
 struct Cli {
+    /// Network profile to use instead of the active one
+    #[arg(long, global = true)]
+    profile: Option<String>,
+
+    /// Output format
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    output: OutputFormat,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -41,7 +64,19 @@ enum Commands {
     },
 
     /// Deploy the lending pool account
-    DeployPool,
+    DeployPool {
+        /// Faucet issuing the USDC reserve's asset (hex account ID)
+        #[arg(long)]
+        usdc_faucet: String,
+
+        /// Faucet issuing the DAI reserve's asset (hex account ID)
+        #[arg(long)]
+        dai_faucet: String,
+
+        /// Faucet issuing the WETH reserve's asset (hex account ID)
+        #[arg(long)]
+        weth_faucet: String,
+    },
 
     /// Deploy the price oracle account
     DeployOracle,
@@ -52,8 +87,8 @@ enum Commands {
         #[arg(short, long)]
         asset_id: u32,
 
-        /// Amount to deposit
-        #[arg(short, long)]
+        /// Amount to deposit, in the asset's smallest unit (at most 2^63 - 1 units per balance)
+        #[arg(long)]
         amount: u64,
     },
 
@@ -64,7 +99,7 @@ enum Commands {
         asset_id: u32,
 
         /// Amount to withdraw
-        #[arg(short, long)]
+        #[arg(long)]
         amount: u64,
     },
 
@@ -74,8 +109,19 @@ enum Commands {
         #[arg(short, long)]
         asset_id: u32,
 
-        /// Amount to supply
+        /// Amount to supply, in the asset's smallest unit (at most 2^63 - 1 units per balance)
+        #[arg(long)]
+        amount: u64,
+    },
+
+    /// Withdraw collateral, keeping the health factor above 1.0
+    WithdrawCollateral {
+        /// Asset ID (1=USDC, 2=DAI, 3=WETH)
         #[arg(short, long)]
+        asset_id: u32,
+
+        /// Amount to withdraw
+        #[arg(long)]
         amount: u64,
     },
 
@@ -86,7 +132,7 @@ enum Commands {
         asset_id: u32,
 
         /// Amount to borrow
-        #[arg(short, long)]
+        #[arg(long)]
         amount: u64,
     },
 
@@ -97,7 +143,7 @@ enum Commands {
         asset_id: u32,
 
         /// Amount to repay
-        #[arg(short, long)]
+        #[arg(long)]
         amount: u64,
     },
 
@@ -116,9 +162,13 @@ enum Commands {
         /// Asset ID (1=USDC, 2=DAI, 3=WETH, 4=WBTC)
         #[arg(short, long)]
         asset_id: u32,
+
+        /// Get the time-weighted average price instead of the latest one
+        #[arg(long)]
+        twap: bool,
     },
 
-    /// Update asset price in oracle (admin only)
+    /// Update asset price in oracle, signed by the user account (authorized updaters only)
     UpdatePrice {
         /// Asset ID (1=USDC, 2=DAI, 3=WETH, 4=WBTC)
         #[arg(short, long)]
@@ -129,64 +179,562 @@ enum Commands {
         price: u64,
     },
 
+    /// Sign an asset price off-chain with the user account's key, as a price note any account can
+    /// relay to the oracle (authorized updaters only)
+    SignPrice {
+        /// Asset ID (1=USDC, 2=DAI, 3=WETH, 4=WBTC)
+        #[arg(short, long)]
+        asset_id: u32,
+
+        /// Signed price (8 decimals precision)
+        #[arg(short, long)]
+        price: u64,
+
+        /// Time the price was observed at, in seconds since the Unix epoch (defaults to now)
+        #[arg(short, long)]
+        timestamp: Option<u64>,
+
+        /// File to write the price note to
+        #[arg(short, long)]
+        file: PathBuf,
+    },
+
+    /// Relay signed price notes to the oracle
+    RelayPriceNotes {
+        /// Files containing the price notes
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+    },
+
+    /// Show the price each oracle updater submitted for an asset
+    PriceSubmissions {
+        /// Asset ID (1=USDC, 2=DAI, 3=WETH, 4=WBTC)
+        #[arg(short, long)]
+        asset_id: u32,
+    },
+
+    /// Set how many fresh submissions are needed to report an asset's price (admin only)
+    SetPriceQuorum {
+        /// Asset ID (1=USDC, 2=DAI, 3=WETH, 4=WBTC)
+        #[arg(short, long)]
+        asset_id: u32,
+
+        /// Number of fresh submissions
+        #[arg(short, long)]
+        quorum: u64,
+    },
+
+    /// Add an oracle price feed for a new asset, with its initial price (admin only)
+    AddPriceFeed {
+        /// Asset ID of the new feed
+        #[arg(short, long)]
+        asset_id: u32,
+
+        /// Initial price (8 decimals precision)
+        #[arg(short, long)]
+        price: u64,
+    },
+
+    /// Set how long an asset's oracle price stays valid (admin only)
+    SetMaxPriceAge {
+        /// Asset ID (1=USDC, 2=DAI, 3=WETH, 4=WBTC)
+        #[arg(short, long)]
+        asset_id: u32,
+
+        /// Maximum price age in seconds
+        #[arg(short, long)]
+        max_age: u64,
+    },
+
+    /// Set the time window of an asset's time-weighted average price (admin only)
+    SetTwapWindow {
+        /// Asset ID (1=USDC, 2=DAI, 3=WETH, 4=WBTC)
+        #[arg(short, long)]
+        asset_id: u32,
+
+        /// TWAP window in seconds
+        #[arg(short, long)]
+        window: u64,
+    },
+
+    /// Configure the circuit breaker pausing an asset's price feed on large moves (admin only)
+    SetCircuitBreaker {
+        /// Asset ID (1=USDC, 2=DAI, 3=WETH, 4=WBTC)
+        #[arg(short, long)]
+        asset_id: u32,
+
+        /// Maximum price move in basis points, 0 to disable the circuit breaker
+        #[arg(short, long)]
+        max_deviation: u64,
+
+        /// Interval the price move is measured over, in seconds
+        #[arg(short, long, default_value_t = 3600)]
+        interval: u64,
+    },
+
+    /// Resume an asset's price feed paused by its circuit breaker (admin only)
+    ResumePriceFeed {
+        /// Asset ID (1=USDC, 2=DAI, 3=WETH, 4=WBTC)
+        #[arg(short, long)]
+        asset_id: u32,
+    },
+
+    /// Manage the accounts authorized to update oracle prices (admin only)
+    Updaters {
+        #[command(subcommand)]
+        command: UpdaterCommands,
+    },
+
+    /// List a new asset in the lending pool (admin only)
+    AddReserve {
+        /// Asset ID, as used by the price oracle
+        #[arg(short, long)]
+        asset_id: u32,
+
+        /// Number of decimals of the asset
+        #[arg(short, long)]
+        decimals: u32,
+
+        /// Fungible faucet issuing the asset (hex account ID)
+        #[arg(short, long)]
+        faucet_id: String,
+
+        /// Symbol to record the asset under in the config and deployment manifest
+        #[arg(short, long)]
+        symbol: Option<String>,
+
+        /// Interest rate strategy, defaulting to the volatile asset curve
+        #[command(flatten)]
+        strategy: RateStrategyArgs,
+
+        /// Collateral risk parameters, defaulting to the volatile asset ones
+        #[command(flatten)]
+        risk: RiskParameterArgs,
+    },
+
+    /// Tune the interest rate strategy of a listed asset (admin only)
+    SetRateStrategy {
+        /// Asset ID of the reserve
+        #[arg(short, long)]
+        asset_id: u32,
+
+        /// Strategy parameters to change, the others are kept
+        #[command(flatten)]
+        strategy: RateStrategyArgs,
+    },
+
+    /// List the assets listed in the lending pool
+    Reserves,
+
+    /// Select the oracle prices liquidations value positions at: spot or twap (admin only)
+    SetLiquidationPriceSource {
+        /// Price source (spot or twap)
+        source: PriceSource,
+    },
+
+    /// Show and withdraw the reserves accrued to the protocol treasury
+    Treasury {
+        #[command(subcommand)]
+        command: TreasuryCommands,
+    },
+
     /// Calculate health factor
     HealthFactor,
+
+    /// Liquidate an undercollateralized position
+    Liquidate {
+        /// Borrower account ID (hex)
+        #[arg(short, long)]
+        borrower: String,
+
+        /// Collateral asset to seize (1=USDC, 2=DAI, 3=WETH)
+        #[arg(short, long)]
+        collateral_asset_id: u32,
+
+        /// Debt asset to repay (1=USDC, 2=DAI, 3=WETH)
+        #[arg(short, long)]
+        debt_asset_id: u32,
+
+        /// Amount of debt to cover
+        #[arg(long)]
+        amount: u64,
+    },
+
+    /// List known user positions, riskiest first
+    Positions {
+        /// Only show positions with health factor below 1.0
+        #[arg(long)]
+        unhealthy: bool,
+    },
+
+    /// Manage network profiles
+    Profile {
+        #[command(subcommand)]
+        command: ProfileCommands,
+    },
+
+    /// Manage account keys
+    Keys {
+        #[command(subcommand)]
+        command: KeyCommands,
+    },
+}
+
+/// Interest rate strategy parameters, in basis points
+#[derive(Args)]
+struct RateStrategyArgs {
+    /// Borrow rate at zero utilization
+    #[arg(long)]
+    base_rate: Option<u64>,
+
+    /// Borrow rate increase up to the optimal utilization
+    #[arg(long)]
+    slope1: Option<u64>,
+
+    /// Borrow rate increase from the optimal utilization to 100%
+    #[arg(long)]
+    slope2: Option<u64>,
+
+    /// Utilization at which the second slope starts
+    #[arg(long)]
+    optimal_utilization: Option<u64>,
+
+    /// Share of borrow interest kept by the protocol
+    #[arg(long)]
+    reserve_factor: Option<u64>,
+}
+
+impl RateStrategyArgs {
+    /// Returns `strategy` with the given parameters replaced
+    fn apply(&self, strategy: RateStrategy) -> RateStrategy {
+        RateStrategy {
+            base_rate: self.base_rate.unwrap_or(strategy.base_rate),
+            slope1: self.slope1.unwrap_or(strategy.slope1),
+            slope2: self.slope2.unwrap_or(strategy.slope2),
+            optimal_utilization: self.optimal_utilization.unwrap_or(strategy.optimal_utilization),
+            reserve_factor: self.reserve_factor.unwrap_or(strategy.reserve_factor),
+        }
+    }
+}
+
+/// Collateral risk parameters, in basis points
+#[derive(Args)]
+struct RiskParameterArgs {
+    /// Share of the collateral value that can be borrowed against
+    #[arg(long)]
+    ltv: Option<u64>,
+
+    /// Share of the collateral value at which positions become liquidatable
+    #[arg(long)]
+    liquidation_threshold: Option<u64>,
+
+    /// Collateral value liquidators receive per unit of debt repaid (10500 = 5% bonus)
+    #[arg(long)]
+    liquidation_bonus: Option<u64>,
+}
+
+impl RiskParameterArgs {
+    /// Returns `risk` with the given parameters replaced
+    fn apply(&self, risk: RiskParameters) -> RiskParameters {
+        RiskParameters {
+            ltv: self.ltv.unwrap_or(risk.ltv),
+            liquidation_threshold: self.liquidation_threshold.unwrap_or(risk.liquidation_threshold),
+            liquidation_bonus: self.liquidation_bonus.unwrap_or(risk.liquidation_bonus),
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum TreasuryCommands {
+    /// Show the reserves accrued to the treasury for each listed asset
+    Show,
+
+    /// Pay out accrued reserves to the treasury account (admin only)
+    Withdraw {
+        /// Asset ID of the reserve
+        #[arg(short, long)]
+        asset_id: u32,
+
+        /// Amount to withdraw, defaulting to everything accrued
+        #[arg(long)]
+        amount: Option<u64>,
+
+        /// Treasury account ID (hex), saved to the configuration for later withdrawals
+        #[arg(long)]
+        to: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum UpdaterCommands {
+    /// Authorize an account to update prices
+    Add {
+        /// Account ID (hex)
+        account_id: String,
+    },
+
+    /// Revoke the authorization of an account
+    Remove {
+        /// Account ID (hex)
+        account_id: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum KeyCommands {
+    /// List stored keys
+    List,
+
+    /// Export the secret key of an account (unencrypted)
+    Export {
+        /// Account ID (hex)
+        account_id: String,
+
+        /// New file to write the key to instead of stdout, readable by the owner only
+        #[arg(short, long)]
+        file: Option<PathBuf>,
+    },
+
+    /// Import the secret key of an account, which must match the account's public key
+    Import {
+        /// Account ID (hex)
+        account_id: String,
+
+        /// File containing the exported key
+        file: PathBuf,
+
+        /// Replace the key already stored for the account
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ProfileCommands {
+    /// List all profiles
+    List,
+
+    /// Add a new profile
+    Add {
+        /// Profile name
+        name: String,
+
+        /// RPC endpoint for Miden node
+        #[arg(short, long)]
+        rpc: String,
+
+        /// Local store directory, relative to the config directory (defaults to store/<name>)
+        #[arg(short, long)]
+        storage_path: Option<PathBuf>,
+    },
+
+    /// Make a profile the active one
+    Switch {
+        /// Profile name
+        name: String,
+    },
+}
+
+/// Output format of command results
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// Human-readable text
+    Text,
+    /// A single JSON object per command
+    Json,
+}
+
+/// Result of a command, printed as text lines or collected into one JSON object
+struct Output {
+    format: OutputFormat,
+    fields: serde_json::Map<String, serde_json::Value>,
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
+impl Output {
+    fn new(format: OutputFormat) -> Self {
+        Self {
+            format,
+            fields: serde_json::Map::new(),
+        }
+    }
+
+    /// Prints a line of text output
+    fn text(&self, line: impl std::fmt::Display) {
+        if self.format == OutputFormat::Text {
+            println!("{}", line);
+        }
+    }
+
+    /// Adds a field to the JSON output
+    fn field(&mut self, key: &str, value: impl Serialize) {
+        if self.format == OutputFormat::Json {
+            let value = serde_json::to_value(value).unwrap_or(serde_json::Value::Null);
+            self.fields.insert(key.to_string(), value);
+        }
+    }
+
+    /// Adds the fields of a submitted transaction to the JSON output
+    fn transaction(&mut self, tx_id: &str, asset_id: u32, amount: u64) {
+        self.field("tx_id", tx_id);
+        self.field("asset_id", asset_id);
+        self.field("amount", amount);
+    }
+
+    /// Prints the JSON output of a successful command
+    fn finish(self) {
+        if self.format == OutputFormat::Json {
+            let mut object = serde_json::Map::new();
+            object.insert("success".to_string(), true.into());
+            object.extend(self.fields);
+            println!("{}", serde_json::Value::Object(object));
+        }
+    }
+
+    /// Reports a failed command
+    fn fail(self, err: &anyhow::Error) {
+        match self.format {
+            OutputFormat::Text => eprintln!("❌ {:#}", err),
+            OutputFormat::Json => {
+                println!("{}", serde_json::json!({ "success": false, "error": format!("{:#}", err) }))
+            }
+        }
+    }
+}
+
+/// Stack size of the thread commands run on
+///
+/// Executing and proving transactions needs more than the main thread's default stack in debug
+/// builds.
+const MAIN_STACK_SIZE: usize = 32 * 1024 * 1024;
+
+fn main() {
+    let cli_thread = std::thread::Builder::new()
+        .stack_size(MAIN_STACK_SIZE)
+        .spawn(|| {
+            tokio::runtime::Builder::new_multi_thread()
+                .enable_all()
+                .build()
+                .expect("Failed to build the tokio runtime")
+                .block_on(async_main())
+        })
+        .expect("Failed to spawn the client thread");
+
+    if cli_thread.join().is_err() {
+        std::process::exit(1);
+    }
+}
+
+async fn async_main() {
     // Initialize tracing
+    // Logs go to stderr so command output can be scripted
     tracing_subscriber::fmt()
         .with_max_level(Level::INFO)
+        .with_writer(std::io::stderr)
         .init();
 
     info!("Miden Lending Protocol Client v0.1.0");
 
     let cli = Cli::parse();
+    let mut out = Output::new(cli.output);
+
+    match run(cli, &mut out).await {
+        Ok(()) => out.finish(),
+        Err(err) => {
+            out.fail(&err);
+            std::process::exit(1);
+        }
+    }
+}
 
-    use config::Config;
+async fn run(cli: Cli, out: &mut Output) -> Result<()> {
+    use config::{Config, Profiles};
     use accounts::AccountManager;
-    use std::path::PathBuf;
+
+    // Profiles are managed without loading a single profile
+    let command = match cli.command {
+        Commands::Profile { command } => return run_profile_command(command, out),
+        command => command,
+    };
 
     // Load configuration
-    let mut config = Config::load()?;
+    let mut config = match (&command, &cli.profile) {
+        // Init creates the selected profile if it does not exist yet
+        (Commands::Init { .. }, Some(profile)) => Profiles::load()?.get_or_new(profile),
+        _ => Config::load_profile(cli.profile.as_deref())?,
+    };
+    info!("Using profile `{}`", config.profile);
 
-    match cli.command {
+    match command {
         Commands::Init { rpc } => {
             info!("Initializing lending protocol with RPC: {}", rpc);
             config.rpc_endpoint = rpc;
 
             // Create storage directory
-            std::fs::create_dir_all(&config.storage_path)?;
+            std::fs::create_dir_all(config.storage_dir())?;
             config.save()?;
 
-            println!("✅ Protocol initialized");
-            println!("   RPC: {}", config.rpc_endpoint);
-            println!("   Storage: {:?}", config.storage_path);
+            out.text("✅ Protocol initialized");
+            out.text(format!("   Profile: {}", config.profile));
+            out.text(format!("   RPC: {}", config.rpc_endpoint));
+            out.text(format!("   Storage: {:?}", config.storage_dir()));
+            out.field("profile", &config.profile);
+            out.field("rpc_endpoint", &config.rpc_endpoint);
+            out.field("storage_path", config.storage_dir());
         }
 
         Commands::CreateAccount { storage_mode } => {
             info!("Creating user lending account ({})", storage_mode);
 
+            // User accounts reference the deployed pool, which holds their position
+            let pool_id = configured_account_id(&config.lending_pool_id(), "lending_pool_account_id")?;
+
             let mut account_manager = AccountManager::new(
                 &config.rpc_endpoint,
-                &config.storage_path
+                &config.storage_dir()
             ).await?;
 
-            let result = account_manager.create_user_account(&storage_mode).await?;
-            println!("✅ {}", result);
+            let account_id = account_manager
+                .create_user_account(&storage_mode, &pool_id)
+                .await?
+                .to_hex();
+            out.text(format!("✅ User account created: {}", account_id));
+            out.field("account_id", &account_id);
+
+            // The first account becomes the active user, later ones are only tracked
+            if config.user_account_id.is_none() {
+                config.user_account_id = Some(account_id.clone());
+                out.text("   Set as active user account");
+            }
+            out.field("active", config.user_account_id.as_ref() == Some(&account_id));
+
+            // Remember the account so its position shows up in `positions`
+            config.known_user_accounts.push(account_id);
+            config.save()?;
         }
 
-        Commands::DeployPool => {
+        Commands::DeployPool { usdc_faucet, dai_faucet, weth_faucet } => {
             info!("Deploying lending pool account");
 
+            // The pool values positions with the deployed oracle
+            let oracle_id = configured_account_id(&config.price_oracle_id(), "price_oracle_account_id")?;
+            let faucet_ids = [
+                parse_account_id(&usdc_faucet)?,
+                parse_account_id(&dai_faucet)?,
+                parse_account_id(&weth_faucet)?,
+            ];
+
             let mut account_manager = AccountManager::new(
                 &config.rpc_endpoint,
-                &config.storage_path
+                &config.storage_dir()
             ).await?;
 
-            let result = account_manager.deploy_lending_pool().await?;
-            println!("✅ {}", result);
+            let pool_id = account_manager.deploy_lending_pool(&oracle_id, &faucet_ids).await?.to_hex();
+            out.text(format!("✅ Lending pool deployed: {}", pool_id));
+            out.field("lending_pool_id", &pool_id);
+
+            config.lending_pool_account_id = Some(pool_id.clone());
+            config.save()?;
+            update_deployment_info(&config, |info| info.lending_pool_id = pool_id)?;
         }
 
         Commands::DeployOracle => {
@@ -194,78 +742,755 @@ async fn main() -> Result<()> {
 
             let mut account_manager = AccountManager::new(
                 &config.rpc_endpoint,
-                &config.storage_path
+                &config.storage_dir()
             ).await?;
 
-            let result = account_manager.deploy_price_oracle().await?;
-            println!("✅ {}", result);
+            let oracle_id = account_manager.deploy_price_oracle().await?.to_hex();
+            out.text(format!("✅ Price oracle deployed: {}", oracle_id));
+            out.field("price_oracle_id", &oracle_id);
+
+            config.price_oracle_account_id = Some(oracle_id.clone());
+            config.save()?;
+            update_deployment_info(&config, |info| info.price_oracle_id = oracle_id)?;
         }
 
         Commands::Deposit { asset_id, amount } => {
             info!("Depositing {} units of asset {}", amount, asset_id);
-            println!("⚠️  Transaction execution requires full Miden client integration");
-            println!("   Asset: {}, Amount: {}", asset_id, amount);
-            println!("   This will be enabled after Miden API integration");
+
+            let user_id = user_account_id(&config)?;
+            let tx_id = transaction_builder(&config).await?
+                .deposit(&user_id, asset_id.into(), amount)
+                .await?;
+
+            out.text(format!("✅ Deposited {} {}", amount, asset_id_to_name(asset_id)));
+            out.text(format!("   TX ID: {}", tx_id));
+            out.transaction(&tx_id, asset_id, amount);
         }
 
         Commands::Withdraw { asset_id, amount } => {
             info!("Withdrawing {} units of asset {}", amount, asset_id);
-            println!("⚠️  Transaction execution requires full Miden client integration");
-            println!("   Asset: {}, Amount: {}", asset_id, amount);
-            println!("   This will be enabled after Miden API integration");
+
+            let user_id = user_account_id(&config)?;
+            let tx_id = transaction_builder(&config).await?
+                .withdraw(&user_id, asset_id.into(), amount)
+                .await?;
+
+            out.text(format!("✅ Withdrew {} {}", amount, asset_id_to_name(asset_id)));
+            out.text(format!("   TX ID: {}", tx_id));
+            out.transaction(&tx_id, asset_id, amount);
         }
 
         Commands::SupplyCollateral { asset_id, amount } => {
             info!("Supplying {} units of asset {} as collateral", amount, asset_id);
-            println!("⚠️  Transaction execution requires full Miden client integration");
-            println!("   Asset: {}, Amount: {}", asset_id, amount);
-            println!("   This will be enabled after Miden API integration");
+
+            let user_id = user_account_id(&config)?;
+            let tx_id = transaction_builder(&config).await?
+                .supply_collateral(&user_id, asset_id.into(), amount)
+                .await?;
+
+            out.text(format!("✅ Supplied {} {} as collateral", amount, asset_id_to_name(asset_id)));
+            out.text(format!("   TX ID: {}", tx_id));
+            out.transaction(&tx_id, asset_id, amount);
+        }
+
+        Commands::WithdrawCollateral { asset_id, amount } => {
+            info!("Withdrawing {} units of asset {} of collateral", amount, asset_id);
+
+            let user_id = user_account_id(&config)?;
+            let tx_id = transaction_builder(&config).await?
+                .withdraw_collateral(&user_id, asset_id.into(), amount)
+                .await?;
+
+            out.text(format!("✅ Withdrew {} {} of collateral", amount, asset_id_to_name(asset_id)));
+            out.text(format!("   TX ID: {}", tx_id));
+            out.transaction(&tx_id, asset_id, amount);
         }
 
         Commands::Borrow { asset_id, amount } => {
             info!("Borrowing {} units of asset {}", amount, asset_id);
-            println!("⚠️  Transaction execution requires full Miden client integration");
-            println!("   Asset: {}, Amount: {}", asset_id, amount);
-            println!("   This will be enabled after Miden API integration");
+
+            let user_id = user_account_id(&config)?;
+            let tx_id = transaction_builder(&config).await?
+                .borrow(&user_id, asset_id.into(), amount)
+                .await?;
+
+            out.text(format!("✅ Borrowed {} {}", amount, asset_id_to_name(asset_id)));
+            out.text(format!("   TX ID: {}", tx_id));
+            out.transaction(&tx_id, asset_id, amount);
         }
 
         Commands::Repay { asset_id, amount } => {
             info!("Repaying {} units of asset {}", amount, asset_id);
-            println!("⚠️  Transaction execution requires full Miden client integration");
-            println!("   Asset: {}, Amount: {}", asset_id, amount);
-            println!("   This will be enabled after Miden API integration");
+
+            let user_id = user_account_id(&config)?;
+            let tx_id = transaction_builder(&config).await?
+                .repay(&user_id, asset_id.into(), amount)
+                .await?;
+
+            out.text(format!("✅ Repaid {} {}", amount, asset_id_to_name(asset_id)));
+            out.text(format!("   TX ID: {}", tx_id));
+            out.transaction(&tx_id, asset_id, amount);
         }
 
         Commands::GetAccountInfo => {
             info!("Fetching account information");
-            println!("⚠️  Account queries require full Miden client integration");
-            println!("   This will be enabled after Miden API integration");
+
+            let user_id = user_account_id(&config)?;
+            let account_info = transaction_builder(&config).await?
+                .get_account_info(&user_id)
+                .await?;
+            out.text(format!("📋 Account {}", account_info.account_id));
+            for reserve in &account_info.reserves {
+                let asset_id = u32::try_from(reserve.asset_id).unwrap_or(u32::MAX);
+                out.text(format!("   {}: collateral {}, debt {} (scaled), supplied {} (scaled)",
+                         asset_id_to_name(asset_id),
+                         reserve.collateral,
+                         reserve.scaled_debt,
+                         reserve.scaled_supply));
+            }
+            out.text(format!("   Health factor: {}", format_health_factor(account_info.health_factor)));
+            out.field("account", &account_info);
         }
 
         Commands::GetReserveData { asset_id } => {
             info!("Fetching reserve data for asset {}", asset_id);
-            println!("⚠️  Reserve queries require full Miden client integration");
-            println!("   This will be enabled after Miden API integration");
+
+            let reserve_data = transaction_builder(&config).await?
+                .get_reserve_data(asset_id.into())
+                .await?;
+
+            out.text(format!("📊 {} reserve", asset_id_to_name(asset_id)));
+            out.text(format!("   Total liquidity:     {}", reserve_data.total_liquidity));
+            out.text(format!("   Total borrowed:      {}", reserve_data.total_borrowed));
+            out.text(format!("   Available liquidity: {}", reserve_data.available_liquidity()));
+            out.text(format!("   Utilization:         {:.2}%", reserve_data.utilization_rate() * 100.0));
+            out.text(format!("   Supply rate:         {}", wad_to_percentage(reserve_data.liquidity_rate)));
+            out.text(format!("   Borrow rate:         {}", wad_to_percentage(reserve_data.borrow_rate)));
+            out.text(format!("   Liquidity index:     {}", format_index(reserve_data.liquidity_index)));
+            out.text(format!("   Borrow index:        {}", format_index(reserve_data.borrow_index)));
+            out.field("reserve", &reserve_data);
+            out.field("available_liquidity", reserve_data.available_liquidity());
+            out.field("utilization_rate", reserve_data.utilization_rate());
         }
 
-        Commands::GetPrice { asset_id } => {
+        Commands::GetPrice { asset_id, twap } => {
             info!("Fetching price for asset {}", asset_id);
-            println!("⚠️  Price queries require full Miden client integration");
-            println!("   This will be enabled after Miden API integration");
+
+            let mut tx_builder = transaction_builder(&config).await?;
+            let (price, label) = if twap {
+                (tx_builder.get_twap(asset_id.into()).await?, " (TWAP)")
+            } else {
+                (tx_builder.get_price(asset_id.into()).await?, "")
+            };
+
+            out.text(format!("📊 {}{}: {}", asset_id_to_name(asset_id), label, format_price(price)));
+            out.field("price", AssetPrice { asset_id: asset_id.into(), price });
         }
 
         Commands::UpdatePrice { asset_id, price } => {
             info!("Updating price for asset {} to {}", asset_id, price);
-            println!("⚠️  Price updates require full Miden client integration");
-            println!("   This will be enabled after Miden API integration");
+
+            let admin_id = user_account_id(&config)?;
+            let tx_id = transaction_builder(&config).await?
+                .update_price(&admin_id, asset_id.into(), price)
+                .await?;
+
+            out.text(format!("✅ Updated {} price to {}", asset_id_to_name(asset_id), format_price(price)));
+            out.text(format!("   TX ID: {}", tx_id));
+            out.field("tx_id", &tx_id);
+            out.field("price", AssetPrice { asset_id: asset_id.into(), price });
+
+            let price = format!("{}.{:02}", price / 100_000_000, price % 100_000_000 / 1_000_000);
+            if let Some(asset) = config.assets.values_mut().find(|asset| asset.id == asset_id) {
+                asset.price = price.clone();
+                config.save()?;
+            }
+            update_deployment_info(&config, |info| {
+                if let Some(asset) = info.assets.values_mut().find(|asset| asset.id == asset_id) {
+                    asset.price = price;
+                }
+            })?;
+        }
+
+        Commands::SignPrice { asset_id, price, timestamp, file } => {
+            let timestamp = timestamp.unwrap_or_else(|| chrono::Utc::now().timestamp() as u64);
+            info!("Signing price {} for asset {} at {}", price, asset_id, timestamp);
+
+            let updater_id = user_account_id(&config)?;
+            let price_note = transaction_builder(&config).await?
+                .sign_price(&updater_id, asset_id.into(), price, timestamp)
+                .await?;
+            std::fs::write(&file, hex::encode(price_note.to_bytes()))?;
+
+            out.text(format!(
+                "✅ Signed {} price {} at {}, written to {:?}",
+                asset_id_to_name(asset_id), format_price(price), timestamp, file
+            ));
+            out.field("path", &file);
+            out.field("note_id", price_note.note().id().to_hex());
+            out.field("timestamp", timestamp);
+            out.field("price", AssetPrice { asset_id: asset_id.into(), price });
+        }
+
+        Commands::RelayPriceNotes { files } => {
+            info!("Relaying {} price notes", files.len());
+
+            let price_notes = files
+                .iter()
+                .map(|file| {
+                    let bytes = std::fs::read_to_string(file)
+                        .with_context(|| format!("Failed to read {:?}", file))
+                        .and_then(|note| hex::decode(note.trim()).context("Price note is not valid hex"))?;
+                    PriceNote::read_from_bytes(&bytes)
+                        .with_context(|| format!("{:?} does not contain a price note", file))
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            let tx_id = transaction_builder(&config).await?
+                .relay_price_notes(&price_notes)
+                .await?;
+
+            out.text(format!("✅ Relayed {} signed prices to the oracle", price_notes.len()));
+            for price_note in &price_notes {
+                out.text(format!(
+                    "   {}: {} at {}",
+                    asset_id_to_name(price_note.asset_id() as u32),
+                    format_price(price_note.price()),
+                    price_note.timestamp()
+                ));
+            }
+            out.text(format!("   TX ID: {}", tx_id));
+            out.field("tx_id", &tx_id);
+        }
+
+        Commands::AddReserve { asset_id, decimals, faucet_id, symbol, strategy, risk } => {
+            info!("Listing asset {} with {} decimals", asset_id, decimals);
+
+            let reserve = ReserveConfig::new(
+                asset_id.into(),
+                MidenAccountId::try_from(&parse_account_id(&faucet_id)?)?,
+                decimals,
+                strategy.apply(RateStrategy::volatile()),
+                risk.apply(RiskParameters::volatile()),
+            );
+            let mut tx_builder = transaction_builder(&config).await?;
+            let tx_id = tx_builder.add_reserve(reserve).await?;
+
+            out.text(format!("✅ Listed asset {} with {} decimals", asset_id, decimals));
+            out.text(format!("   TX ID: {}", tx_id));
+            out.field("tx_id", &tx_id);
+            out.field("reserve", reserve);
+
+            if let Some(symbol) = symbol {
+                // Assets without an oracle price yet are recorded at zero
+                let price = tx_builder.get_price(asset_id.into()).await.unwrap_or(0);
+                let asset = DeployedAsset {
+                    id: asset_id,
+                    price: format!("{}.{:02}", price / 100_000_000, price % 100_000_000 / 1_000_000),
+                    decimals,
+                    faucet_id: Some(faucet_id),
+                };
+
+                config.assets.insert(symbol.clone(), asset.clone());
+                config.save()?;
+                update_deployment_info(&config, |info| {
+                    info.assets.insert(symbol, asset);
+                })?;
+            }
+        }
+
+        Commands::SetRateStrategy { asset_id, strategy } => {
+            info!("Setting rate strategy of asset {}", asset_id);
+
+            let mut tx_builder = transaction_builder(&config).await?;
+            let strategy = strategy.apply(tx_builder.get_rate_strategy(asset_id.into()).await?);
+            let tx_id = tx_builder.set_rate_strategy(asset_id.into(), strategy).await?;
+
+            out.text(format!("✅ Updated rate strategy of {}", asset_id_to_name(asset_id)));
+            out.text(format!("   TX ID: {}", tx_id));
+            out.field("tx_id", &tx_id);
+            out.field("strategy", strategy);
+        }
+
+        Commands::SetLiquidationPriceSource { source } => {
+            info!("Setting liquidation price source to {}", source);
+
+            let tx_id = transaction_builder(&config).await?
+                .set_liquidation_price_source(source)
+                .await?;
+
+            out.text(format!("✅ Liquidations now value positions at {} prices", source));
+            out.text(format!("   TX ID: {}", tx_id));
+            out.field("tx_id", &tx_id);
+            out.field("price_source", source);
+        }
+
+        Commands::Reserves => {
+            info!("Fetching listed reserves");
+
+            let reserves = transaction_builder(&config).await?.get_reserves().await?;
+
+            out.text(format!(
+                "{:<10} {:<8} {:>8} {:>8} {:>8} {:>8} {:>8} {:>8} {:>8} {:>8} {:>8}",
+                "ASSET ID", "SYMBOL", "DECIMALS", "BASE", "SLOPE1", "SLOPE2", "OPTIMAL", "RESERVE",
+                "LTV", "LT", "BONUS"
+            ));
+            for reserve in &reserves {
+                let symbol = config
+                    .assets
+                    .iter()
+                    .find(|(_, asset)| u64::from(asset.id) == reserve.asset_id)
+                    .map(|(symbol, _)| symbol.as_str())
+                    .unwrap_or("-");
+                let strategy = &reserve.strategy;
+                let risk = &reserve.risk;
+                out.text(format!(
+                    "{:<10} {:<8} {:>8} {:>8} {:>8} {:>8} {:>8} {:>8} {:>8} {:>8} {:>8}",
+                    reserve.asset_id,
+                    symbol,
+                    reserve.decimals,
+                    basis_points_to_percentage(strategy.base_rate),
+                    basis_points_to_percentage(strategy.slope1),
+                    basis_points_to_percentage(strategy.slope2),
+                    basis_points_to_percentage(strategy.optimal_utilization),
+                    basis_points_to_percentage(strategy.reserve_factor),
+                    basis_points_to_percentage(risk.ltv),
+                    basis_points_to_percentage(risk.liquidation_threshold),
+                    basis_points_to_percentage(risk.liquidation_bonus),
+                ));
+            }
+            out.field("reserves", &reserves);
+        }
+
+        Commands::PriceSubmissions { asset_id } => {
+            info!("Fetching price submissions for asset {}", asset_id);
+
+            let mut tx_builder = transaction_builder(&config).await?;
+            let parameters = tx_builder.get_price_parameters(asset_id.into()).await?;
+            let submissions = tx_builder.get_price_submissions(asset_id.into()).await?;
+
+            let num_fresh = submissions.iter().filter(|submission| submission.is_fresh).count();
+            let num_updaters = submissions.iter().filter(|submission| !submission.is_initial).count();
+            out.text(format!(
+                "📊 {} submissions: {} fresh of {} updaters (quorum {}, max age {}s)",
+                asset_id_to_name(asset_id),
+                num_fresh,
+                num_updaters,
+                parameters.quorum,
+                parameters.max_age
+            ));
+            if parameters.is_paused {
+                out.text("⚠️  Feed paused by its circuit breaker, reads fail until it is resumed".to_string());
+            }
+            out.text(format!("{:<20} {:>16} {:>12} {:>6}", "UPDATER", "PRICE", "TIMESTAMP", "FRESH"));
+            for submission in &submissions {
+                let price = if submission.timestamp == 0 { "-".to_string() } else { format_price(submission.price) };
+                let updater = if submission.is_initial { "admin (initial)" } else { &submission.updater_key[..18] };
+                out.text(format!(
+                    "{:<20} {:>16} {:>12} {:>6}",
+                    updater,
+                    price,
+                    submission.timestamp,
+                    if submission.is_fresh { "yes" } else { "no" }
+                ));
+            }
+            out.field("quorum", parameters.quorum);
+            out.field("max_age", parameters.max_age);
+            out.field("is_paused", parameters.is_paused);
+            out.field("submissions", &submissions);
+        }
+
+        Commands::SetPriceQuorum { asset_id, quorum } => {
+            info!("Setting price quorum of asset {} to {}", asset_id, quorum);
+
+            let tx_id = transaction_builder(&config).await?
+                .set_price_quorum(asset_id.into(), quorum)
+                .await?;
+
+            out.text(format!("✅ {} prices now need {} fresh submissions", asset_id_to_name(asset_id), quorum));
+            out.text(format!("   TX ID: {}", tx_id));
+            out.field("tx_id", &tx_id);
+            out.field("quorum", quorum);
+        }
+
+        Commands::AddPriceFeed { asset_id, price } => {
+            info!("Adding price feed of asset {} at {}", asset_id, price);
+
+            let tx_id = transaction_builder(&config).await?
+                .add_price_feed(asset_id.into(), price)
+                .await?;
+
+            out.text(format!("✅ Added price feed of asset {} at {}", asset_id, format_price(price)));
+            out.text(format!("   TX ID: {}", tx_id));
+            out.field("tx_id", &tx_id);
+            out.field("price", AssetPrice { asset_id: asset_id.into(), price });
+        }
+
+        Commands::SetMaxPriceAge { asset_id, max_age } => {
+            info!("Setting maximum price age of asset {} to {}s", asset_id, max_age);
+
+            let tx_id = transaction_builder(&config).await?
+                .set_max_price_age(asset_id.into(), max_age)
+                .await?;
+
+            out.text(format!("✅ {} prices now expire after {}s", asset_id_to_name(asset_id), max_age));
+            out.text(format!("   TX ID: {}", tx_id));
+            out.field("tx_id", &tx_id);
+            out.field("max_age", max_age);
+        }
+
+        Commands::SetTwapWindow { asset_id, window } => {
+            info!("Setting TWAP window of asset {} to {}s", asset_id, window);
+
+            let tx_id = transaction_builder(&config).await?
+                .set_twap_window(asset_id.into(), window)
+                .await?;
+
+            out.text(format!("✅ {} TWAP now averages over {}s", asset_id_to_name(asset_id), window));
+            out.text(format!("   TX ID: {}", tx_id));
+            out.field("tx_id", &tx_id);
+            out.field("twap_window", window);
+        }
+
+        Commands::SetCircuitBreaker { asset_id, max_deviation, interval } => {
+            info!(
+                "Setting circuit breaker of asset {} to {} bps over {}s",
+                asset_id, max_deviation, interval
+            );
+
+            let tx_id = transaction_builder(&config).await?
+                .set_circuit_breaker(asset_id.into(), max_deviation, interval)
+                .await?;
+
+            if max_deviation == 0 {
+                out.text(format!("✅ Disabled circuit breaker of {}", asset_id_to_name(asset_id)));
+            } else {
+                out.text(format!(
+                    "✅ {} feed pauses on moves above {} within {}s",
+                    asset_id_to_name(asset_id),
+                    basis_points_to_percentage(max_deviation),
+                    interval
+                ));
+            }
+            out.text(format!("   TX ID: {}", tx_id));
+            out.field("tx_id", &tx_id);
+            out.field("max_deviation", max_deviation);
+            out.field("deviation_interval", interval);
+        }
+
+        Commands::ResumePriceFeed { asset_id } => {
+            info!("Resuming price feed of asset {}", asset_id);
+
+            let mut tx_builder = transaction_builder(&config).await?;
+            let tx_id = tx_builder.resume_price_feed(asset_id.into()).await?;
+            let price = tx_builder.get_price(asset_id.into()).await?;
+
+            out.text(format!("✅ Resumed {} feed at {}", asset_id_to_name(asset_id), format_price(price)));
+            out.text(format!("   TX ID: {}", tx_id));
+            out.field("tx_id", &tx_id);
+            out.field("price", AssetPrice { asset_id: asset_id.into(), price });
+        }
+
+        Commands::Updaters { command } => {
+            let mut tx_builder = transaction_builder(&config).await?;
+
+            let (account_id, tx_id) = match command {
+                UpdaterCommands::Add { account_id } => {
+                    let updater_id = parse_account_id(&account_id)?;
+                    let tx_id = tx_builder.add_price_updater(&updater_id).await?;
+                    out.text(format!("✅ Authorized {} to update prices", account_id));
+                    (account_id, tx_id)
+                }
+                UpdaterCommands::Remove { account_id } => {
+                    let updater_id = parse_account_id(&account_id)?;
+                    let tx_id = tx_builder.remove_price_updater(&updater_id).await?;
+                    out.text(format!("✅ Revoked price updates by {}", account_id));
+                    (account_id, tx_id)
+                }
+            };
+
+            out.text(format!("   TX ID: {}", tx_id));
+            out.field("tx_id", &tx_id);
+            out.field("updater", account_id);
+        }
+
+        Commands::Treasury { command } => {
+            let mut tx_builder = transaction_builder(&config).await?;
+
+            match command {
+                TreasuryCommands::Show => {
+                    info!("Fetching treasury reserves");
+
+                    let reserves = tx_builder.get_reserves().await?;
+
+                    out.text(format!("{:<10} {:<8} {:>24}", "ASSET ID", "SYMBOL", "ACCRUED"));
+                    let mut balances = Vec::with_capacity(reserves.len());
+                    for reserve in &reserves {
+                        let accrued = tx_builder.get_accrued_to_treasury(reserve.asset_id).await?;
+                        let symbol = config
+                            .assets
+                            .iter()
+                            .find(|(_, asset)| u64::from(asset.id) == reserve.asset_id)
+                            .map(|(symbol, _)| symbol.as_str())
+                            .unwrap_or("-");
+                        out.text(format!("{:<10} {:<8} {:>24}", reserve.asset_id, symbol, accrued));
+                        balances.push(serde_json::json!({ "asset_id": reserve.asset_id, "accrued": accrued }));
+                    }
+                    out.field("treasury", balances);
+                }
+
+                TreasuryCommands::Withdraw { asset_id, amount, to } => {
+                    if let Some(to) = to {
+                        parse_account_id(&to)?;
+                        config.treasury_account_id = Some(to);
+                        config.save()?;
+                    }
+                    let treasury_id = configured_account_id(&config.treasury_account_id, "treasury_account_id")?;
+
+                    // Interest keeps accruing, so claiming everything leaves what accrues meanwhile
+                    let amount = match amount {
+                        Some(amount) => amount,
+                        None => tx_builder.get_accrued_to_treasury(asset_id.into()).await?,
+                    };
+                    if amount == 0 {
+                        anyhow::bail!("No reserves accrued to the treasury for {}", asset_id_to_name(asset_id));
+                    }
+
+                    info!("Withdrawing {} units of asset {} to the treasury", amount, asset_id);
+                    let tx_id = tx_builder.claim_reserves(asset_id.into(), amount, &treasury_id).await?;
+
+                    out.text(format!(
+                        "✅ Sent {} {} of reserves to treasury {}",
+                        amount,
+                        asset_id_to_name(asset_id),
+                        treasury_id.to_hex()
+                    ));
+                    out.text(format!("   TX ID: {}", tx_id));
+                    out.field("treasury", treasury_id.to_hex());
+                    out.transaction(&tx_id, asset_id, amount);
+                }
+            }
         }
 
         Commands::HealthFactor => {
-            info!("Calculating health factor");
-            println!("⚠️  Health factor calculation requires full Miden client integration");
-            println!("   This will be enabled after Miden API integration");
+            info!("Updating health factor on-chain");
+
+            let user_id = user_account_id(&config)?;
+            let health_factor = transaction_builder(&config).await?
+                .update_health_factor(&user_id)
+                .await?;
+
+            out.text(format!("📊 Health factor: {}", format_health_factor(health_factor)));
+            out.field("account_id", user_id.to_hex());
+            out.field("health_factor", health_factor);
+        }
+
+        Commands::Liquidate { borrower, collateral_asset_id, debt_asset_id, amount } => {
+            info!("Liquidating {} units of asset {} debt of {}", amount, debt_asset_id, borrower);
+
+            let liquidator_id = user_account_id(&config)?;
+            let borrower_id = parse_account_id(&borrower)?;
+
+            // Only undercollateralized positions can be liquidated, which the builder checks
+            let liquidation = transaction_builder(&config).await?
+                .liquidate(
+                    &liquidator_id,
+                    &borrower_id,
+                    collateral_asset_id.into(),
+                    debt_asset_id.into(),
+                    amount,
+                )
+                .await?;
+
+            out.text(format!(
+                "✅ Liquidated {} {} of debt",
+                liquidation.debt_covered,
+                asset_id_to_name(debt_asset_id)
+            ));
+            out.text(format!(
+                "   Seized {} {} of collateral",
+                liquidation.collateral_seized,
+                asset_id_to_name(collateral_asset_id)
+            ));
+            out.text(format!(
+                "   Borrower health factor before: {}",
+                format_health_factor(liquidation.health_factor_before)
+            ));
+            out.text(format!("   TX ID: {}", liquidation.tx_id));
+            out.field("borrower", &borrower);
+            out.field("health_factor_before", liquidation.health_factor_before);
+            out.field("collateral_seized", liquidation.collateral_seized);
+            out.transaction(&liquidation.tx_id, debt_asset_id, liquidation.debt_covered);
+        }
+
+        Commands::Keys { command } => {
+            let keystore = FileKeyStore::from_env(&config.storage_dir())?;
+
+            match command {
+                KeyCommands::List => {
+                    out.text(format!("{:<34} PUBLIC KEY", "ACCOUNT"));
+                    let keys = keystore.list_keys()?;
+                    for key in &keys {
+                        out.text(format!("{:<34} {}", key.account_id, key.public_key));
+                    }
+                    out.field("keys", &keys);
+                }
+
+                KeyCommands::Export { account_id, file } => {
+                    let id = parse_account_id(&account_id)?;
+
+                    match file {
+                        Some(path) => {
+                            keystore.export_key_to_file(&id, &path)?;
+                            out.text(format!("✅ Key of {} exported to {:?}", account_id, path));
+                            out.field("path", &path);
+                        }
+                        None => {
+                            let secret_key = keystore.export_key(&id)?;
+                            out.text(&secret_key);
+                            out.field("secret_key", &secret_key);
+                        }
+                    }
+                    out.field("account_id", &account_id);
+                }
+
+                KeyCommands::Import { account_id, file, force } => {
+                    let secret_key = std::fs::read_to_string(&file)
+                        .with_context(|| format!("Failed to read {:?}", file))?;
+
+                    // The key must match the public key of the account's auth component
+                    let id = parse_account_id(&account_id)?;
+                    let client = LendingClient::new(&config.rpc_endpoint, config.storage_dir()).await?;
+                    let public_key = client.get_account(&id).await?.public_key;
+                    keystore.import_key(&id, &secret_key, public_key, force)?;
+
+                    out.text(format!("✅ Key of {} imported", account_id));
+                    out.field("account_id", &account_id);
+                }
+            }
+        }
+
+        Commands::Profile { .. } => unreachable!("profile commands are handled before loading a profile"),
+
+        Commands::Positions { unhealthy } => {
+            info!("Scanning user positions");
+
+            let user_ids = config
+                .scanned_user_accounts()
+                .iter()
+                .map(|user_id| parse_account_id(user_id))
+                .collect::<Result<Vec<_>>>()?;
+
+            let positions = transaction_builder(&config).await?
+                .get_positions(&user_ids, unhealthy)
+                .await?;
+
+            out.text(format!("{:<34} {:>20} {:>20} {:>14}", "ACCOUNT", "COLLATERAL", "DEBT", "HEALTH FACTOR"));
+            for position in &positions {
+                out.text(format!(
+                    "{:<34} {:>20} {:>20} {:>14}",
+                    position.account_id.to_hex(),
+                    format_price(position.collateral_value),
+                    format_price(position.debt_value),
+                    format_health_factor(position.health_factor)
+                ));
+            }
+            out.field("positions", &positions);
+        }
+    }
+
+    Ok(())
+}
+
+/// Builds a transaction builder for the pool and oracle accounts in the configuration
+async fn transaction_builder(config: &config::Config) -> Result<TransactionBuilder> {
+    let pool_id = configured_account_id(&config.lending_pool_id(), "lending_pool_account_id")?;
+    let oracle_id = configured_account_id(&config.price_oracle_id(), "price_oracle_account_id")?;
+
+    let client = LendingClient::new(&config.rpc_endpoint, config.storage_dir()).await?;
+    Ok(TransactionBuilder::new(client, pool_id, oracle_id))
+}
+
+/// Applies a change to the deployment manifest, creating it on the first deploy
+fn update_deployment_info(
+    config: &config::Config,
+    update: impl FnOnce(&mut DeploymentInfo),
+) -> Result<()> {
+    let path = config.deployment_info_path();
+    let mut info = DeploymentInfo::load(&path)
+        .context("Failed to read deployment manifest")?
+        .unwrap_or_else(|| DeploymentInfo::new(&config.network_name(), config.assets.clone()));
+
+    update(&mut info);
+    info.save(&path).context("Failed to write deployment manifest")?;
+
+    info!("Deployment manifest updated: {}", path.display());
+    Ok(())
+}
+
+/// Runs a profile management command
+fn run_profile_command(command: ProfileCommands, out: &mut Output) -> Result<()> {
+    let mut profiles = config::Profiles::load()?;
+
+    match command {
+        ProfileCommands::List => {
+            out.text(format!("{:<2}{:<16} {:<40} STORAGE", "", "PROFILE", "RPC"));
+            for (name, profile) in &profiles.profiles {
+                let marker = if *name == profiles.active_profile { "*" } else { "" };
+                out.text(format!(
+                    "{:<2}{:<16} {:<40} {}",
+                    marker,
+                    name,
+                    profile.rpc_endpoint,
+                    profile.storage_dir().display()
+                ));
+            }
+            out.field("active_profile", &profiles.active_profile);
+            out.field("profiles", &profiles.profiles);
+        }
+
+        ProfileCommands::Add { name, rpc, storage_path } => {
+            let storage_path = storage_path.unwrap_or_else(|| config::Config::default_storage_path(&name));
+
+            profiles.add(config::Config::new(&name, &rpc, storage_path))?;
+            profiles.save()?;
+
+            out.text(format!("✅ Profile `{}` added", name));
+            out.text(format!("   RPC: {}", rpc));
+            out.field("profile", &name);
+            out.field("rpc_endpoint", &rpc);
+        }
+
+        ProfileCommands::Switch { name } => {
+            profiles.switch(&name)?;
+            profiles.save()?;
+
+            out.text(format!("✅ Switched to profile `{}`", name));
+            out.field("profile", &name);
         }
     }
 
     Ok(())
 }
+
+/// Returns the user account ID from the configuration
+fn user_account_id(config: &config::Config) -> Result<AccountId> {
+    configured_account_id(&config.user_account_id, "user_account_id")
+}
+
+/// Parses a hex account ID from the configuration
+fn configured_account_id(account_id: &Option<String>, field: &str) -> Result<AccountId> {
+    let account_id = account_id
+        .as_deref()
+        .with_context(|| format!("`{}` is not set in the configuration", field))?;
+
+    parse_account_id(account_id).with_context(|| format!("Invalid `{}`", field))
+}
+
+/// Parses a hex account ID
+fn parse_account_id(account_id: &str) -> Result<AccountId> {
+    let bytes = hex::decode(account_id.trim_start_matches("0x"))
+        .with_context(|| format!("`{}` is not a valid hex account ID", account_id))?;
+
+    Ok(AccountId(bytes))
+}
@@ -1,208 +1,607 @@
 // Miden client wrapper for the lending protocol
 // This module handles direct interaction with Miden VM and blockchain
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use ::miden_client::{
+    rpc::{
+        domain::account::AccountStorageRequirements, Endpoint, NodeRpcClient, TonicRpcClient,
+    },
+    store::{sqlite_store::SqliteStore, NoteFilter, TransactionFilter},
+    transaction::{ForeignAccount, TransactionRequest, TransactionRequestBuilder, TransactionStatus},
+    Client, ExecutionOptions,
+};
+use miden_lib::{
+    account::{
+        auth::{AuthRpoFalcon512, AuthRpoFalcon512Acl},
+        faucets::BasicFungibleFaucet,
+    },
+    note::well_known_note::WellKnownNote,
+    utils::ScriptBuilder,
+};
+use miden_objects::{
+    account::{
+        Account as MidenAccount, AccountBuilder, AuthSecretKey, AccountComponent, AccountId as MidenAccountId,
+        AccountStorageMode as MidenAccountStorageMode, AccountType, StorageSlot,
+    },
+    asset::{FungibleAsset, TokenSymbol},
+    block::BlockNumber,
+    crypto::{dsa::rpo_falcon512::{PublicKey, SecretKey}, rand::RpoRandomCoin},
+    note::{Note as MidenNote, NoteType},
+    transaction::{ExecutedTransaction, OutputNote, TransactionId, TransactionScript},
+    utils::{Deserializable, Serializable},
+    vm::{AdviceInputs, AdviceMap},
+    Felt, Word, MAX_TX_EXECUTION_CYCLES, MIN_TX_EXECUTION_CYCLES,
+};
+use miden_tx::auth::{SigningInputs, TransactionAuthenticator};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
 
-/// Wrapper around miden-client for lending protocol operations
+pub use crate::backend::LendingBackend;
+use crate::keystore::FileKeyStore;
+use crate::components::{
+    allows_unsigned_notes, foreign_map_keys, lending_pool::lending_pool_library,
+    price_oracle::price_oracle_library, signed_procedure_roots, user_lending::user_lending_library,
+};
+
+/// Name of the sqlite database the client keeps its view of the chain in, under the store path
+const STORE_FILE: &str = "store.sqlite3";
+
+/// Timeout of requests to the node
+const RPC_TIMEOUT_MS: u64 = 10_000;
+
+/// Number of blocks after which a transaction that is still not committed is discarded
+const TX_GRACEFUL_BLOCKS: u32 = 20;
+
+/// Interval at which the client syncs while waiting for a transaction to be committed
+const TX_COMMIT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Number of syncs after which the client stops waiting for a transaction to be committed
+const TX_COMMIT_MAX_POLLS: u32 = 60;
+
+/// Wrapper around the Miden client for lending protocol operations
 ///
-/// Note: This is a stub implementation for testing and development.
-/// In production, this will wrap the actual miden-client SDK.
+/// Accounts are deployed from the compiled lending components. Transactions are executed and
+/// proven locally, submitted to the node over gRPC and tracked in a sqlite store under
+/// `store_path`, which also keeps the accounts, notes and block headers synced from the node.
 pub struct LendingClient {
-    // Placeholder for actual miden-client instance
-    // In production: client: miden_client::Client,
-    config_path: PathBuf,
-    store_path: PathBuf,
-    // Simulated account storage for testing
-    accounts: std::sync::Arc<std::sync::Mutex<std::collections::HashMap<Vec<u8>, Account>>>,
+    client: Mutex<Client<FileKeyStore>>,
+    rpc_api: Arc<TonicRpcClient>,
+    keystore: Arc<FileKeyStore>,
 }
 
 impl LendingClient {
     /// Create a new lending client instance
     ///
-    /// In production, this will initialize the actual Miden client with:
-    /// ```ignore
-    /// let client = ClientBuilder::new()
-    ///     .rpc(rpc_endpoint)
-    ///     .store_path(&store_path)
-    ///     .build()
-    ///     .await?;
-    /// ```
-    pub async fn new(_rpc_endpoint: &str, store_path: PathBuf) -> Result<Self> {
-        use std::collections::HashMap;
-        use std::sync::{Arc, Mutex};
-
-        Ok(Self {
-            config_path: store_path.join("config"),
-            store_path,
-            accounts: Arc::new(Mutex::new(HashMap::new())),
-        })
+    /// Opens (or initializes) the store at `store_path`, together with the keystore under it, and
+    /// syncs it with the node at `rpc_endpoint`. The keystore password is read from
+    /// `MIDEN_LENDING_KEYSTORE_PASSWORD`.
+    pub async fn new(rpc_endpoint: &str, store_path: PathBuf) -> Result<Self> {
+        let endpoint = Endpoint::try_from(rpc_endpoint)
+            .map_err(|err| anyhow::anyhow!("Invalid RPC endpoint {}: {}", rpc_endpoint, err))?;
+        std::fs::create_dir_all(&store_path)
+            .with_context(|| format!("Failed to create store at {:?}", store_path))?;
+
+        let keystore = Arc::new(FileKeyStore::from_env(&store_path)?);
+        let store = SqliteStore::new(store_path.join(STORE_FILE))
+            .await
+            .context("Failed to open store")?;
+        let rpc_api = Arc::new(TonicRpcClient::new(&endpoint, RPC_TIMEOUT_MS));
+        let rng = RpoRandomCoin::new(rand::random::<[u64; 4]>().map(Felt::new).into());
+        let exec_options =
+            ExecutionOptions::new(Some(MAX_TX_EXECUTION_CYCLES), MIN_TX_EXECUTION_CYCLES, false, false)?;
+
+        let client = Client::new(
+            rpc_api.clone(),
+            Box::new(rng),
+            Arc::new(store),
+            Some(keystore.clone()),
+            exec_options,
+            Some(TX_GRACEFUL_BLOCKS),
+            None,
+        )
+        .await
+        .context("Failed to create Miden client")?;
+
+        let lending_client = Self {
+            client: Mutex::new(client),
+            rpc_api,
+            keystore,
+        };
+        lending_client.sync().await?;
+
+        Ok(lending_client)
+    }
+
+    /// Returns the keystore holding the keys of the accounts created by this client
+    pub fn keystore(&self) -> &FileKeyStore {
+        &self.keystore
     }
 
     /// Sync with the Miden network
     ///
-    /// In production, this syncs the local client state with the Miden network:
-    /// ```ignore
-    /// self.client.sync_state().await?;
-    /// ```
-    pub async fn sync(&mut self) -> Result<()> {
-        // Stub: In production, sync local state with network
-        tracing::debug!("Syncing with Miden network (stub)");
+    /// Updates the tracked accounts and notes to the chain tip.
+    pub async fn sync(&self) -> Result<()> {
+        let summary = self
+            .client
+            .lock()
+            .await
+            .sync_state()
+            .await
+            .context("Failed to sync with the node")?;
+
+        tracing::debug!("Synced with the node: chain tip at block {}", summary.block_num);
         Ok(())
     }
 
-    /// Create a new account with the specified code
+    /// Create a public fungible faucet, e.g. to issue the assets of reserves on a local network
     ///
-    /// In production, this creates an account using:
-    /// ```ignore
-    /// let (account, seed) = AccountBuilder::new(seed)
-    ///     .account_type(AccountType::RegularAccountUpdatableCode)
-    ///     .storage_mode(storage_mode.into())
-    ///     .with_component(account_component)
-    ///     .build()?;
-    /// self.client.insert_account(&account, Some(seed))?;
-    /// ```
-    pub async fn create_account(
-        &mut self,
-        account_code: &str,
-        storage_mode: AccountStorageMode,
-    ) -> Result<AccountId> {
-        use rand::Rng;
+    /// The faucet's key is stored in the keystore, so that the client can mint its asset with
+    /// [`mint`](Self::mint). The faucet is committed on-chain together with its first mint.
+    pub async fn create_faucet(&mut self, symbol: &str, decimals: u8, max_supply: u64) -> Result<AccountId> {
+        let symbol = TokenSymbol::new(symbol)
+            .map_err(|err| anyhow::anyhow!("Invalid token symbol {}: {}", symbol, err))?;
+        let faucet_component = BasicFungibleFaucet::new(symbol, decimals, Felt::new(max_supply))
+            .context("Invalid faucet parameters")?;
+
+        let secret_key = SecretKey::new();
+        let (faucet, seed) = AccountBuilder::new(rand::random())
+            .account_type(AccountType::FungibleFaucet)
+            .storage_mode(MidenAccountStorageMode::Public)
+            .with_auth_component(AuthRpoFalcon512::new(secret_key.public_key()))
+            .with_component(faucet_component)
+            .build()
+            .context("Failed to build faucet")?;
+        let faucet_id = AccountId::from(faucet.id());
+
+        self.keystore
+            .add_key(&faucet_id, &AuthSecretKey::RpoFalcon512(secret_key))
+            .context("Failed to store faucet key")?;
+        self.client
+            .get_mut()
+            .add_account(&faucet, Some(seed), false)
+            .await
+            .context("Failed to add faucet to the store")?;
+
+        Ok(faucet_id)
+    }
 
-        // Generate a random account ID for testing
-        let mut rng = rand::thread_rng();
-        let account_id_bytes: Vec<u8> = (0..32).map(|_| rng.gen()).collect();
-        let account_id = AccountId(account_id_bytes);
+    /// Mints a faucet's asset to an account, which consumes the P2ID note it is sent in
+    ///
+    /// Both the faucet and the account must be created by this client.
+    pub async fn mint(&mut self, faucet_id: &AccountId, account_id: &AccountId, amount: u64) -> Result<()> {
+        let asset = FungibleAsset::new(faucet_id.try_into()?, amount).context("Invalid amount")?;
+        let request = TransactionRequestBuilder::new()
+            .build_mint_fungible_asset(
+                asset,
+                account_id.try_into()?,
+                NoteType::Public,
+                self.client.get_mut().rng(),
+            )
+            .context("Invalid mint request")?;
+
+        let result = self.submit_transaction(faucet_id, request).await.context("Failed to mint")?;
+        self.consume_notes(result.output_notes, AdviceMap::default(), account_id)
+            .await
+            .context("Failed to receive minted assets")?;
+        Ok(())
+    }
 
-        // Create account with initial empty storage
-        let account = Account {
-            id: account_id.clone(),
-            storage: vec![],
-        };
+    /// Executes a transaction request, proves it and submits it to the node
+    ///
+    /// Accounts not tracked by the client, e.g. a pool deployed by someone else, are imported
+    /// from the node first. Returns once the transaction is committed, so that the following
+    /// transactions can read the state it produced.
+    async fn submit_transaction(
+        &self,
+        account_id: &AccountId,
+        request: TransactionRequest,
+    ) -> Result<TransactionResult> {
+        let account_id = MidenAccountId::try_from(account_id)?;
+        self.load_account(account_id).await?;
+        let mut client = self.client.lock().await;
+
+        let tx_result = client
+            .new_transaction(account_id, request)
+            .await
+            .context("Transaction execution failed")?;
+        let executed = tx_result.executed_transaction();
+        let tx_id = executed.id();
+        let output_notes = full_output_notes(executed);
+        let cycles = executed.measurements().total_cycles();
+
+        client
+            .submit_transaction(tx_result)
+            .await
+            .context("Failed to submit transaction")?;
+        Self::wait_for_commit(&mut client, tx_id).await?;
 
-        // Store account in local map
-        if let Ok(mut accounts) = self.accounts.lock() {
-            accounts.insert(account_id.0.clone(), account);
+        tracing::debug!(
+            "Executed transaction {} for account {} ({} cycles)",
+            tx_id,
+            account_id,
+            cycles
+        );
+
+        Ok(TransactionResult {
+            success: true,
+            tx_id: tx_id.as_bytes().to_vec(),
+            output_notes,
+        })
+    }
+
+    /// Syncs until a submitted transaction is committed
+    async fn wait_for_commit(client: &mut Client<FileKeyStore>, tx_id: TransactionId) -> Result<()> {
+        for _ in 0..TX_COMMIT_MAX_POLLS {
+            client.sync_state().await.context("Failed to sync with the node")?;
+
+            let transactions = client.get_transactions(TransactionFilter::Ids(vec![tx_id])).await?;
+            match transactions.first().map(|transaction| &transaction.status) {
+                Some(TransactionStatus::Committed { .. }) => return Ok(()),
+                Some(TransactionStatus::Discarded(cause)) => {
+                    anyhow::bail!("Transaction {} was discarded: {:?}", tx_id, cause)
+                }
+                _ => tokio::time::sleep(TX_COMMIT_POLL_INTERVAL).await,
+            }
         }
 
+        anyhow::bail!("Transaction {} was not committed in time", tx_id)
+    }
+
+    /// Describes foreign accounts to be read with foreign procedure invocation by an account
+    ///
+    /// Foreign accounts are loaded with proofs of the storage map entries the lending contracts
+    /// read from them, including entries they do not hold: the pool's reserves and the reading
+    /// account's own position, or the oracle's price feeds. Other accounts are loaded with all the
+    /// entries of their storage maps.
+    async fn foreign_accounts(
+        &self,
+        reader_id: &AccountId,
+        account_ids: &[AccountId],
+    ) -> Result<BTreeSet<ForeignAccount>> {
+        let reader_id = MidenAccountId::try_from(reader_id)?;
+        let mut foreign_accounts = BTreeSet::new();
+        for account_id in account_ids {
+            let account_id = MidenAccountId::try_from(account_id)?;
+            let fetched = self
+                .rpc_api
+                .get_account_details(account_id)
+                .await
+                .with_context(|| format!("Foreign account {} is not committed yet", account_id))?;
+            let account = fetched
+                .account()
+                .with_context(|| format!("Foreign account {} is not public", account_id))?;
+
+            let offset = lending_storage_offset(account);
+            let slots = account.storage().slots();
+            let storage_requirements = match foreign_map_keys(account.code(), &slots[offset..], reader_id) {
+                Some(map_keys) => AccountStorageRequirements::new(
+                    map_keys.iter().map(|(slot, keys)| (offset as u8 + slot, keys)),
+                ),
+                None => AccountStorageRequirements::new(slots.iter().enumerate().filter_map(
+                    |(index, slot)| match slot {
+                        StorageSlot::Map(map) => Some((index as u8, map.entries().map(|(key, _)| key))),
+                        StorageSlot::Value(_) => None,
+                    },
+                )),
+            };
+            foreign_accounts.insert(ForeignAccount::public(account_id, storage_requirements)?);
+        }
+
+        Ok(foreign_accounts)
+    }
+
+    /// Returns the state of an account, importing it from the node if it is not tracked yet
+    async fn load_account(&self, account_id: MidenAccountId) -> Result<MidenAccount> {
+        let mut client = self.client.lock().await;
+        if client.get_account(account_id).await?.is_none() {
+            client
+                .import_account_by_id(account_id)
+                .await
+                .with_context(|| format!("Account {} not found", account_id))?;
+        }
+
+        let record = client
+            .get_account(account_id)
+            .await?
+            .with_context(|| format!("Account {} not found", account_id))?;
+        Ok(record.into())
+    }
+}
+
+impl LendingBackend for LendingClient {
+    /// Create a new account with the specified components
+    ///
+    /// A new Falcon key is generated and stored in the keystore. The account is tracked by the
+    /// client and committed on-chain together with its first transaction.
+    async fn create_account(
+        &mut self,
+        components: Vec<AccountComponent>,
+        storage_mode: AccountStorageMode,
+    ) -> Result<AccountId> {
+        let secret_key = SecretKey::new();
+        let (account, seed) = build_account(components, storage_mode, secret_key.public_key())?;
+        let account_id = AccountId::from(account.id());
+
+        self.keystore
+            .add_key(&account_id, &AuthSecretKey::RpoFalcon512(secret_key))
+            .context("Failed to store account key")?;
+        self.client
+            .get_mut()
+            .add_account(&account, Some(seed), false)
+            .await
+            .context("Failed to add account to the store")?;
+
         tracing::info!(
-            "Created account {} with mode {:?} (stub)",
+            "Created account {} with mode {:?}",
             account_id.to_hex(),
             storage_mode
         );
-        tracing::debug!("Account code length: {} bytes", account_code.len());
 
         Ok(account_id)
     }
 
     /// Execute a transaction
     ///
-    /// In production, this executes a transaction using:
-    /// ```ignore
-    /// let script = ScriptBuilder::new()
-    ///     .with_code(tx_script)
-    ///     .build()?;
-    /// let tx_request = TransactionRequestBuilder::new()
-    ///     .with_script(script)
-    ///     .build()?;
-    /// let tx_result = self.client.execute_transaction(tx_request).await?;
-    /// self.client.submit_transaction(tx_result).await?;
-    /// ```
-    pub async fn execute_transaction(
+    /// Compiles the script against the lending contract libraries, then executes and proves it
+    /// against the account and submits it to the node.
+    async fn execute_transaction_with_foreign_accounts(
         &mut self,
         tx_script: &str,
+        advice_map: AdviceMap,
         account_id: &AccountId,
+        foreign_account_ids: &[AccountId],
     ) -> Result<TransactionResult> {
-        use rand::Rng;
-
-        // Generate a random transaction ID
-        let mut rng = rand::thread_rng();
-        let tx_id: Vec<u8> = (0..32).map(|_| rng.gen()).collect();
-
-        tracing::debug!(
-            "Executing transaction for account {} (stub)",
-            account_id.to_hex()
-        );
         tracing::trace!("Transaction script:\n{}", tx_script);
 
-        // Note: In stub mode, we don't validate account existence since
-        // accounts may be created by different client instances during testing.
-        // In production, the actual Miden client will handle validation.
+        let request = TransactionRequestBuilder::new()
+            .custom_script(compile_tx_script(tx_script)?)
+            .extend_advice_map(advice_map)
+            .foreign_accounts(self.foreign_accounts(account_id, foreign_account_ids).await?)
+            .build()
+            .context("Invalid transaction request")?;
 
-        // Stub: Simulate successful transaction execution
-        Ok(TransactionResult {
-            success: true,
-            tx_id,
-        })
+        self.submit_transaction(account_id, request).await
     }
 
-    /// Get account state
+    /// Execute a transaction creating notes
     ///
-    /// In production, this fetches account from the client:
-    /// ```ignore
-    /// let account = self.client.get_account(account_id)?;
-    /// ```
-    pub async fn get_account(&self, account_id: &AccountId) -> Result<Account> {
-        tracing::debug!("Fetching account {} (stub)", account_id.to_hex());
-
-        // Try to get account from local storage
-        if let Ok(accounts) = self.accounts.lock() {
-            if let Some(account) = accounts.get(&account_id.0) {
-                return Ok(Account {
-                    id: account.id.clone(),
-                    storage: account.storage.clone(),
-                });
-            }
-        }
+    /// The recipients of the notes are provided to the transaction, so the notes are output in
+    /// full and can be consumed right away.
+    async fn send_notes(
+        &mut self,
+        notes: Vec<MidenNote>,
+        account_id: &AccountId,
+    ) -> Result<TransactionResult> {
+        tracing::debug!("Sending {} notes from account {}", notes.len(), account_id.to_hex());
 
-        // Return default account if not found
-        Ok(Account {
-            id: account_id.clone(),
-            storage: vec![],
-        })
+        let request = TransactionRequestBuilder::new()
+            .custom_script(send_notes_script(&notes)?)
+            .expected_output_recipients(notes.iter().map(|note| note.recipient().clone()).collect())
+            .build()
+            .context("Invalid transaction request")?;
+
+        self.submit_transaction(account_id, request).await
     }
 
-    /// Submit a note to the network
+    /// Execute a transaction consuming unauthenticated notes
     ///
-    /// In production, this submits a note using:
-    /// ```ignore
-    /// self.client.submit_note(note).await?;
-    /// ```
-    pub async fn submit_note(&mut self, note: Note) -> Result<()> {
-        tracing::debug!(
-            "Submitting note from {} to {} (stub)",
-            note.sender.to_hex(),
-            note.recipient.to_hex()
-        );
-        tracing::trace!("Note contains {} assets", note.assets.len());
+    /// The notes are not looked up on-chain, so they can be consumed as soon as they are received.
+    async fn consume_notes_with_foreign_accounts(
+        &mut self,
+        notes: Vec<MidenNote>,
+        advice_map: AdviceMap,
+        account_id: &AccountId,
+        foreign_account_ids: &[AccountId],
+    ) -> Result<TransactionResult> {
+        tracing::debug!("Consuming {} notes with account {}", notes.len(), account_id.to_hex());
 
-        // Stub: In production, submit note to network
-        Ok(())
+        let request = TransactionRequestBuilder::new()
+            .unauthenticated_input_notes(notes.into_iter().map(|note| (note, None)))
+            .extend_advice_map(advice_map)
+            .foreign_accounts(self.foreign_accounts(account_id, foreign_account_ids).await?)
+            .build()
+            .context("Invalid transaction request")?;
+
+        self.submit_transaction(account_id, request).await
     }
 
-    /// Get notes for an account
+    /// Execute a read-only script against an account
     ///
-    /// In production, this fetches notes using:
-    /// ```ignore
-    /// let notes = self.client.get_input_notes(account_id)?;
-    /// ```
-    pub async fn get_notes(&self, account_id: &AccountId) -> Result<Vec<Note>> {
-        tracing::debug!("Fetching notes for account {} (stub)", account_id.to_hex());
+    /// Accounts not tracked by the client, e.g. a pool deployed by someone else, are imported
+    /// from the node first.
+    async fn execute_view_script_with_foreign_accounts(
+        &self,
+        tx_script: &str,
+        account_id: &AccountId,
+        foreign_account_ids: &[AccountId],
+    ) -> Result<Vec<u64>> {
+        tracing::trace!("View script:\n{}", tx_script);
+
+        let script = compile_tx_script(tx_script)?;
+        let foreign_accounts = self.foreign_accounts(account_id, foreign_account_ids).await?;
+        let account_id = MidenAccountId::try_from(account_id)?;
+        self.load_account(account_id).await?;
+
+        let stack = self
+            .client
+            .lock()
+            .await
+            .execute_program(account_id, script, AdviceInputs::default(), foreign_accounts)
+            .await
+            .context("Script execution failed")?;
+
+        Ok(stack.iter().map(|felt| felt.as_int()).collect())
+    }
+
+    /// Get the latest block the client synced to
+    async fn get_block_number(&self) -> Result<BlockNumber> {
+        Ok(self.client.lock().await.get_sync_height().await?)
+    }
+
+    /// Get account state
+    async fn get_account(&self, account_id: &AccountId) -> Result<Account> {
+        let account = self.load_account(MidenAccountId::try_from(account_id)?).await?;
+        Ok(Account::from(&account))
+    }
+
+    /// Sign a message with the key of an account from the keystore
+    async fn sign_message(&self, account_id: &AccountId, message: Word) -> Result<Vec<Felt>> {
+        let public_key = self.get_account(account_id).await?.public_key;
 
-        // Stub: In production, fetch notes from network
-        // Returns empty list for now
-        Ok(vec![])
+        self.keystore
+            .get_signature(public_key, &SigningInputs::Blind(message))
+            .await
+            .context("Failed to sign message")
+    }
+
+    /// Get the committed P2ID notes for an account tracked by the client, and not consumed yet
+    async fn get_notes(&self, account_id: &AccountId) -> Result<Vec<MidenNote>> {
+        tracing::debug!("Fetching notes for account {}", account_id.to_hex());
+
+        let account_id = MidenAccountId::try_from(account_id)?;
+        let records = self.client.lock().await.get_input_notes(NoteFilter::Committed).await?;
+
+        let mut notes = Vec::new();
+        for record in records {
+            let note: MidenNote = record.try_into().context("Invalid note record")?;
+            if is_p2id_note_for(&note, account_id) {
+                notes.push(note);
+            }
+        }
+
+        Ok(notes)
+    }
+}
+
+/// Builds a new lending account with an RpoFalcon512 ACL auth component for `public_key`
+///
+/// Components exporting their own auth procedure, like the price oracle, authenticate the account
+/// themselves instead. Like the standard Falcon components, they keep the account's public key in
+/// their first storage slot.
+///
+/// Returns the account and the seed it must be committed with.
+pub fn build_account(
+    components: Vec<AccountComponent>,
+    storage_mode: AccountStorageMode,
+    public_key: PublicKey,
+) -> Result<(MidenAccount, Word)> {
+    use miden_lib::account::auth::AuthRpoFalcon512AclConfig;
+    use rand::Rng;
+
+    let init_seed: [u8; 32] = rand::thread_rng().gen();
+
+    let builder = AccountBuilder::new(init_seed)
+        .account_type(AccountType::RegularAccountUpdatableCode)
+        .storage_mode(storage_mode.into());
+
+    let (mut auth_components, components): (Vec<_>, Vec<_>) =
+        components.into_iter().partition(has_auth_procedure);
+    if auth_components.len() > 1 {
+        anyhow::bail!("Account can only have one auth component");
     }
+
+    let builder = if let Some(component) = auth_components.pop() {
+        let mut storage_slots = component.storage_slots().to_vec();
+        let key_slot = storage_slots.first_mut().context("Auth component has no key slot")?;
+        *key_slot = StorageSlot::Value(public_key.into());
+
+        let component = AccountComponent::new(component.library().clone(), storage_slots)
+            .context("Failed to build auth component")?
+            .with_supported_types(component.supported_types().clone());
+        builder.with_auth_component(component)
+    } else {
+        // Signed procedures of the components require a signature, as do transactions creating or
+        // consuming notes unless the components allow unsigned notes
+        let unsigned_notes = components.iter().any(allows_unsigned_notes);
+        let auth_config = AuthRpoFalcon512AclConfig::new()
+            .with_auth_trigger_procedures(components.iter().flat_map(signed_procedure_roots).collect())
+            .with_allow_unauthorized_input_notes(unsigned_notes)
+            .with_allow_unauthorized_output_notes(unsigned_notes);
+        let auth_component = AuthRpoFalcon512Acl::new(public_key, auth_config)
+            .context("Failed to build auth component")?;
+        builder.with_auth_component(auth_component)
+    };
+
+    components
+        .into_iter()
+        .fold(builder, |builder, component| builder.with_component(component))
+        .build()
+        .context("Failed to build account")
+}
+
+/// Returns whether a component exports an auth procedure
+fn has_auth_procedure(component: &AccountComponent) -> bool {
+    component.library().module_infos().any(|module| {
+        module.procedures().any(|(_, procedure)| procedure.name.contains("auth__"))
+    })
+}
+
+/// Returns whether a note is a P2ID note consumable by `account_id`
+pub(crate) fn is_p2id_note_for(note: &MidenNote, account_id: MidenAccountId) -> bool {
+    note.script().root() == WellKnownNote::P2ID.script_root()
+        && note.inputs().values() == [account_id.suffix(), account_id.prefix().as_felt()]
+}
+
+/// Returns the notes output in full by an executed transaction
+pub(crate) fn full_output_notes(executed: &ExecutedTransaction) -> Vec<MidenNote> {
+    executed
+        .output_notes()
+        .iter()
+        .filter_map(|note| match note {
+            OutputNote::Full(note) => Some(note.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Compiles a transaction script with the lending contract libraries linked
+pub fn compile_tx_script(code: &str) -> Result<TransactionScript> {
+    let script = ScriptBuilder::new(true)
+        .with_dynamically_linked_library(&lending_pool_library())?
+        .with_dynamically_linked_library(&user_lending_library())?
+        .with_dynamically_linked_library(&price_oracle_library())?
+        .compile_tx_script(code)
+        .context("Failed to compile transaction script")?;
+
+    Ok(script)
+}
+
+/// Compiles a transaction script creating the given notes from the executing account
+///
+/// The assets of the notes are moved out of the account's vault with the basic wallet, which the
+/// account must expose.
+pub fn send_notes_script(notes: &[MidenNote]) -> Result<TransactionScript> {
+    let script = notes
+        .iter()
+        .fold(TransactionScriptBuilder::new(), |script, note| script.create_note(note))
+        .build();
+
+    compile_tx_script(&script)
 }
 
 /// Account storage mode
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy)]
 pub enum AccountStorageMode {
     Public,
     Private,
 }
 
-/// Account ID (32 bytes)
+impl From<AccountStorageMode> for MidenAccountStorageMode {
+    fn from(mode: AccountStorageMode) -> Self {
+        match mode {
+            AccountStorageMode::Public => MidenAccountStorageMode::Public,
+            AccountStorageMode::Private => MidenAccountStorageMode::Private,
+        }
+    }
+}
+
+/// Account ID (serialized Miden account ID)
+///
+/// Serialized as a hex string.
 #[derive(Debug, Clone)]
 pub struct AccountId(pub Vec<u8>);
 
@@ -212,11 +611,79 @@ impl AccountId {
     }
 }
 
+impl Serialize for AccountId {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_hex())
+    }
+}
+
+impl<'de> Deserialize<'de> for AccountId {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let account_id = String::deserialize(deserializer)?;
+        hex::decode(account_id).map(Self).map_err(serde::de::Error::custom)
+    }
+}
+
+impl From<MidenAccountId> for AccountId {
+    fn from(account_id: MidenAccountId) -> Self {
+        Self(account_id.to_bytes())
+    }
+}
+
+impl TryFrom<&AccountId> for MidenAccountId {
+    type Error = anyhow::Error;
+
+    fn try_from(account_id: &AccountId) -> Result<Self> {
+        MidenAccountId::read_from_bytes(&account_id.0)
+            .with_context(|| format!("Invalid account ID: {}", account_id.to_hex()))
+    }
+}
+
 /// Account representation
 #[derive(Debug)]
 pub struct Account {
     pub id: AccountId,
     pub storage: Vec<u64>,
+    /// Public key the account's transactions are signed with
+    pub public_key: Word,
+}
+
+impl From<&MidenAccount> for Account {
+    /// Takes the value slots of the account's lending component
+    fn from(account: &MidenAccount) -> Self {
+        let offset = lending_storage_offset(account);
+
+        let storage = account.storage().slots()[offset..]
+            .iter()
+            .map(|slot| match slot {
+                StorageSlot::Value(value) => value[0].as_int(),
+                StorageSlot::Map(_) => 0,
+            })
+            .collect();
+
+        // Auth components keep the account's public key in the first storage slot
+        let public_key = account.storage().get_item(0).unwrap_or_default();
+
+        Self {
+            id: AccountId::from(account.id()),
+            storage,
+            public_key,
+        }
+    }
+}
+
+/// Returns the index of the first storage slot of an account's lending component
+///
+/// Lending components follow the auth component, so their slots start at the highest storage
+/// offset used by the account's procedures.
+fn lending_storage_offset(account: &MidenAccount) -> usize {
+    account
+        .code()
+        .procedures()
+        .iter()
+        .map(|procedure| procedure.storage_offset() as usize)
+        .max()
+        .unwrap_or_default()
 }
 
 /// Transaction result
@@ -224,44 +691,57 @@ pub struct Account {
 pub struct TransactionResult {
     pub success: bool,
     pub tx_id: Vec<u8>,
-}
-
-/// Note representation
-#[derive(Debug)]
-pub struct Note {
-    pub id: Vec<u8>,
-    pub sender: AccountId,
-    pub recipient: AccountId,
-    pub assets: Vec<Asset>,
-    pub metadata: Vec<u8>,
-}
-
-/// Asset representation
-#[derive(Debug, Clone)]
-pub struct Asset {
-    pub asset_id: u32,
-    pub amount: u64,
+    /// Public notes created by the transaction, e.g. assets paid out by the pool
+    pub output_notes: Vec<MidenNote>,
 }
 
 /// Helper to build transaction scripts
 pub struct TransactionScriptBuilder {
+    /// Paths of the imported modules
+    imports: BTreeSet<String>,
     code: String,
 }
 
 impl TransactionScriptBuilder {
     pub fn new() -> Self {
         Self {
+            imports: BTreeSet::new(),
             code: String::new(),
         }
     }
 
-    /// Add a call to an account procedure
-    pub fn call_procedure(mut self, account_id: &AccountId, procedure: &str) -> Self {
+    /// Add a call to a procedure of a lending contract (e.g. `lending_pool`)
+    pub fn call_procedure(mut self, contract: &str, procedure: &str) -> Self {
+        self.imports.insert(format!("lending::{}", contract));
+        self.code.push_str(&format!("call.{}::{}\n", contract, procedure));
+        self
+    }
+
+    /// Create a note from the executing account
+    ///
+    /// Only the recipient of the note is committed to, so the transaction must be given the
+    /// note's recipient details to output the note in full. The note's assets are moved out of
+    /// the account's vault with the basic wallet.
+    pub fn create_note(mut self, note: &MidenNote) -> Self {
+        let metadata = note.metadata();
+
+        self.imports.insert("miden::tx".to_string());
+        self = self.push_word(note.recipient().digest());
         self.code.push_str(&format!(
-            "call.{}::{}\n",
-            account_id.to_hex(),
-            procedure
+            "push.{}.{}.{}.{}\nexec.tx::create_note\n",
+            Felt::from(metadata.execution_hint()).as_int(),
+            Felt::from(metadata.note_type()).as_int(),
+            metadata.aux().as_int(),
+            metadata.tag().as_u32()
         ));
+
+        for asset in note.assets().iter() {
+            self.imports.insert("miden::contracts::wallets::basic->wallet".to_string());
+            self = self.push_word(Word::from(*asset));
+            self.code.push_str("call.wallet::move_asset_to_note\ndropw\n");
+        }
+
+        self.code.push_str("drop\n");
         self
     }
 
@@ -271,9 +751,38 @@ impl TransactionScriptBuilder {
         self
     }
 
+    /// Push an account ID onto the stack as `[account_id_prefix, account_id_suffix]`
+    pub fn push_account_id(mut self, account_id: MidenAccountId) -> Self {
+        self.code.push_str(&format!(
+            "push.{}.{}\n",
+            account_id.suffix().as_int(),
+            account_id.prefix().as_u64()
+        ));
+        self
+    }
+
+    /// Push a word onto the stack, in the order account storage returns it
+    pub fn push_word(mut self, word: Word) -> Self {
+        let elements: Vec<String> = word.iter().map(|felt| felt.as_int().to_string()).collect();
+        self.code.push_str(&format!("push.{}\n", elements.join(".")));
+        self
+    }
+
     /// Build the final script
     pub fn build(self) -> String {
-        format!("begin\n{}\nend", self.code)
+        let imports: String = self
+            .imports
+            .iter()
+            .map(|module| format!("use.{}\n", module))
+            .collect();
+
+        format!("use.std::sys\n{}\nbegin\n{}exec.sys::truncate_stack\nend", imports, self.code)
+    }
+}
+
+impl Default for TransactionScriptBuilder {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -291,4 +800,122 @@ mod tests {
         assert!(script.contains("push.1000"));
         assert!(script.contains("push.1"));
     }
+
+    #[test]
+    fn test_script_builder_pushes_account_id_prefix_on_top() {
+        use miden_objects::testing::account_id::ACCOUNT_ID_REGULAR_PUBLIC_ACCOUNT_UPDATABLE_CODE;
+
+        let account_id =
+            MidenAccountId::try_from(ACCOUNT_ID_REGULAR_PUBLIC_ACCOUNT_UPDATABLE_CODE).unwrap();
+        let script = TransactionScriptBuilder::new().push_account_id(account_id).build();
+
+        assert!(script.contains(&format!(
+            "push.{}.{}",
+            account_id.suffix().as_int(),
+            account_id.prefix().as_u64()
+        )));
+    }
+
+    #[test]
+    fn test_user_account_signs_health_factor_updates() {
+        use crate::components::user_lending::UserLendingAccount;
+        use miden_objects::testing::account_id::ACCOUNT_ID_REGULAR_PUBLIC_ACCOUNT_UPDATABLE_CODE;
+
+        let pool_id =
+            MidenAccountId::try_from(ACCOUNT_ID_REGULAR_PUBLIC_ACCOUNT_UPDATABLE_CODE).unwrap();
+        let component: AccountComponent = UserLendingAccount::new(pool_id).into();
+        let health_factor_root = component
+            .get_procedure_root_by_name("lending::user_lending::calculate_health_factor")
+            .unwrap();
+
+        let (account, _) = build_account(
+            vec![component],
+            AccountStorageMode::Private,
+            SecretKey::new().public_key(),
+        )
+        .unwrap();
+
+        // The ACL auth component keeps its trigger procedures in its third slot
+        let trigger_root = account.storage().get_map_item(2, Word::from([0u32, 0, 0, 0])).unwrap();
+        assert_eq!(trigger_root, health_factor_root);
+
+        // ...and consuming notes is signed too
+        let config = account.storage().get_item(1).unwrap();
+        assert_eq!(config, Word::from([1u32, 0, 0, 0]));
+    }
+
+    #[test]
+    fn test_pool_consumes_notes_unsigned() {
+        use crate::components::lending_pool::LendingPoolAccount;
+        use miden_objects::testing::account_id::{
+            ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET, ACCOUNT_ID_REGULAR_PUBLIC_ACCOUNT_UPDATABLE_CODE,
+        };
+
+        let oracle_id =
+            MidenAccountId::try_from(ACCOUNT_ID_REGULAR_PUBLIC_ACCOUNT_UPDATABLE_CODE).unwrap();
+        let faucet_id = MidenAccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET).unwrap();
+        let component: AccountComponent =
+            LendingPoolAccount::new(oracle_id, [faucet_id; 3]).into();
+
+        let (account, _) = build_account(
+            vec![component],
+            AccountStorageMode::Public,
+            SecretKey::new().public_key(),
+        )
+        .unwrap();
+
+        // The ACL auth component keeps [num_trigger_procedures, allow_unauthorized_output_notes,
+        // allow_unauthorized_input_notes, 0] in its second slot
+        let config = account.storage().get_item(1).unwrap();
+        assert_eq!(config, Word::from([5u32, 1, 1, 0]));
+    }
+
+    #[test]
+    fn test_foreign_map_keys_cover_reserves_and_reader_position() {
+        use crate::components::{lending_pool::LendingPoolAccount, user_lending::UserLendingAccount};
+        use miden_objects::testing::account_id::{
+            ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET, ACCOUNT_ID_REGULAR_PUBLIC_ACCOUNT_UPDATABLE_CODE,
+        };
+
+        let oracle_id =
+            MidenAccountId::try_from(ACCOUNT_ID_REGULAR_PUBLIC_ACCOUNT_UPDATABLE_CODE).unwrap();
+        let faucet_id = MidenAccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET).unwrap();
+        let (pool, _) = build_account(
+            vec![LendingPoolAccount::new(oracle_id, [faucet_id; 3]).into()],
+            AccountStorageMode::Public,
+            SecretKey::new().public_key(),
+        )
+        .unwrap();
+
+        let offset = lending_storage_offset(&pool);
+        let map_keys =
+            foreign_map_keys(pool.code(), &pool.storage().slots()[offset..], oracle_id).unwrap();
+        let num_keys: Vec<_> = map_keys.iter().map(|(slot, keys)| (*slot, keys.len())).collect();
+        // 20 fields and one list entry per reserve, and the reader's collateral and debt in each
+        assert_eq!(num_keys, [(0, 60), (2, 3), (4, 6)]);
+
+        // Slots are relative to the pool component, which follows the auth component
+        let (slot, keys) = &map_keys[0];
+        let listed = pool.storage().get_map_item(offset as u8 + slot, keys[0]).unwrap();
+        assert_eq!(listed, Word::from([1u32, 0, 0, 0]));
+
+        let (user, _) = build_account(
+            vec![UserLendingAccount::new(pool.id()).into()],
+            AccountStorageMode::Public,
+            SecretKey::new().public_key(),
+        )
+        .unwrap();
+        assert!(foreign_map_keys(user.code(), user.storage().slots(), oracle_id).is_none());
+    }
+
+    #[test]
+    fn test_script_builder_imports_called_contracts() {
+        let script = TransactionScriptBuilder::new()
+            .push(1)
+            .call_procedure("lending_pool", "get_reserve_data")
+            .build();
+
+        assert!(script.contains("use.lending::lending_pool"));
+        assert!(script.contains("call.lending_pool::get_reserve_data"));
+    }
 }
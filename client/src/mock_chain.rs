@@ -0,0 +1,451 @@
+// Mock chain backend for the lending client
+// Runs the compiled lending contracts against an in-process MockChain, so end-to-end flows can
+// be tested without a node running
+
+use anyhow::{Context, Result};
+use miden_lib::note::create_p2id_note;
+use miden_objects::{
+    account::{Account, AccountComponent, AccountId, AuthSecretKey},
+    asset::FungibleAsset,
+    block::BlockNumber,
+    crypto::{dsa::rpo_falcon512::SecretKey, rand::RpoRandomCoin},
+    note::{Note as MidenNote, NoteId, NoteType},
+    transaction::{OutputNote, TransactionArgs, TransactionScript},
+    vm::{AdviceInputs, AdviceMap},
+    Felt, Word,
+};
+use miden_testing::{MockChain, TransactionContext, TransactionContextBuilder};
+use miden_tx::{
+    auth::{BasicAuthenticator, SigningInputs, TransactionAuthenticator},
+    TransactionExecutor,
+};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::backend::LendingBackend;
+use crate::miden_client::{
+    build_account, compile_tx_script, full_output_notes, is_p2id_note_for, send_notes_script,
+    Account as LendingAccount, AccountId as LendingAccountId, AccountStorageMode, TransactionResult,
+};
+
+/// Handle to a mock chain shared between lending clients
+///
+/// Cloning the handle shares the underlying chain, so an `AccountManager` and a
+/// `TransactionBuilder` running on clones of the same chain see each other's accounts and
+/// transactions. Every executed transaction is committed in its own block, except transactions
+/// sending notes, which are committed together with the transaction consuming their notes.
+#[derive(Clone, Default)]
+pub struct MockLendingChain {
+    inner: Arc<Mutex<MockChainState>>,
+}
+
+struct MockChainState {
+    chain: MockChain,
+    /// Latest state of the accounts created locally, along with their seed until they are
+    /// committed to the chain with their first transaction. The chain only stores commitments of
+    /// private accounts, so their state has to be tracked here.
+    accounts: BTreeMap<AccountId, (Account, Option<Word>)>,
+    /// Keys of the created accounts, by public key commitment
+    keys: Vec<(Word, AuthSecretKey)>,
+    /// Notes output in full by executed transactions and not consumed yet
+    notes: Vec<MidenNote>,
+    /// Notes sent by transactions awaiting the next block
+    pending_notes: BTreeMap<NoteId, MidenNote>,
+}
+
+impl MockLendingChain {
+    /// Create a new mock chain with only the genesis block
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of the latest block
+    pub async fn latest_block_num(&self) -> BlockNumber {
+        self.inner.lock().await.chain.latest_block_header().block_num()
+    }
+
+    /// Returns the timestamp of the latest block, which transactions read as the current time
+    pub async fn latest_block_timestamp(&self) -> u32 {
+        self.inner.lock().await.chain.latest_block_header().timestamp()
+    }
+
+    /// Proves an empty block `seconds` after the latest one, so that interest accrues
+    pub async fn advance_time(&self, seconds: u32) -> Result<()> {
+        let mut state = self.inner.lock().await;
+
+        let timestamp = state.chain.latest_block_header().timestamp() + seconds;
+        state.chain.prove_next_block_at(timestamp)?;
+        state.pending_notes.clear();
+
+        Ok(())
+    }
+
+    /// Mints assets to an account's wallet
+    ///
+    /// The assets are sent in a P2ID note from the faucet of the first one, which the account
+    /// consumes. The mock chain does not check that the faucets issued the assets.
+    pub async fn mint(&mut self, account_id: &LendingAccountId, assets: Vec<FungibleAsset>) -> Result<()> {
+        let faucet_id = assets.first().context("No assets to mint")?.faucet_id();
+        let note = create_p2id_note(
+            faucet_id,
+            AccountId::try_from(account_id)?,
+            assets.into_iter().map(Into::into).collect(),
+            NoteType::Public,
+            Felt::new(0),
+            &mut RpoRandomCoin::new(Word::new(rand::random::<[u64; 4]>().map(Felt::new))),
+        )?;
+
+        self.consume_notes(vec![note], AdviceMap::default(), account_id)
+            .await
+            .context("Failed to mint assets")?;
+        Ok(())
+    }
+
+    /// Drops the key kept for an account, so that transactions requiring its signature fail
+    pub async fn forget_key(&self, account_id: &LendingAccountId) -> Result<()> {
+        let public_key = self.get_account(account_id).await?.public_key;
+        self.inner.lock().await.keys.retain(|(key, _)| *key != public_key);
+
+        Ok(())
+    }
+
+    /// Returns the balance of a faucet's asset in the vault of a locally created account
+    pub async fn balance(&self, account_id: &LendingAccountId, faucet_id: AccountId) -> Result<u64> {
+        let account_id = AccountId::try_from(account_id)?;
+        let state = self.inner.lock().await;
+        let (account, _) = state
+            .accounts
+            .get(&account_id)
+            .with_context(|| format!("Account {} is not tracked locally", account_id))?;
+
+        Ok(account.vault().get_balance(faucet_id)?)
+    }
+
+    /// Executes a read-only script against the latest state of an account
+    async fn view(
+        &self,
+        account_id: AccountId,
+        script: TransactionScript,
+        foreign_account_ids: &[AccountId],
+    ) -> Result<[Felt; 16]> {
+        let state = self.inner.lock().await;
+
+        let tx_context = state.tx_context(account_id, foreign_account_ids, &[], &[])?;
+        let block_ref = tx_context.tx_inputs().block_header().block_num();
+        let foreign_account_inputs = tx_context.tx_args().foreign_account_inputs().to_vec();
+
+        let executor: TransactionExecutor<'_, '_, _, ()> = TransactionExecutor::new(&tx_context);
+        executor
+            .execute_tx_view_script(
+                account_id,
+                block_ref,
+                script,
+                AdviceInputs::default(),
+                foreign_account_inputs,
+            )
+            .await
+            .context("Script execution failed")
+    }
+}
+
+impl LendingBackend for MockLendingChain {
+    /// Create a new account, committed to the chain with its first transaction
+    async fn create_account(
+        &mut self,
+        components: Vec<AccountComponent>,
+        storage_mode: AccountStorageMode,
+    ) -> Result<LendingAccountId> {
+        // Keys are kept in memory to sign transactions calling admin procedures
+        let secret_key = SecretKey::new();
+        let public_key = secret_key.public_key();
+        let (account, seed) = build_account(components, storage_mode, public_key)?;
+        let account_id = LendingAccountId::from(account.id());
+
+        let mut state = self.inner.lock().await;
+        state.keys.push((public_key.into(), AuthSecretKey::RpoFalcon512(secret_key)));
+        state.accounts.insert(account.id(), (account, Some(seed)));
+
+        Ok(account_id)
+    }
+
+    /// Execute a transaction and commit it in a new block
+    async fn execute_transaction_with_foreign_accounts(
+        &mut self,
+        tx_script: &str,
+        advice_map: AdviceMap,
+        account_id: &LendingAccountId,
+        foreign_account_ids: &[LendingAccountId],
+    ) -> Result<TransactionResult> {
+        let account_id = AccountId::try_from(account_id)?;
+        let foreign_account_ids = to_miden_account_ids(foreign_account_ids)?;
+        let script = compile_tx_script(tx_script)?;
+
+        let mut state = self.inner.lock().await;
+
+        let tx_context = state.tx_context(account_id, &foreign_account_ids, &[], &[])?;
+        let mut tx_args = tx_context.tx_args().clone().with_tx_script(script);
+        tx_args.extend_advice_inputs(AdviceInputs { map: advice_map, ..Default::default() });
+
+        state.execute(account_id, tx_context, tx_args).await
+    }
+
+    /// Execute a transaction creating notes
+    ///
+    /// The transaction is committed with the next block, so the notes can be consumed as
+    /// unauthenticated notes in the same block, at the same reference block as this transaction.
+    async fn send_notes(
+        &mut self,
+        notes: Vec<MidenNote>,
+        account_id: &LendingAccountId,
+    ) -> Result<TransactionResult> {
+        let account_id = AccountId::try_from(account_id)?;
+        let script = send_notes_script(&notes)?;
+
+        let mut state = self.inner.lock().await;
+
+        let tx_context = state.tx_context(account_id, &[], &[], &[])?;
+        let mut tx_args = tx_context.tx_args().clone().with_tx_script(script);
+        tx_args.extend_output_note_recipients(&notes);
+
+        let result = state.execute_pending(account_id, tx_context, tx_args).await?;
+        state.pending_notes.extend(notes.into_iter().map(|note| (note.id(), note)));
+
+        Ok(result)
+    }
+
+    /// Execute a transaction consuming notes and commit it in a new block
+    ///
+    /// The mock chain only accepts unauthenticated notes created in the same block. Notes sent by
+    /// a pending transaction are consumed unauthenticated alongside it, while other notes are
+    /// first committed in a block of their own and consumed with their inclusion proofs.
+    async fn consume_notes_with_foreign_accounts(
+        &mut self,
+        notes: Vec<MidenNote>,
+        advice_map: AdviceMap,
+        account_id: &LendingAccountId,
+        foreign_account_ids: &[LendingAccountId],
+    ) -> Result<TransactionResult> {
+        let account_id = AccountId::try_from(account_id)?;
+        let foreign_account_ids = to_miden_account_ids(foreign_account_ids)?;
+
+        let mut state = self.inner.lock().await;
+
+        let (note_ids, unauthenticated_notes) =
+            if notes.iter().all(|note| state.pending_notes.contains_key(&note.id())) {
+                (Vec::new(), notes)
+            } else {
+                let uncommitted_notes: Vec<MidenNote> = notes
+                    .iter()
+                    .filter(|note| !state.chain.committed_notes().contains_key(&note.id()))
+                    .cloned()
+                    .collect();
+                if !uncommitted_notes.is_empty() {
+                    for note in uncommitted_notes {
+                        if !state.pending_notes.contains_key(&note.id()) {
+                            state.chain.add_pending_note(OutputNote::Full(note));
+                        }
+                    }
+                    state.prove_next_block()?;
+                }
+
+                (notes.iter().map(MidenNote::id).collect(), Vec::new())
+            };
+
+        let tx_context = state.tx_context(
+            account_id,
+            &foreign_account_ids,
+            &note_ids,
+            &unauthenticated_notes,
+        )?;
+        let mut tx_args = tx_context.tx_args().clone();
+        tx_args.extend_advice_inputs(AdviceInputs { map: advice_map, ..Default::default() });
+
+        let result = state.execute(account_id, tx_context, tx_args).await;
+        if result.is_err() && !state.pending_notes.is_empty() {
+            // The notes stay on-chain for a later transaction to consume
+            state.prove_next_block()?;
+        }
+
+        result
+    }
+
+    async fn execute_view_script_with_foreign_accounts(
+        &self,
+        tx_script: &str,
+        account_id: &LendingAccountId,
+        foreign_account_ids: &[LendingAccountId],
+    ) -> Result<Vec<u64>> {
+        let script = compile_tx_script(tx_script)?;
+        let foreign_account_ids = to_miden_account_ids(foreign_account_ids)?;
+        let stack = self
+            .view(AccountId::try_from(account_id)?, script, &foreign_account_ids)
+            .await?;
+
+        Ok(stack.iter().map(|felt| felt.as_int()).collect())
+    }
+
+    /// Get the number of the latest block of the chain
+    async fn get_block_number(&self) -> Result<BlockNumber> {
+        Ok(self.latest_block_num().await)
+    }
+
+    /// Get the latest state of an account
+    async fn get_account(&self, account_id: &LendingAccountId) -> Result<LendingAccount> {
+        let miden_account_id = AccountId::try_from(account_id)?;
+        let state = self.inner.lock().await;
+
+        if let Some((account, _)) = state.accounts.get(&miden_account_id) {
+            return Ok(LendingAccount::from(account));
+        }
+
+        let account = state
+            .chain
+            .committed_account(miden_account_id)
+            .with_context(|| format!("Account {} not found on mock chain", account_id.to_hex()))?;
+
+        Ok(LendingAccount::from(account))
+    }
+
+    /// Sign a message with the key kept for an account
+    async fn sign_message(&self, account_id: &LendingAccountId, message: Word) -> Result<Vec<Felt>> {
+        let public_key = self.get_account(account_id).await?.public_key;
+        let state = self.inner.lock().await;
+
+        let authenticator =
+            BasicAuthenticator::new_with_rng(&state.keys, RpoRandomCoin::new(message));
+        authenticator
+            .get_signature(public_key, &SigningInputs::Blind(message))
+            .await
+            .context("Failed to sign message")
+    }
+
+    async fn get_notes(&self, account_id: &LendingAccountId) -> Result<Vec<MidenNote>> {
+        let account_id = AccountId::try_from(account_id)?;
+        Ok(self
+            .inner
+            .lock()
+            .await
+            .notes
+            .iter()
+            .filter(|note| is_p2id_note_for(note, account_id))
+            .cloned()
+            .collect())
+    }
+}
+
+impl Default for MockChainState {
+    fn default() -> Self {
+        Self {
+            chain: MockChain::new(),
+            accounts: BTreeMap::new(),
+            keys: Vec::new(),
+            notes: Vec::new(),
+            pending_notes: BTreeMap::new(),
+        }
+    }
+}
+
+impl MockChainState {
+    /// Builds a transaction context against the latest block for an account
+    ///
+    /// Accounts created locally execute against their tracked state, with their seed if they are
+    /// not on-chain yet. `MockChain::build_tx_context` only knows the seeds and private state of
+    /// accounts it created itself.
+    ///
+    /// Foreign accounts are read from their committed state, which must be public. Input notes are
+    /// either committed on-chain, by ID, or unauthenticated.
+    fn tx_context(
+        &self,
+        account_id: AccountId,
+        foreign_account_ids: &[AccountId],
+        note_ids: &[NoteId],
+        unauthenticated_notes: &[MidenNote],
+    ) -> Result<TransactionContext> {
+        let foreign_account_inputs = foreign_account_ids
+            .iter()
+            .map(|foreign_account_id| {
+                self.chain.get_foreign_account_inputs(*foreign_account_id).with_context(|| {
+                    format!("Foreign account {} is not committed on mock chain", foreign_account_id)
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let builder = match self.accounts.get(&account_id) {
+            Some((account, seed)) => {
+                let tx_inputs =
+                    self.chain.get_transaction_inputs(account.clone(), *seed, note_ids, unauthenticated_notes)?;
+                TransactionContextBuilder::new(account.clone()).tx_inputs(tx_inputs)
+            },
+            None => self.chain.build_tx_context(account_id, note_ids, unauthenticated_notes)?,
+        };
+
+        builder.foreign_accounts(foreign_account_inputs).build()
+    }
+
+    /// Executes a transaction against the latest block and commits it in a new block
+    async fn execute(
+        &mut self,
+        account_id: AccountId,
+        tx_context: TransactionContext,
+        tx_args: TransactionArgs,
+    ) -> Result<TransactionResult> {
+        let result = self.execute_pending(account_id, tx_context, tx_args).await?;
+        self.prove_next_block()?;
+
+        Ok(result)
+    }
+
+    /// Proves the next block, committing the pending transactions
+    fn prove_next_block(&mut self) -> Result<()> {
+        self.chain.prove_next_block()?;
+        self.pending_notes.clear();
+
+        Ok(())
+    }
+
+    /// Executes a transaction against the latest block, to be committed with the next block
+    async fn execute_pending(
+        &mut self,
+        account_id: AccountId,
+        tx_context: TransactionContext,
+        tx_args: TransactionArgs,
+    ) -> Result<TransactionResult> {
+        let block_ref = tx_context.tx_inputs().block_header().block_num();
+
+        let authenticator =
+            BasicAuthenticator::new_with_rng(&self.keys, RpoRandomCoin::new(Word::default()));
+        let executor = TransactionExecutor::new(&tx_context).with_authenticator(&authenticator);
+        let executed = executor
+            .execute_transaction(account_id, block_ref, tx_context.input_notes().clone(), tx_args)
+            .await
+            .context("Transaction execution failed")?;
+
+        self.chain.add_pending_executed_transaction(&executed)?;
+        if let Some((account, seed)) = self.accounts.get_mut(&account_id) {
+            account.apply_delta(executed.account_delta())?;
+            *seed = None;
+        }
+
+        let output_notes = full_output_notes(&executed);
+        let consumed_note_ids: Vec<NoteId> = executed.input_notes().iter().map(|note| note.id()).collect();
+        self.notes.retain(|note| !consumed_note_ids.contains(&note.id()));
+        self.notes.extend_from_slice(&output_notes);
+
+        tracing::debug!(
+            "Executed transaction {} for account {} on mock chain",
+            executed.id(),
+            account_id
+        );
+
+        Ok(TransactionResult {
+            success: true,
+            tx_id: executed.id().as_bytes().to_vec(),
+            output_notes,
+        })
+    }
+}
+
+fn to_miden_account_ids(account_ids: &[LendingAccountId]) -> Result<Vec<AccountId>> {
+    account_ids.iter().map(AccountId::try_from).collect()
+}
@@ -1,20 +1,43 @@
 // Transaction building and execution module
 
-use crate::miden_client::{LendingClient, AccountId, TransactionScriptBuilder};
+use crate::components::lending_pool::{PriceSource, RateStrategy, ReserveConfig, RiskParameters};
+use crate::components::liquidation_note::create_liquidation_note;
+use crate::components::position_note::{create_position_note, payout_recipient, PositionOperation};
+use crate::components::price_note::{PriceNote, PriceNoteBuilder};
+use crate::components::price_oracle::PriceOracleAccount;
+use crate::errors::{with_lending_error, LendingError};
+use crate::fixed_point::{wad_div_up, wad_mul};
+use crate::accounts::{AccountInfo, ReservePosition};
+use crate::miden_client::{
+    AccountId, LendingBackend, LendingClient, TransactionResult, TransactionScriptBuilder,
+};
 use anyhow::{Context, Result};
+use miden_lib::note::utils::build_p2id_recipient;
+use miden_objects::{
+    account::AccountId as MidenAccountId,
+    asset::FungibleAsset,
+    note::{Note as MidenNote, NoteAssets, NoteDetails, NoteTag},
+    transaction::TransactionArgs,
+    vm::AdviceMap,
+    Felt, Word,
+};
+use serde::Serialize;
 use tracing::{info, debug};
 
 /// Transaction builder and executor for lending protocol operations
-pub struct TransactionBuilder {
-    client: LendingClient,
+pub struct TransactionBuilder<B: LendingBackend = LendingClient> {
+    client: B,
     pool_account_id: AccountId,
     oracle_account_id: AccountId,
 }
 
-impl TransactionBuilder {
+impl<B: LendingBackend> TransactionBuilder<B> {
     /// Create a new transaction builder
+    ///
+    /// Pool operations execute against the lending pool account, position tracking against the
+    /// user's lending account and price updates against the oracle account.
     pub fn new(
-        client: LendingClient,
+        client: B,
         pool_account_id: AccountId,
         oracle_account_id: AccountId,
     ) -> Self {
@@ -26,21 +49,18 @@ impl TransactionBuilder {
     }
 
     /// Deposit assets to the lending pool
-    pub async fn deposit(&mut self, user_account_id: &AccountId, asset_id: u64, amount: u64) -> Result<()> {
-        info!("Executing deposit: {} units of asset {}", amount, asset_id);
-
-        // Build transaction script that calls lending_pool::deposit
-        let script = TransactionScriptBuilder::new()
-            .push(amount)
-            .push(asset_id)
-            .call_procedure(&self.pool_account_id, "deposit")
-            .build();
+    ///
+    /// Returns the ID of the executed transaction, as do the other operations below.
+    pub async fn deposit(&mut self, user_account_id: &AccountId, asset_id: u64, amount: u64) -> Result<String> {
+        info!(
+            "Executing deposit for {}: {} units of asset {}",
+            user_account_id.to_hex(), amount, asset_id
+        );
 
-        debug!("Deposit script:\n{}", script);
+        self.ensure_reserve_listed(asset_id).await?;
 
-        // Execute transaction
-        let result = self.client
-            .execute_transaction(&script, user_account_id)
+        let result = self
+            .execute_position_transaction(PositionOperation::Deposit, user_account_id, asset_id, amount)
             .await
             .context("Failed to execute deposit transaction")?;
 
@@ -50,25 +70,25 @@ impl TransactionBuilder {
             anyhow::bail!("Deposit transaction failed");
         }
 
-        Ok(())
+        Ok(hex::encode(&result.tx_id))
     }
 
     /// Withdraw assets from the lending pool
-    pub async fn withdraw(&mut self, user_account_id: &AccountId, asset_id: u64, amount: u64) -> Result<()> {
-        info!("Executing withdrawal: {} units of asset {}", amount, asset_id);
+    pub async fn withdraw(&mut self, user_account_id: &AccountId, asset_id: u64, amount: u64) -> Result<String> {
+        info!(
+            "Executing withdrawal for {}: {} units of asset {}",
+            user_account_id.to_hex(), amount, asset_id
+        );
 
-        // Build transaction script that calls lending_pool::withdraw
-        let script = TransactionScriptBuilder::new()
-            .push(amount)
-            .push(asset_id)
-            .call_procedure(&self.pool_account_id, "withdraw")
-            .build();
+        self.ensure_reserve_listed(asset_id).await?;
 
-        debug!("Withdraw script:\n{}", script);
+        let balance = self.get_supply_balance(user_account_id, asset_id).await?;
+        if amount > balance {
+            anyhow::bail!("Withdrawal of {} exceeds supplied balance of {}", amount, balance);
+        }
 
-        // Execute transaction
-        let result = self.client
-            .execute_transaction(&script, user_account_id)
+        let result = self
+            .execute_position_transaction(PositionOperation::Withdraw, user_account_id, asset_id, amount)
             .await
             .context("Failed to execute withdraw transaction")?;
 
@@ -78,25 +98,17 @@ impl TransactionBuilder {
             anyhow::bail!("Withdrawal transaction failed");
         }
 
-        Ok(())
+        Ok(hex::encode(&result.tx_id))
     }
 
-    /// Supply collateral to user's lending account
-    pub async fn supply_collateral(&mut self, user_account_id: &AccountId, asset_id: u64, amount: u64) -> Result<()> {
+    /// Supply collateral to user's position in the lending pool
+    pub async fn supply_collateral(&mut self, user_account_id: &AccountId, asset_id: u64, amount: u64) -> Result<String> {
         info!("Supplying collateral: {} units of asset {}", amount, asset_id);
 
-        // Build transaction script that calls user_lending::supply_collateral
-        let script = TransactionScriptBuilder::new()
-            .push(amount)
-            .push(asset_id)
-            .call_procedure(user_account_id, "supply_collateral")
-            .build();
-
-        debug!("Supply collateral script:\n{}", script);
+        self.ensure_reserve_listed(asset_id).await?;
 
-        // Execute transaction
-        let result = self.client
-            .execute_transaction(&script, user_account_id)
+        let result = self
+            .execute_position_transaction(PositionOperation::SupplyCollateral, user_account_id, asset_id, amount)
             .await
             .context("Failed to execute supply collateral transaction")?;
 
@@ -106,137 +118,243 @@ impl TransactionBuilder {
             anyhow::bail!("Supply collateral transaction failed");
         }
 
-        Ok(())
+        Ok(hex::encode(&result.tx_id))
+    }
+
+    /// Withdraw collateral from user's position in the lending pool
+    ///
+    /// Fails if the remaining collateral would leave the user's health factor below 1.0.
+    pub async fn withdraw_collateral(&mut self, user_account_id: &AccountId, asset_id: u64, amount: u64) -> Result<String> {
+        info!("Withdrawing collateral: {} units of asset {}", amount, asset_id);
+
+        self.ensure_reserve_listed(asset_id).await?;
+
+        // The pool rejects withdrawals leaving the position unhealthy, so check it before sending
+        // the request note
+        let position = self.position_after(user_account_id, asset_id, amount, 0).await?;
+        if position.is_liquidatable() {
+            anyhow::bail!(
+                "Withdrawal would leave health factor at {:.2}, below 1.0",
+                position.health_factor as f64 / HEALTH_FACTOR_ONE as f64
+            );
+        }
+
+        let result = self
+            .execute_position_transaction(PositionOperation::WithdrawCollateral, user_account_id, asset_id, amount)
+            .await
+            .context("Failed to execute withdraw collateral transaction")?;
+
+        if result.success {
+            info!("Collateral withdrawn successfully. TX ID: {}", hex::encode(&result.tx_id));
+        } else {
+            anyhow::bail!("Withdraw collateral transaction failed");
+        }
+
+        Ok(hex::encode(&result.tx_id))
     }
 
     /// Borrow assets from the lending pool
-    pub async fn borrow(&mut self, user_account_id: &AccountId, asset_id: u64, amount: u64) -> Result<()> {
+    ///
+    /// Fails if the user's debt would exceed the LTV-weighted value of their collateral, or their
+    /// health factor would fall below 1.0.
+    pub async fn borrow(&mut self, user_account_id: &AccountId, asset_id: u64, amount: u64) -> Result<String> {
         info!("Executing borrow: {} units of asset {}", amount, asset_id);
 
-        // First, calculate health factor to verify borrowing is safe
-        // In production, this would involve:
-        // 1. Get user's collateral value (from user account storage)
-        // 2. Get current debt value
-        // 3. Calculate health factor
-        // 4. Assert health factor >= 1.0 after borrow
-
-        // Build transaction script that calls lending_pool::borrow
-        let script = TransactionScriptBuilder::new()
-            .push(amount)
-            .push(asset_id)
-            .call_procedure(&self.pool_account_id, "borrow")
-            .build();
+        self.ensure_reserve_listed(asset_id).await?;
 
-        debug!("Borrow script:\n{}", script);
+        // The pool rejects borrows past the LTV limit, so check the resulting position before
+        // sending the request note
+        let position = self.position_after(user_account_id, asset_id, 0, amount).await?;
+        if position.debt_value > position.borrow_limit {
+            anyhow::bail!(
+                "Borrow would bring debt worth {} past the LTV limit of {}",
+                position.debt_value, position.borrow_limit
+            );
+        }
+        if position.is_liquidatable() {
+            anyhow::bail!(
+                "Borrow would leave health factor at {:.2}, below 1.0",
+                position.health_factor as f64 / HEALTH_FACTOR_ONE as f64
+            );
+        }
 
-        // Execute transaction
-        let result = self.client
-            .execute_transaction(&script, user_account_id)
+        let result = self
+            .execute_position_transaction(PositionOperation::Borrow, user_account_id, asset_id, amount)
             .await
             .context("Failed to execute borrow transaction")?;
 
         if result.success {
             info!("Borrow successful. TX ID: {}", hex::encode(&result.tx_id));
-
-            // Update user's debt tracking
-            self.record_borrow_in_user_account(user_account_id, asset_id, amount).await?;
         } else {
             anyhow::bail!("Borrow transaction failed");
         }
 
-        Ok(())
+        Ok(hex::encode(&result.tx_id))
     }
 
     /// Repay borrowed assets
-    pub async fn repay(&mut self, user_account_id: &AccountId, asset_id: u64, amount: u64) -> Result<()> {
+    ///
+    /// Fails with [`LendingError::NoDebt`] if the user has no debt in the asset. The pool repays at
+    /// most the whole debt and refunds the excess to the user in a P2ID note.
+    pub async fn repay(&mut self, user_account_id: &AccountId, asset_id: u64, amount: u64) -> Result<String> {
         info!("Executing repayment: {} units of asset {}", amount, asset_id);
 
-        // Build transaction script that calls lending_pool::repay
-        let script = TransactionScriptBuilder::new()
-            .push(amount)
-            .push(asset_id)
-            .call_procedure(&self.pool_account_id, "repay")
-            .build();
+        self.ensure_reserve_listed(asset_id).await?;
 
-        debug!("Repay script:\n{}", script);
+        // The pool rejects repayments without debt, so check it before sending the request note
+        let debt = self.get_debt(user_account_id, asset_id).await?;
+        if debt == 0 {
+            return Err(LendingError::NoDebt.into());
+        }
+        if amount > debt {
+            info!("Repayment exceeds the debt of {} units, the excess is refunded", debt);
+        }
 
-        // Execute transaction
-        let result = self.client
-            .execute_transaction(&script, user_account_id)
+        let result = self
+            .execute_position_transaction(PositionOperation::Repay, user_account_id, asset_id, amount)
             .await
             .context("Failed to execute repay transaction")?;
 
         if result.success {
             info!("Repayment successful. TX ID: {}", hex::encode(&result.tx_id));
-
-            // Update user's debt tracking
-            self.record_repayment_in_user_account(user_account_id, asset_id, amount).await?;
         } else {
             anyhow::bail!("Repayment transaction failed");
         }
 
-        Ok(())
+        Ok(hex::encode(&result.tx_id))
     }
 
     /// Execute a liquidation
+    ///
+    /// Covers part of the debt of a borrower whose health factor is below 1.0: the pool's total
+    /// borrowed and the borrower's debt are both reduced by the covered debt, and collateral worth
+    /// the covered debt plus the collateral asset's liquidation bonus is taken from the borrower's
+    /// position. The liquidator sends a liquidation note carrying `debt_to_cover` of the debt
+    /// asset, which the pool applies in a single transaction paying the seized collateral out to
+    /// the liquidator in a P2ID note.
+    ///
+    /// The pool covers at most the [`CLOSE_FACTOR`](crate::components::lending_pool::CLOSE_FACTOR)
+    /// share of the borrower's debt in the asset, and at most the debt the borrower's collateral
+    /// is worth with the bonus. The part of `debt_to_cover` left uncovered is refunded in the
+    /// P2ID note.
+    ///
+    /// Fails without sending the note if the collateral and debt assets are the same, or if the
+    /// borrower's position is healthy at the prices the pool liquidates at.
     pub async fn liquidate(
         &mut self,
         liquidator_account_id: &AccountId,
-        _borrower_account_id: &AccountId,
+        borrower_account_id: &AccountId,
         collateral_asset_id: u64,
         debt_asset_id: u64,
         debt_to_cover: u64,
-    ) -> Result<()> {
+    ) -> Result<Liquidation> {
         info!(
-            "Executing liquidation: covering {} units of debt asset {} for borrower",
-            debt_to_cover, debt_asset_id
-        );
-
-        // Verify borrower is undercollateralized
-        // This would check health factor < 1.0
-
-        // Build liquidation transaction
-        // In production, this would create a liquidation note
-        let script = format!(
-            "begin\n    push.{}\n    push.{}\n    push.{}\n    # Liquidation logic here\nend",
-            debt_to_cover, debt_asset_id, collateral_asset_id
+            "Executing liquidation by {}: covering {} units of debt asset {} for borrower {} (collateral asset {})",
+            liquidator_account_id.to_hex(),
+            debt_to_cover,
+            debt_asset_id,
+            borrower_account_id.to_hex(),
+            collateral_asset_id
         );
 
-        debug!("Liquidation script:\n{}", script);
+        // The pool pays the seized collateral and the uncovered debt out in the same note
+        if collateral_asset_id == debt_asset_id {
+            anyhow::bail!("Liquidation collateral and debt must be different assets");
+        }
+        self.ensure_reserve_listed(collateral_asset_id).await?;
+        self.ensure_reserve_listed(debt_asset_id).await?;
+
+        // Verify borrower is undercollateralized at the prices the pool will use
+        let health_factor = self.calculate_liquidation_health_factor(borrower_account_id).await?;
+        if health_factor >= HEALTH_FACTOR_ONE {
+            anyhow::bail!(
+                "Borrower's health factor is {:.2}, positions can only be liquidated below 1.0",
+                health_factor as f64 / HEALTH_FACTOR_ONE as f64
+            );
+        }
 
-        // Execute transaction
-        let result = self.client
-            .execute_transaction(&script, liquidator_account_id)
+        let debt = self.reserve_asset(debt_asset_id, debt_to_cover).await?;
+        let note = create_liquidation_note(
+            liquidator_account_id.try_into()?,
+            (&self.pool_account_id).try_into()?,
+            borrower_account_id.try_into()?,
+            collateral_asset_id,
+            debt_asset_id,
+            debt,
+            random_serial_num(),
+            self.client.get_block_number().await?,
+        )?;
+
+        // Execute transaction, valuing the borrower's position with the oracle
+        let result = self
+            .submit_request(liquidator_account_id, note)
             .await
             .context("Failed to execute liquidation transaction")?;
 
-        if result.success {
-            info!("Liquidation successful. TX ID: {}", hex::encode(&result.tx_id));
-        } else {
+        if !result.success {
             anyhow::bail!("Liquidation transaction failed");
         }
+        info!("Liquidation successful. TX ID: {}", hex::encode(&result.tx_id));
+
+        let collateral_faucet_id = self.get_reserve_faucet(collateral_asset_id).await?;
+        let paid_out = |faucet_id| -> u64 {
+            result
+                .output_notes
+                .iter()
+                .flat_map(|note| note.assets().iter_fungible())
+                .filter(|asset| asset.faucet_id() == faucet_id)
+                .map(|asset| asset.amount())
+                .sum()
+        };
+        let collateral_seized = paid_out(collateral_faucet_id);
+        let debt_covered = debt_to_cover
+            .checked_sub(paid_out(debt.faucet_id()))
+            .context("Liquidation refunded more debt than was sent")?;
+        info!(
+            "Liquidator covers {} units of debt and receives {} units of collateral asset {}",
+            debt_covered,
+            collateral_seized,
+            collateral_asset_id
+        );
 
-        Ok(())
+        Ok(Liquidation {
+            tx_id: hex::encode(&result.tx_id),
+            health_factor_before: health_factor,
+            debt_covered,
+            collateral_seized,
+        })
     }
 
     /// Update asset price in the oracle
-    pub async fn update_price(&mut self, admin_account_id: &AccountId, asset_id: u64, price: u64) -> Result<()> {
-        info!("Updating price for asset {}: {}", asset_id, price);
+    ///
+    /// The transaction must be signed by an authorized updater, otherwise it fails with
+    /// [`LendingError::UnauthorizedPriceUpdater`](crate::errors::LendingError).
+    pub async fn update_price(&mut self, updater_account_id: &AccountId, asset_id: u64, price: u64) -> Result<String> {
+        info!(
+            "Updating price for asset {}: {} (by {})",
+            asset_id, price, updater_account_id.to_hex()
+        );
+
+        self.ensure_price_feed(asset_id).await?;
+
+        let updater = self.client.get_account(updater_account_id).await?;
 
         // Build transaction script that calls price_oracle::update_asset_price
         let script = TransactionScriptBuilder::new()
-            .push(price)
             .push(asset_id)
-            .call_procedure(&self.oracle_account_id, "update_asset_price")
+            .push(price)
+            .push_word(updater.public_key)
+            .call_procedure("price_oracle", "update_asset_price")
             .build();
 
         debug!("Update price script:\n{}", script);
 
         // Execute transaction
         let result = self.client
-            .execute_transaction(&script, admin_account_id)
+            .execute_transaction(&script, &self.oracle_account_id)
             .await
-            .context("Failed to execute price update transaction")?;
+            .context("Failed to execute price update transaction")
+            .map_err(with_lending_error)?;
 
         if result.success {
             info!("Price updated successfully. TX ID: {}", hex::encode(&result.tx_id));
@@ -244,136 +362,1317 @@ impl TransactionBuilder {
             anyhow::bail!("Price update transaction failed");
         }
 
-        Ok(())
+        Ok(hex::encode(&result.tx_id))
     }
 
-    /// Get reserve data from lending pool
-    pub async fn get_reserve_data(&mut self, asset_id: u64) -> Result<ReserveData> {
-        info!("Fetching reserve data for asset {}", asset_id);
+    /// Sign an asset price off-chain with the key of an updater account
+    ///
+    /// Returns a price note published by the updater, which any account can relay to the oracle
+    /// with [`relay_price_notes`](Self::relay_price_notes). `timestamp` is the time the price was
+    /// observed at, in seconds.
+    pub async fn sign_price(
+        &mut self,
+        updater_account_id: &AccountId,
+        asset_id: u64,
+        price: u64,
+        timestamp: u64,
+    ) -> Result<PriceNote> {
+        info!(
+            "Signing price for asset {}: {} at {} (by {})",
+            asset_id, price, timestamp, updater_account_id.to_hex()
+        );
+
+        self.ensure_price_feed(asset_id).await?;
+
+        let builder = PriceNoteBuilder::new(
+            MidenAccountId::try_from(&self.oracle_account_id)?,
+            asset_id,
+            price,
+            timestamp,
+        );
+        let updater = self.client.get_account(updater_account_id).await?;
+        let signature = self.client.sign_message(updater_account_id, builder.message()).await?;
+
+        builder
+            .build(MidenAccountId::try_from(updater_account_id)?, updater.public_key, signature)
+            .context("Failed to build price note")
+    }
+
+    /// Relay signed price notes to the oracle
+    ///
+    /// The oracle consumes the notes in a single transaction, verifying the signature of each
+    /// price before submitting it. The transaction needs no signature, so any account can relay
+    /// the notes; it fails with [`LendingError::UnauthorizedPriceUpdater`](crate::errors::LendingError)
+    /// if a note was signed by an unregistered key, and with `ERR_PRICE_ORACLE_OUTDATED_PRICE` if an updater already
+    /// submitted a newer price.
+    pub async fn relay_price_notes(&mut self, price_notes: &[PriceNote]) -> Result<String> {
+        info!("Relaying {} price notes to the oracle", price_notes.len());
+
+        let oracle_id = MidenAccountId::try_from(&self.oracle_account_id)?;
+        if let Some(price_note) = price_notes.iter().find(|note| note.oracle_id() != oracle_id) {
+            anyhow::bail!("Price note was signed for oracle {}", price_note.oracle_id());
+        }
+
+        let mut advice_map = AdviceMap::default();
+        for price_note in price_notes {
+            advice_map.extend(price_note.advice_map());
+        }
+        let notes = price_notes.iter().map(|price_note| price_note.note().clone()).collect();
+
+        let result = self.client
+            .consume_notes(notes, advice_map, &self.oracle_account_id)
+            .await
+            .context("Failed to execute price note transaction")
+            .map_err(with_lending_error)?;
+
+        if !result.success {
+            anyhow::bail!("Price note transaction failed");
+        }
+
+        Ok(hex::encode(&result.tx_id))
+    }
+
+    /// Authorize an account to update oracle prices
+    ///
+    /// `add_updater` is an admin procedure, so the transaction is signed with the oracle's key.
+    pub async fn add_price_updater(&mut self, updater_account_id: &AccountId) -> Result<String> {
+        info!("Authorizing {} to update oracle prices", updater_account_id.to_hex());
+
+        self.manage_updater(updater_account_id, "add_updater").await
+    }
+
+    /// Revoke the authorization of an oracle price updater
+    pub async fn remove_price_updater(&mut self, updater_account_id: &AccountId) -> Result<String> {
+        info!("Revoking oracle price updates by {}", updater_account_id.to_hex());
+
+        self.manage_updater(updater_account_id, "remove_updater").await
+    }
+
+    /// Add an oracle price feed for an asset, reporting its initial price
+    ///
+    /// The feed starts with the default settings. `add_price_feed` is an admin procedure, so the
+    /// transaction is signed with the oracle's key.
+    pub async fn add_price_feed(&mut self, asset_id: u64, price: u64) -> Result<String> {
+        info!("Adding price feed of asset {} at {}", asset_id, price);
+
+        if self.has_price_feed(asset_id).await? {
+            anyhow::bail!("Asset {} already has a price feed in the oracle", asset_id);
+        }
+        if price == 0 {
+            anyhow::bail!("Price must be positive");
+        }
 
-        // Build query script that calls lending_pool::get_reserve_data
         let script = TransactionScriptBuilder::new()
+            .push(price)
             .push(asset_id)
-            .call_procedure(&self.pool_account_id, "get_reserve_data")
+            .call_procedure("price_oracle", "add_price_feed")
             .build();
 
-        debug!("Get reserve data script:\n{}", script);
+        debug!("Add price feed script:\n{}", script);
 
-        // Execute transaction (read-only)
-        let _result = self.client
-            .execute_transaction(&script, &self.pool_account_id)
+        let result = self.client
+            .execute_transaction(&script, &self.oracle_account_id)
             .await
-            .context("Failed to query reserve data")?;
+            .context("Failed to execute add price feed transaction")?;
 
-        // In production, parse the transaction output to extract:
-        // [total_liquidity, total_borrowed, liquidity_rate, borrow_rate]
+        if !result.success {
+            anyhow::bail!("Add price feed transaction failed");
+        }
 
-        // Placeholder values
-        Ok(ReserveData {
-            asset_id,
-            total_liquidity: 0,
-            total_borrowed: 0,
-            liquidity_rate: 0,
-            borrow_rate: 0,
-        })
+        Ok(hex::encode(&result.tx_id))
     }
 
-    /// Get asset price from oracle
-    pub async fn get_price(&mut self, asset_id: u64) -> Result<u64> {
-        info!("Fetching price for asset {}", asset_id);
+    /// Set how many fresh submissions are needed to report an asset's oracle price
+    ///
+    /// The reported price is the median of the fresh submissions, so with a quorum of `2f + 1`
+    /// up to `f` updaters cannot move it on their own. `set_price_quorum` is an admin procedure.
+    pub async fn set_price_quorum(&mut self, asset_id: u64, quorum: u64) -> Result<String> {
+        info!("Setting price quorum of asset {} to {}", asset_id, quorum);
+
+        self.ensure_price_feed(asset_id).await?;
+        if quorum == 0 || quorum > PriceOracleAccount::MAX_UPDATERS {
+            anyhow::bail!(
+                "Price quorum must be between 1 and {} updaters",
+                PriceOracleAccount::MAX_UPDATERS
+            );
+        }
 
-        // Build query script that calls price_oracle::get_asset_price
         let script = TransactionScriptBuilder::new()
             .push(asset_id)
-            .call_procedure(&self.oracle_account_id, "get_asset_price")
+            .push(quorum)
+            .call_procedure("price_oracle", "set_price_quorum")
             .build();
 
-        debug!("Get price script:\n{}", script);
+        debug!("Set price quorum script:\n{}", script);
 
-        // Execute transaction (read-only)
-        let _result = self.client
+        let result = self.client
             .execute_transaction(&script, &self.oracle_account_id)
             .await
-            .context("Failed to query asset price")?;
+            .context("Failed to execute price quorum transaction")?;
 
-        // In production, parse the transaction output
-        // Placeholder
-        Ok(100000000) // $1.00 with 8 decimals
+        if !result.success {
+            anyhow::bail!("Price quorum transaction failed");
+        }
+
+        Ok(hex::encode(&result.tx_id))
     }
 
-    /// Calculate user's health factor
-    pub async fn calculate_health_factor(&mut self, user_account_id: &AccountId) -> Result<u64> {
-        info!("Calculating health factor for user");
+    /// Calls an oracle procedure managing the updater set with the key of an updater account
+    async fn manage_updater(&mut self, updater_account_id: &AccountId, procedure: &str) -> Result<String> {
+        let updater = self.client.get_account(updater_account_id).await?;
+
+        let script = TransactionScriptBuilder::new()
+            .push_word(updater.public_key)
+            .call_procedure("price_oracle", procedure)
+            .build();
+
+        debug!("Updater script:\n{}", script);
+
+        let result = self.client
+            .execute_transaction(&script, &self.oracle_account_id)
+            .await
+            .context("Failed to execute updater transaction")?;
+
+        if !result.success {
+            anyhow::bail!("Updater transaction failed");
+        }
+
+        Ok(hex::encode(&result.tx_id))
+    }
+
+    /// Set how long an asset's oracle price stays valid after its last update, in seconds
+    ///
+    /// Borrows, collateral withdrawals and liquidations fail while a price they read is older.
+    /// `set_max_price_age` is an admin procedure, so the transaction is signed with the oracle's
+    /// key.
+    pub async fn set_max_price_age(&mut self, asset_id: u64, max_age: u64) -> Result<String> {
+        info!("Setting maximum price age of asset {} to {}s", asset_id, max_age);
+
+        self.ensure_price_feed(asset_id).await?;
+        if max_age == 0 {
+            anyhow::bail!("Maximum price age must be positive");
+        }
+
+        let script = TransactionScriptBuilder::new()
+            .push(asset_id)
+            .push(max_age)
+            .call_procedure("price_oracle", "set_max_price_age")
+            .build();
+
+        debug!("Set max price age script:\n{}", script);
+
+        let result = self.client
+            .execute_transaction(&script, &self.oracle_account_id)
+            .await
+            .context("Failed to execute max price age transaction")?;
 
-        // Build script that calls user_lending::calculate_health_factor
-        let script = format!(
-            "begin\n    # Get collateral and debt from storage\n    # Calculate health factor\nend"
+        if !result.success {
+            anyhow::bail!("Max price age transaction failed");
+        }
+
+        Ok(hex::encode(&result.tx_id))
+    }
+
+    /// Set the window of an asset's time-weighted average oracle price, in seconds
+    ///
+    /// `set_twap_window` is an admin procedure, so the transaction is signed with the oracle's key.
+    pub async fn set_twap_window(&mut self, asset_id: u64, window: u64) -> Result<String> {
+        info!("Setting TWAP window of asset {} to {}s", asset_id, window);
+
+        self.ensure_price_feed(asset_id).await?;
+        if window == 0 {
+            anyhow::bail!("TWAP window must be positive");
+        }
+
+        let script = TransactionScriptBuilder::new()
+            .push(asset_id)
+            .push(window)
+            .call_procedure("price_oracle", "set_twap_window")
+            .build();
+
+        debug!("Set TWAP window script:\n{}", script);
+
+        let result = self.client
+            .execute_transaction(&script, &self.oracle_account_id)
+            .await
+            .context("Failed to execute TWAP window transaction")?;
+
+        if !result.success {
+            anyhow::bail!("TWAP window transaction failed");
+        }
+
+        Ok(hex::encode(&result.tx_id))
+    }
+
+    /// Configure the circuit breaker of an asset's oracle price
+    ///
+    /// A new median moving more than `max_deviation` basis points away from the price reported
+    /// `interval` seconds earlier pauses the feed instead of being reported. Reads of a paused
+    /// price fail with `ERR_PRICE_ORACLE_PAUSED` until the feed is resumed. A zero maximum deviation
+    /// disables the circuit breaker. `set_circuit_breaker` is an admin procedure.
+    pub async fn set_circuit_breaker(&mut self, asset_id: u64, max_deviation: u64, interval: u64) -> Result<String> {
+        info!(
+            "Setting circuit breaker of asset {} to {} bps over {}s",
+            asset_id, max_deviation, interval
         );
 
-        debug!("Health factor script:\n{}", script);
+        self.ensure_price_feed(asset_id).await?;
+        if max_deviation != 0 && interval == 0 {
+            anyhow::bail!("Circuit breaker interval must be positive");
+        }
+
+        let script = TransactionScriptBuilder::new()
+            .push(asset_id)
+            .push(interval)
+            .push(max_deviation)
+            .call_procedure("price_oracle", "set_circuit_breaker")
+            .build();
+
+        debug!("Set circuit breaker script:\n{}", script);
 
-        // Execute transaction (read-only)
-        let _result = self.client
-            .execute_transaction(&script, user_account_id)
+        let result = self.client
+            .execute_transaction(&script, &self.oracle_account_id)
             .await
-            .context("Failed to calculate health factor")?;
+            .context("Failed to execute circuit breaker transaction")?;
+
+        if !result.success {
+            anyhow::bail!("Circuit breaker transaction failed");
+        }
 
-        // In production, parse the result
-        // Placeholder: return 1.5 (healthy)
-        Ok(15000) // 1.5 in basis points (10000 = 1.0)
+        Ok(hex::encode(&result.tx_id))
     }
 
-    // Helper functions
+    /// Resume an oracle price feed paused by its circuit breaker
+    ///
+    /// The median of the fresh submissions is reported however far it moved. `resume_price_feed`
+    /// is an admin procedure.
+    pub async fn resume_price_feed(&mut self, asset_id: u64) -> Result<String> {
+        info!("Resuming price feed of asset {}", asset_id);
+
+        self.ensure_price_feed(asset_id).await?;
 
-    /// Record borrow in user's account storage
-    async fn record_borrow_in_user_account(
-        &mut self,
-        user_account_id: &AccountId,
-        asset_id: u64,
-        amount: u64,
-    ) -> Result<()> {
         let script = TransactionScriptBuilder::new()
-            .push(amount)
             .push(asset_id)
-            .call_procedure(user_account_id, "record_borrow")
+            .call_procedure("price_oracle", "resume_price_feed")
             .build();
 
-        self.client
-            .execute_transaction(&script, user_account_id)
-            .await?;
+        debug!("Resume price feed script:\n{}", script);
 
-        Ok(())
+        let result = self.client
+            .execute_transaction(&script, &self.oracle_account_id)
+            .await
+            .context("Failed to execute resume price feed transaction")?;
+
+        if !result.success {
+            anyhow::bail!("Resume price feed transaction failed");
+        }
+
+        Ok(hex::encode(&result.tx_id))
+    }
+
+    /// List a new asset in the lending pool
+    ///
+    /// The oracle must have a price feed for the asset, and the faucet must not back another
+    /// reserve. `add_reserve` is an admin procedure, so the transaction is signed with the pool's
+    /// key.
+    pub async fn add_reserve(&mut self, reserve: ReserveConfig) -> Result<String> {
+        info!(
+            "Listing asset {} of faucet {} with {} decimals",
+            reserve.asset_id, reserve.faucet_id, reserve.decimals
+        );
+
+        reserve.validate()?;
+        self.ensure_price_feed(reserve.asset_id).await?;
+
+        // The pool rejects faucets already backing a reserve, so check them before building the
+        // transaction
+        let reserves = self.get_reserves().await?;
+        if let Some(listed) = reserves.iter().find(|listed| listed.faucet_id == reserve.faucet_id) {
+            anyhow::bail!("Faucet {} already backs reserve {}", reserve.faucet_id, listed.asset_id);
+        }
+
+        // Build transaction script that calls lending_pool::add_reserve
+        let builder = TransactionScriptBuilder::new()
+            .push(reserve.risk.liquidation_bonus)
+            .push(reserve.risk.liquidation_threshold)
+            .push(reserve.risk.ltv);
+        let script = push_strategy(builder, &reserve.strategy)
+            .push(reserve.faucet_id.suffix().as_int())
+            .push(reserve.faucet_id.prefix().as_u64())
+            .push(reserve.asset_id)
+            .push(reserve.decimals as u64)
+            .call_procedure("lending_pool", "add_reserve")
+            .build();
+
+        debug!("Add reserve script:\n{}", script);
+
+        // Execute transaction
+        // The pool checks the oracle has a price feed for the asset
+        let result = self.client
+            .execute_transaction_with_foreign_accounts(
+                &script,
+                AdviceMap::default(),
+                &self.pool_account_id,
+                std::slice::from_ref(&self.oracle_account_id),
+            )
+            .await
+            .context("Failed to execute add reserve transaction")?;
+
+        if result.success {
+            info!("Reserve added successfully. TX ID: {}", hex::encode(&result.tx_id));
+        } else {
+            anyhow::bail!("Add reserve transaction failed");
+        }
+
+        Ok(hex::encode(&result.tx_id))
     }
 
-    /// Record repayment in user's account storage
-    async fn record_repayment_in_user_account(
+    /// Pay out reserves accrued to the treasury
+    ///
+    /// `claim_reserves` is an admin procedure, so the transaction is signed with the pool's key. The
+    /// pool sends the claimed amount out of its vault to the treasury account in a public P2ID
+    /// note, which the treasury consumes to receive it.
+    pub async fn claim_reserves(
         &mut self,
-        user_account_id: &AccountId,
         asset_id: u64,
         amount: u64,
-    ) -> Result<()> {
+        treasury_account_id: &AccountId,
+    ) -> Result<String> {
+        info!(
+            "Claiming {} units of asset {} reserves to treasury {}",
+            amount, asset_id, treasury_account_id.to_hex()
+        );
+
+        self.ensure_reserve_listed(asset_id).await?;
+
+        // The pool outputs the P2ID note in full given the details of its recipient
+        let treasury_id = MidenAccountId::try_from(treasury_account_id)?;
+        let recipient = build_p2id_recipient(treasury_id, random_serial_num())?;
+        let mut tx_args = TransactionArgs::default();
+        tx_args.add_output_note_recipient(NoteDetails::new(NoteAssets::default(), recipient.clone()));
+
+        // Build transaction script that calls lending_pool::claim_reserves
         let script = TransactionScriptBuilder::new()
+            .push_word(recipient.digest())
+            .push(NoteTag::from_account_id(treasury_id).as_u32() as u64)
+            .push(asset_id)
             .push(amount)
+            .call_procedure("lending_pool", "claim_reserves")
+            .build();
+
+        debug!("Claim reserves script:\n{}", script);
+
+        // Execute transaction
+        let result = self.client
+            .execute_transaction_with_advice(&script, tx_args.advice_inputs().map.clone(), &self.pool_account_id)
+            .await
+            .context("Failed to execute claim reserves transaction")?;
+
+        if result.success {
+            info!("Reserves claimed successfully. TX ID: {}", hex::encode(&result.tx_id));
+            for note in &result.output_notes {
+                info!("Treasury can consume P2ID note {}", note.id());
+            }
+        } else {
+            anyhow::bail!("Claim reserves transaction failed");
+        }
+
+        Ok(hex::encode(&result.tx_id))
+    }
+
+    /// Get the reserves accrued to the treasury for an asset, up to the latest block
+    pub async fn get_accrued_to_treasury(&mut self, asset_id: u64) -> Result<u64> {
+        let script = TransactionScriptBuilder::new()
             .push(asset_id)
-            .call_procedure(user_account_id, "record_repayment")
+            .call_procedure("lending_pool", "get_accrued_to_treasury")
             .build();
 
-        self.client
-            .execute_transaction(&script, user_account_id)
-            .await?;
+        let stack = self.client
+            .execute_view_script(&script, &self.pool_account_id)
+            .await
+            .context("Failed to query treasury reserves")?;
 
-        Ok(())
+        // Output stack: [accrued_to_treasury]
+        Ok(stack[0])
     }
+
+    /// Replace the interest rate strategy of a listed reserve
+    ///
+    /// `set_rate_strategy` is an admin procedure, so the transaction is signed with the pool's key.
+    pub async fn set_rate_strategy(&mut self, asset_id: u64, strategy: RateStrategy) -> Result<String> {
+        info!("Setting rate strategy of asset {}: {:?}", asset_id, strategy);
+
+        strategy.validate()?;
+        self.ensure_reserve_listed(asset_id).await?;
+
+        // Build transaction script that calls lending_pool::set_rate_strategy
+        let script = push_strategy(TransactionScriptBuilder::new(), &strategy)
+            .push(asset_id)
+            .call_procedure("lending_pool", "set_rate_strategy")
+            .build();
+
+        debug!("Set rate strategy script:\n{}", script);
+
+        // Execute transaction
+        let result = self.client
+            .execute_transaction(&script, &self.pool_account_id)
+            .await
+            .context("Failed to execute set rate strategy transaction")?;
+
+        if result.success {
+            info!("Rate strategy updated successfully. TX ID: {}", hex::encode(&result.tx_id));
+        } else {
+            anyhow::bail!("Set rate strategy transaction failed");
+        }
+
+        Ok(hex::encode(&result.tx_id))
+    }
+
+    /// Get the interest rate strategy of a reserve
+    pub async fn get_rate_strategy(&mut self, asset_id: u64) -> Result<RateStrategy> {
+        let script = TransactionScriptBuilder::new()
+            .push(asset_id)
+            .call_procedure("lending_pool", "get_rate_strategy")
+            .build();
+
+        let stack = self.client
+            .execute_view_script(&script, &self.pool_account_id)
+            .await
+            .context("Failed to query rate strategy")?;
+
+        // Output stack: [base_rate, slope1, slope2, optimal_utilization, reserve_factor]
+        Ok(RateStrategy {
+            base_rate: stack[0],
+            slope1: stack[1],
+            slope2: stack[2],
+            optimal_utilization: stack[3],
+            reserve_factor: stack[4],
+        })
+    }
+
+    /// Get the risk parameters of a reserve's asset as collateral
+    pub async fn get_risk_parameters(&mut self, asset_id: u64) -> Result<RiskParameters> {
+        let script = TransactionScriptBuilder::new()
+            .push(asset_id)
+            .call_procedure("lending_pool", "get_risk_parameters")
+            .build();
+
+        let stack = self.client
+            .execute_view_script(&script, &self.pool_account_id)
+            .await
+            .context("Failed to query risk parameters")?;
+
+        // Output stack: [ltv, liquidation_threshold, liquidation_bonus]
+        Ok(RiskParameters {
+            ltv: stack[0],
+            liquidation_threshold: stack[1],
+            liquidation_bonus: stack[2],
+        })
+    }
+
+    /// Select whether liquidations value positions at spot or time-weighted average prices
+    ///
+    /// `set_liquidation_price_source` is an admin procedure, so the transaction is signed with the
+    /// pool's key.
+    pub async fn set_liquidation_price_source(&mut self, source: PriceSource) -> Result<String> {
+        info!("Setting liquidation price source to {}", source);
+
+        let script = TransactionScriptBuilder::new()
+            .push(u64::from(source))
+            .call_procedure("lending_pool", "set_liquidation_price_source")
+            .build();
+
+        debug!("Set liquidation price source script:\n{}", script);
+
+        let result = self.client
+            .execute_transaction(&script, &self.pool_account_id)
+            .await
+            .context("Failed to execute liquidation price source transaction")?;
+
+        if !result.success {
+            anyhow::bail!("Liquidation price source transaction failed");
+        }
+
+        Ok(hex::encode(&result.tx_id))
+    }
+
+    /// Get the price source liquidations value positions at
+    pub async fn get_liquidation_price_source(&mut self) -> Result<PriceSource> {
+        let script = TransactionScriptBuilder::new()
+            .call_procedure("lending_pool", "get_liquidation_price_source")
+            .build();
+
+        let stack = self.client
+            .execute_view_script(&script, &self.pool_account_id)
+            .await
+            .context("Failed to query liquidation price source")?;
+
+        // Output stack: [price_source]
+        PriceSource::try_from(stack[0])
+    }
+
+    pub async fn get_reserve_data(&mut self, asset_id: u64) -> Result<ReserveData> {
+        info!("Fetching reserve data for asset {}", asset_id);
+
+        // Build query script that calls lending_pool::get_reserve_data
+        let script = TransactionScriptBuilder::new()
+            .push(asset_id)
+            .call_procedure("lending_pool", "get_reserve_data")
+            .build();
+
+        debug!("Get reserve data script:\n{}", script);
+
+        // Execute script (read-only)
+        let stack = self.client
+            .execute_view_script(&script, &self.pool_account_id)
+            .await
+            .context("Failed to query reserve data")?;
+
+        let (liquidity_index, borrow_index) = self.get_reserve_indices(asset_id).await?;
+
+        // Output stack: [total_liquidity, total_borrowed, liquidity_rate, borrow_rate]
+        Ok(ReserveData {
+            asset_id,
+            total_liquidity: stack[0],
+            total_borrowed: stack[1],
+            liquidity_rate: stack[2],
+            borrow_rate: stack[3],
+            liquidity_index,
+            borrow_index,
+        })
+    }
+
+    /// Get the liquidity and borrow indices of a reserve, accrued up to the latest block
+    pub async fn get_reserve_indices(&mut self, asset_id: u64) -> Result<(u64, u64)> {
+        let script = TransactionScriptBuilder::new()
+            .push(asset_id)
+            .call_procedure("lending_pool", "get_reserve_indices")
+            .build();
+
+        let stack = self.client
+            .execute_view_script(&script, &self.pool_account_id)
+            .await
+            .context("Failed to query reserve indices")?;
+
+        // Output stack: [liquidity_index, borrow_index]
+        Ok((stack[0], stack[1]))
+    }
+
+    /// Get the balance a user has supplied to a reserve, including accrued interest
+    pub async fn get_supply_balance(&mut self, user_account_id: &AccountId, asset_id: u64) -> Result<u64> {
+        let position = self.get_reserve_position(user_account_id, asset_id).await?;
+        let (liquidity_index, _) = self.get_reserve_indices(asset_id).await?;
+        Ok(unscale(position.scaled_supply, liquidity_index))
+    }
+
+    /// Get the amount repaying a user's whole debt in a reserve, grown to the latest update
+    pub async fn get_debt(&mut self, user_account_id: &AccountId, asset_id: u64) -> Result<u64> {
+        let position = self.get_reserve_position(user_account_id, asset_id).await?;
+        let (_, borrow_index) = self.get_reserve_indices(asset_id).await?;
+        Ok(repayment_of(position.scaled_debt, borrow_index))
+    }
+
+    /// Get a user's position in a reserve
+    pub async fn get_reserve_position(&mut self, user_account_id: &AccountId, asset_id: u64) -> Result<ReservePosition> {
+        let script = TransactionScriptBuilder::new()
+            .push_account_id(user_account_id.try_into()?)
+            .push(asset_id)
+            .call_procedure("lending_pool", "get_position")
+            .build();
+
+        let stack = self.client
+            .execute_view_script(&script, &self.pool_account_id)
+            .await
+            .context("Failed to query position")?;
+
+        // Output stack: [scaled_supply, collateral, scaled_debt]
+        Ok(ReservePosition {
+            asset_id,
+            scaled_supply: stack[0],
+            collateral: stack[1],
+            scaled_debt: stack[2],
+        })
+    }
+
+    /// Get a user's position in every listed reserve, with its health factor
+    pub async fn get_account_info(&mut self, user_account_id: &AccountId) -> Result<AccountInfo> {
+        let listed_reserves = self.get_listed_reserves().await?;
+        let mut reserves = Vec::with_capacity(listed_reserves.len());
+        for (asset_id, _) in &listed_reserves {
+            reserves.push(self.get_reserve_position(user_account_id, *asset_id).await?);
+        }
+
+        let position = self
+            .value_position(user_account_id, &listed_reserves, &reserves, PriceSource::Spot)
+            .await?;
+
+        Ok(AccountInfo {
+            account_id: user_account_id.to_hex(),
+            reserves,
+            health_factor: position.health_factor,
+        })
+    }
+
+    /// Check whether an asset is listed in the lending pool
+    pub async fn is_reserve_listed(&mut self, asset_id: u64) -> Result<bool> {
+        let script = TransactionScriptBuilder::new()
+            .push(asset_id)
+            .call_procedure("lending_pool", "is_reserve_listed")
+            .build();
+
+        let stack = self.client
+            .execute_view_script(&script, &self.pool_account_id)
+            .await
+            .context("Failed to query reserve listing")?;
+
+        // Output stack: [is_listed]
+        Ok(stack[0] == 1)
+    }
+
+    /// Fails before building a pool transaction for an asset the pool would reject
+    async fn ensure_reserve_listed(&mut self, asset_id: u64) -> Result<()> {
+        if !self.is_reserve_listed(asset_id).await? {
+            anyhow::bail!("Asset {} is not listed in the lending pool", asset_id);
+        }
+
+        Ok(())
+    }
+
+    /// Get the reserves listed in the lending pool, in listing order
+    pub async fn get_reserves(&mut self) -> Result<Vec<ReserveConfig>> {
+        info!("Fetching listed reserves");
+
+        let mut reserves = Vec::new();
+        for (asset_id, decimals) in self.get_listed_reserves().await? {
+            let faucet_id = self.get_reserve_faucet(asset_id).await?;
+            let strategy = self.get_rate_strategy(asset_id).await?;
+            let risk = self.get_risk_parameters(asset_id).await?;
+            reserves.push(ReserveConfig::new(asset_id, faucet_id, decimals, strategy, risk));
+        }
+
+        Ok(reserves)
+    }
+
+    /// Get the faucet issuing the asset of a reserve
+    pub async fn get_reserve_faucet(&mut self, asset_id: u64) -> Result<MidenAccountId> {
+        let script = TransactionScriptBuilder::new()
+            .push(asset_id)
+            .call_procedure("lending_pool", "get_reserve_faucet")
+            .build();
+
+        let stack = self.client
+            .execute_view_script(&script, &self.pool_account_id)
+            .await
+            .context("Failed to query reserve faucet")?;
+
+        // Output stack: [faucet_id_prefix, faucet_id_suffix]
+        MidenAccountId::try_from([Felt::new(stack[0]), Felt::new(stack[1])])
+            .context("Reserve has an invalid faucet ID")
+    }
+
+    /// Returns `amount` units of a reserve's asset
+    async fn reserve_asset(&mut self, asset_id: u64, amount: u64) -> Result<FungibleAsset> {
+        let faucet_id = self.get_reserve_faucet(asset_id).await?;
+        FungibleAsset::new(faucet_id, amount)
+            .with_context(|| format!("Invalid amount {} of asset {}", amount, asset_id))
+    }
+
+    /// Get the asset and decimals of the listed reserves, in listing order
+    async fn get_listed_reserves(&mut self) -> Result<Vec<(u64, u32)>> {
+        let script = TransactionScriptBuilder::new()
+            .call_procedure("lending_pool", "get_reserve_count")
+            .build();
+
+        let stack = self.client
+            .execute_view_script(&script, &self.pool_account_id)
+            .await
+            .context("Failed to query reserve count")?;
+
+        // Output stack: [reserve_count]
+        let mut reserves = Vec::with_capacity(stack[0] as usize);
+        for index in 0..stack[0] {
+            let script = TransactionScriptBuilder::new()
+                .push(index)
+                .call_procedure("lending_pool", "get_listed_reserve")
+                .build();
+
+            let stack = self.client
+                .execute_view_script(&script, &self.pool_account_id)
+                .await
+                .context("Failed to query listed reserve")?;
+
+            // Output stack: [asset_id, decimals]
+            reserves.push((stack[0], stack[1] as u32));
+        }
+
+        Ok(reserves)
+    }
+
+    /// Check whether the oracle has a price feed for an asset
+    pub async fn has_price_feed(&mut self, asset_id: u64) -> Result<bool> {
+        let script = TransactionScriptBuilder::new()
+            .push(asset_id)
+            .call_procedure("price_oracle", "has_price_feed")
+            .build();
+
+        let stack = self.client
+            .execute_view_script(&script, &self.oracle_account_id)
+            .await
+            .context("Failed to query price feed")?;
+
+        // Output stack: [has_price_feed]
+        Ok(stack[0] == 1)
+    }
+
+    /// Fails before building a transaction for an asset the oracle would reject
+    async fn ensure_price_feed(&mut self, asset_id: u64) -> Result<()> {
+        if !self.has_price_feed(asset_id).await? {
+            anyhow::bail!("Asset {} has no price feed in the oracle", asset_id);
+        }
+
+        Ok(())
+    }
+
+    /// Get asset price from oracle
+    pub async fn get_price(&mut self, asset_id: u64) -> Result<u64> {
+        info!("Fetching price for asset {}", asset_id);
+
+        // Build query script that calls price_oracle::get_asset_price
+        let script = TransactionScriptBuilder::new()
+            .push(asset_id)
+            .call_procedure("price_oracle", "get_asset_price")
+            .build();
+
+        debug!("Get price script:\n{}", script);
+
+        // Execute script (read-only)
+        let stack = self.client
+            .execute_view_script(&script, &self.oracle_account_id)
+            .await
+            .context("Failed to query asset price")?;
+
+        // Output stack: [price]
+        Ok(stack[0])
+    }
+
+    /// Get the time-weighted average price of an asset over its oracle TWAP window
+    pub async fn get_twap(&mut self, asset_id: u64) -> Result<u64> {
+        info!("Fetching TWAP for asset {}", asset_id);
+
+        let script = TransactionScriptBuilder::new()
+            .push(asset_id)
+            .call_procedure("price_oracle", "get_asset_twap")
+            .build();
+
+        debug!("Get TWAP script:\n{}", script);
+
+        let stack = self.client
+            .execute_view_script(&script, &self.oracle_account_id)
+            .await
+            .context("Failed to query asset TWAP")?;
+
+        // Output stack: [twap]
+        Ok(stack[0])
+    }
+
+    /// Get the prices of three assets from the oracle in a single query
+    pub async fn get_prices(&mut self, asset_ids: [u64; 3]) -> Result<[u64; 3]> {
+        info!("Fetching prices for assets {:?}", asset_ids);
+
+        // Build query script that calls price_oracle::get_multiple_prices
+        let script = TransactionScriptBuilder::new()
+            .push(asset_ids[2])
+            .push(asset_ids[1])
+            .push(asset_ids[0])
+            .call_procedure("price_oracle", "get_multiple_prices")
+            .build();
+
+        debug!("Get prices script:\n{}", script);
+
+        // Execute script (read-only)
+        let stack = self.client
+            .execute_view_script(&script, &self.oracle_account_id)
+            .await
+            .context("Failed to query asset prices")?;
+
+        // Output stack: [price_1, price_2, price_3]
+        Ok([stack[0], stack[1], stack[2]])
+    }
+
+    /// Get the latest price each registered updater submitted for an asset
+    ///
+    /// The admin's initial submission comes last, as long as no updater submitted a price.
+    pub async fn get_price_submissions(&mut self, asset_id: u64) -> Result<Vec<PriceSubmission>> {
+        info!("Fetching price submissions for asset {}", asset_id);
+
+        self.ensure_price_feed(asset_id).await?;
+
+        let script = TransactionScriptBuilder::new()
+            .call_procedure("price_oracle", "get_num_updaters")
+            .build();
+        let stack = self.client
+            .execute_view_script(&script, &self.oracle_account_id)
+            .await
+            .context("Failed to query number of updaters")?;
+
+        let num_updaters = stack[0];
+        let mut submissions = Vec::with_capacity(num_updaters as usize + 1);
+        for index in (0..num_updaters).chain([PriceOracleAccount::ADMIN_SUBMISSION]) {
+            let script = TransactionScriptBuilder::new()
+                .push(asset_id)
+                .push(index)
+                .call_procedure("price_oracle", "get_updater_submission")
+                .build();
+            let stack = self.client
+                .execute_view_script(&script, &self.oracle_account_id)
+                .await
+                .context("Failed to query price submission")?;
+
+            // Output stack: [price, timestamp, is_fresh, UPDATER_KEY]
+            let is_initial = index == PriceOracleAccount::ADMIN_SUBMISSION;
+            if is_initial && stack[1] == 0 {
+                break;
+            }
+            let updater_key = Word::new([6, 5, 4, 3].map(|position| Felt::new(stack[position])));
+            submissions.push(PriceSubmission {
+                updater_key: updater_key.to_hex(),
+                price: stack[0],
+                timestamp: stack[1],
+                is_fresh: stack[2] == 1,
+                is_initial,
+            });
+        }
+
+        Ok(submissions)
+    }
+
+    /// Get the settings of an asset's oracle price feed
+    pub async fn get_price_parameters(&mut self, asset_id: u64) -> Result<PriceFeedParameters> {
+        let script = TransactionScriptBuilder::new()
+            .push(asset_id)
+            .call_procedure("price_oracle", "get_price_parameters")
+            .build();
+
+        let stack = self.client
+            .execute_view_script(&script, &self.oracle_account_id)
+            .await
+            .context("Failed to query price parameters")?;
+
+        // Output stack: [max_age, quorum, twap_window, max_deviation, deviation_interval, is_paused]
+        Ok(PriceFeedParameters {
+            max_age: stack[0],
+            quorum: stack[1],
+            twap_window: stack[2],
+            max_deviation: stack[3],
+            deviation_interval: stack[4],
+            is_paused: stack[5] == 1,
+        })
+    }
+
+    /// Calculate user's health factor
+    ///
+    /// Collateral and debt are valued at the oracle prices, collateral weighted by each asset's
+    /// liquidation threshold. The result is in basis points (10000 = 1.0), or
+    /// `NO_DEBT_HEALTH_FACTOR` when the user has no debt.
+    pub async fn calculate_health_factor(&mut self, user_account_id: &AccountId) -> Result<u64> {
+        info!("Calculating health factor for user");
+
+        let position = self.get_position(user_account_id, PriceSource::Spot).await?;
+
+        Ok(position.health_factor)
+    }
+
+    /// Calculate user's health factor at the prices liquidations use
+    ///
+    /// Matches `calculate_health_factor` unless the pool values liquidations at time-weighted
+    /// average prices.
+    pub async fn calculate_liquidation_health_factor(&mut self, user_account_id: &AccountId) -> Result<u64> {
+        info!("Calculating liquidation health factor for user");
+
+        let price_source = self.get_liquidation_price_source().await?;
+        let position = self.get_position(user_account_id, price_source).await?;
+
+        Ok(position.health_factor)
+    }
+
+    /// Calculate user's health factor on-chain and store it in the user's account
+    ///
+    /// The user account reads its health factor from the pool, which values the position with
+    /// the oracle, with foreign procedure invocation. Returns the stored health factor, which
+    /// matches `calculate_health_factor` at the same block.
+    pub async fn update_health_factor(&mut self, user_account_id: &AccountId) -> Result<u64> {
+        info!("Updating health factor of user {}", user_account_id.to_hex());
+
+        let script = TransactionScriptBuilder::new()
+            .call_procedure("user_lending", "calculate_health_factor")
+            .build();
+
+        debug!("Health factor script:\n{}", script);
+
+        let foreign_account_ids = self.position_accounts();
+        let stack = self.client
+            .execute_view_script_with_foreign_accounts(&script, user_account_id, &foreign_account_ids)
+            .await
+            .context("Failed to calculate health factor")?;
+
+        // Transactions must change the account's state, so an up to date value is left as is
+        let account = self.client.get_account(user_account_id).await?;
+        if account.storage.first() == Some(&stack[0]) {
+            info!("Health factor is up to date");
+            return Ok(stack[0]);
+        }
+
+        let result = self.client
+            .execute_transaction_with_foreign_accounts(&script, AdviceMap::default(), user_account_id, &foreign_account_ids)
+            .await
+            .context("Failed to execute health factor transaction")?;
+
+        info!("Health factor updated. TX ID: {}", hex::encode(&result.tx_id));
+
+        // Health factor slot
+        let account = self.client.get_account(user_account_id).await?;
+        account.storage.first().copied().context("User account has no health factor slot")
+    }
+
+    /// Get the positions of several users, riskiest (lowest health factor) first
+    ///
+    /// Positions are valued at the prices liquidations use. With `unhealthy_only`, only the
+    /// positions that can be liquidated are returned.
+    pub async fn get_positions(
+        &mut self,
+        user_account_ids: &[AccountId],
+        unhealthy_only: bool,
+    ) -> Result<Vec<Position>> {
+        info!("Fetching positions of {} users", user_account_ids.len());
+
+        let price_source = self.get_liquidation_price_source().await?;
+
+        let mut positions = Vec::with_capacity(user_account_ids.len());
+        for user_account_id in user_account_ids {
+            let position = self.get_position(user_account_id, price_source).await?;
+            if !unhealthy_only || position.is_liquidatable() {
+                positions.push(position);
+            }
+        }
+
+        positions.sort_by_key(|position| position.health_factor);
+        Ok(positions)
+    }
+
+    // Helper functions
+
+    /// Requests an operation on a user's position from the pool with a position note
+    async fn execute_position_transaction(
+        &mut self,
+        operation: PositionOperation,
+        user_account_id: &AccountId,
+        asset_id: u64,
+        amount: u64,
+    ) -> Result<TransactionResult> {
+        debug!("Requesting {} of {} units of asset {}", operation.procedure(), amount, asset_id);
+
+        let asset = self.reserve_asset(asset_id, amount).await?;
+        let note = create_position_note(
+            user_account_id.try_into()?,
+            (&self.pool_account_id).try_into()?,
+            operation,
+            asset_id,
+            asset,
+            random_serial_num(),
+            self.client.get_block_number().await?,
+        )?;
+
+        self.submit_request(user_account_id, note).await
+    }
+
+    /// Sends a request note from a user to the pool and has the pool consume it
+    ///
+    /// The user signs the transaction creating the note. The pool consumes it without a signature,
+    /// valuing positions with the oracle, read as a foreign account. Assets the pool pays out are
+    /// received by the user in a second transaction. Returns the result of the pool transaction.
+    ///
+    /// Request notes are reclaimable from the block they are created in, so when the pool rejects
+    /// a request the user consumes the note back, recovering the assets it carries.
+    async fn submit_request(&mut self, user_account_id: &AccountId, note: MidenNote) -> Result<TransactionResult> {
+        // The pool outputs the payout note in full given the details of its recipient
+        let mut tx_args = TransactionArgs::default();
+        tx_args.add_output_note_recipient(NoteDetails::new(
+            NoteAssets::default(),
+            payout_recipient(user_account_id.try_into()?, note.serial_num())?,
+        ));
+
+        self.client
+            .send_notes(vec![note.clone()], user_account_id)
+            .await
+            .context("Failed to send request note")?;
+
+        let result = match self
+            .client
+            .consume_notes_with_foreign_accounts(
+                vec![note.clone()],
+                tx_args.advice_inputs().map.clone(),
+                &self.pool_account_id,
+                std::slice::from_ref(&self.oracle_account_id),
+            )
+            .await
+        {
+            Ok(result) => result,
+            Err(err) => {
+                let err = with_lending_error(err);
+                return Err(
+                    match self.client.consume_notes(vec![note], AdviceMap::default(), user_account_id).await {
+                        Ok(_) => err.context("Pool rejected the request, its assets were reclaimed"),
+                        Err(reclaim_err) => err.context(format!(
+                            "Pool rejected the request and reclaiming its assets failed: {reclaim_err:#}"
+                        )),
+                    },
+                );
+            }
+        };
+
+        if !result.output_notes.is_empty() {
+            self.client
+                .consume_notes(result.output_notes.clone(), AdviceMap::default(), user_account_id)
+                .await
+                .context("Failed to receive payout note")?;
+        }
+
+        Ok(result)
+    }
+
+    /// Accounts user lending accounts read to value their position
+    ///
+    /// The pool is invoked by the user account and invokes the oracle in turn.
+    fn position_accounts(&self) -> [AccountId; 2] {
+        [self.oracle_account_id.clone(), self.pool_account_id.clone()]
+    }
+
+    /// Read user's position in every listed reserve and value it at the prices of a source
+    async fn get_position(&mut self, user_account_id: &AccountId, price_source: PriceSource) -> Result<Position> {
+        let listed_reserves = self.get_listed_reserves().await?;
+        let mut reserves = Vec::with_capacity(listed_reserves.len());
+        for (asset_id, _) in &listed_reserves {
+            reserves.push(self.get_reserve_position(user_account_id, *asset_id).await?);
+        }
+
+        self.value_position(user_account_id, &listed_reserves, &reserves, price_source).await
+    }
+
+    /// Values a user's position after withdrawing collateral from and borrowing in a reserve
+    ///
+    /// Positions are valued at spot prices, like the pool values them for borrows and collateral
+    /// withdrawals.
+    async fn position_after(
+        &mut self,
+        user_account_id: &AccountId,
+        asset_id: u64,
+        withdrawn_collateral: u64,
+        borrowed: u64,
+    ) -> Result<Position> {
+        let listed_reserves = self.get_listed_reserves().await?;
+        let mut reserves = Vec::with_capacity(listed_reserves.len());
+        for (listed_asset_id, _) in &listed_reserves {
+            let mut reserve = self.get_reserve_position(user_account_id, *listed_asset_id).await?;
+            if *listed_asset_id == asset_id {
+                if withdrawn_collateral > reserve.collateral {
+                    anyhow::bail!(
+                        "Withdrawal exceeds the {} units of collateral supplied",
+                        reserve.collateral
+                    );
+                }
+                let (_, borrow_index) = self.get_reserve_indices(asset_id).await?;
+                let scaled_borrow = wad_div_up(borrowed, borrow_index).unwrap_or(u64::MAX);
+
+                reserve.collateral -= withdrawn_collateral;
+                reserve.scaled_debt = reserve.scaled_debt.saturating_add(scaled_borrow);
+            }
+            reserves.push(reserve);
+        }
+
+        self.value_position(user_account_id, &listed_reserves, &reserves, PriceSource::Spot).await
+    }
+
+    /// Values a user's position in the listed reserves at the prices of a source
+    ///
+    /// Uses the same rounding as lending_pool::get_health_factor, and likewise only reads the
+    /// prices of assets the position holds. Debts are grown by the current borrow indices.
+    async fn value_position(
+        &mut self,
+        user_account_id: &AccountId,
+        listed_reserves: &[(u64, u32)],
+        reserves: &[ReservePosition],
+        price_source: PriceSource,
+    ) -> Result<Position> {
+        let mut collateral_value = 0u128;
+        let mut adjusted_collateral_value = 0u128;
+        let mut borrow_limit = 0u128;
+        let mut debt_value = 0u128;
+
+        for ((asset_id, decimals), reserve) in listed_reserves.iter().zip(reserves) {
+            if reserve.collateral == 0 && reserve.scaled_debt == 0 {
+                continue;
+            }
+
+            let price = match price_source {
+                PriceSource::Spot => self.get_price(*asset_id).await?,
+                PriceSource::Twap => self.get_twap(*asset_id).await?,
+            } as u128;
+            let (_, borrow_index) = self.get_reserve_indices(*asset_id).await?;
+            let risk = self.get_risk_parameters(*asset_id).await?;
+            let unit = 10u128
+                .checked_pow(*decimals)
+                .with_context(|| format!("Reserve {} has {} decimals", asset_id, decimals))?;
+
+            let debt = unscale(reserve.scaled_debt, borrow_index) as u128;
+            debt_value += debt * price / unit;
+
+            let value = reserve.collateral as u128 * price / unit;
+            collateral_value += value;
+            adjusted_collateral_value += value * risk.liquidation_threshold as u128 / PRECISION;
+            borrow_limit += value * risk.ltv as u128 / PRECISION;
+        }
+
+        let health_factor = match (adjusted_collateral_value * PRECISION).checked_div(debt_value) {
+            Some(health_factor) => u64::try_from(health_factor).unwrap_or(u64::MAX),
+            None => NO_DEBT_HEALTH_FACTOR,
+        };
+
+        Ok(Position {
+            account_id: user_account_id.clone(),
+            collateral_value: u64::try_from(collateral_value).unwrap_or(u64::MAX),
+            debt_value: u64::try_from(debt_value).unwrap_or(u64::MAX),
+            borrow_limit: u64::try_from(borrow_limit).unwrap_or(u64::MAX),
+            health_factor,
+        })
+    }
+}
+
+/// Returns a random note serial number
+fn random_serial_num() -> Word {
+    Word::new(rand::random::<[u64; 4]>().map(Felt::new))
+}
+
+/// Pushes a rate strategy so that it ends up as
+/// `[base_rate, slope1, slope2, optimal_utilization, reserve_factor]` below the next pushed values
+fn push_strategy(builder: TransactionScriptBuilder, strategy: &RateStrategy) -> TransactionScriptBuilder {
+    builder
+        .push(strategy.reserve_factor)
+        .push(strategy.optimal_utilization)
+        .push(strategy.slope2)
+        .push(strategy.slope1)
+        .push(strategy.base_rate)
+}
+
+/// Basis points in 1.0
+const PRECISION: u128 = 10000;
+
+/// Converts a balance scaled by a pool index back into an amount
+fn unscale(scaled_amount: u64, index: u64) -> u64 {
+    wad_mul(scaled_amount, index).unwrap_or(u64::MAX)
+}
+
+/// Amount repaying a whole scaled debt
+///
+/// The pool scales repayments rounding up, so this is the smallest amount scaling up to the debt.
+fn repayment_of(scaled_debt: u64, borrow_index: u64) -> u64 {
+    match scaled_debt {
+        0 => 0,
+        _ => unscale(scaled_debt - 1, borrow_index).saturating_add(1),
+    }
+}
+
+/// Health factor reported for positions without debt
+pub const NO_DEBT_HEALTH_FACTOR: u64 = 1_000_000;
+
+/// Health factor of 1.0 in basis points, below which a position can be liquidated
+pub const HEALTH_FACTOR_ONE: u64 = 10000;
+
+/// User position valued at oracle prices
+///
+/// Values are in USD with 8 decimals. Health Factor = sum(collateral_value *
+/// liquidation_threshold) / debt_value, in basis points.
+#[derive(Debug, Clone, Serialize)]
+pub struct Position {
+    pub account_id: AccountId,
+    pub collateral_value: u64,
+    pub debt_value: u64,
+    /// LTV-weighted collateral value, which borrows cannot take the debt value past
+    pub borrow_limit: u64,
+    pub health_factor: u64,
+}
+
+impl Position {
+    pub fn is_liquidatable(&self) -> bool {
+        self.health_factor < HEALTH_FACTOR_ONE
+    }
+}
+
+/// Outcome of a liquidation
+#[derive(Debug, Clone, Serialize)]
+pub struct Liquidation {
+    pub tx_id: String,
+    /// Borrower's health factor before the liquidation, at the prices the pool liquidates at
+    pub health_factor_before: u64,
+    /// Units of debt repaid, the rest of the debt to cover being refunded to the liquidator
+    pub debt_covered: u64,
+    /// Units of collateral paid out to the liquidator
+    pub collateral_seized: u64,
+}
+
+/// Oracle price of an asset, in USD with 8 decimals
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct AssetPrice {
+    pub asset_id: u64,
+    pub price: u64,
+}
+
+/// Latest price submitted by an oracle updater for an asset
+#[derive(Debug, Clone, Serialize)]
+pub struct PriceSubmission {
+    /// Public key of the updater, as hex
+    pub updater_key: String,
+    pub price: u64,
+    /// Timestamp of the block the price was submitted in, or of a signed price, zero if the
+    /// updater submitted none
+    pub timestamp: u64,
+    /// Whether the submission counts towards the reported median
+    pub is_fresh: bool,
+    /// Whether this is the initial price the admin added the feed with, submitted with the admin
+    /// key
+    pub is_initial: bool,
+}
+
+/// Settings of an oracle price feed
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct PriceFeedParameters {
+    /// Maximum age of the price, in seconds
+    pub max_age: u64,
+    /// Number of fresh submissions needed to report a price
+    pub quorum: u64,
+    /// Window of the time-weighted average price, in seconds
+    pub twap_window: u64,
+    /// Maximum deviation from the price `deviation_interval` seconds earlier, in basis points,
+    /// zero if the circuit breaker is disabled
+    pub max_deviation: u64,
+    pub deviation_interval: u64,
+    /// Whether the circuit breaker paused the feed
+    pub is_paused: bool,
 }
 
 /// Reserve data structure
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ReserveData {
     pub asset_id: u64,
     pub total_liquidity: u64,
     pub total_borrowed: u64,
+    /// Yearly supply rate, as a wad
     pub liquidity_rate: u64,
+    /// Yearly borrow rate, as a wad
     pub borrow_rate: u64,
+    /// Growth of supplied balances since listing, as a wad
+    pub liquidity_index: u64,
+    /// Growth of debts since listing, as a wad
+    pub borrow_index: u64,
 }
 
 impl ReserveData {
@@ -30,6 +30,18 @@ pub fn format_health_factor(health_factor: u64) -> String {
     }
 }
 
+/// Format a pool index (10^18 = 1.0) with 8 decimals
+pub fn format_index(index: u64) -> String {
+    let scaled = index / 10_000_000_000;
+    format!("{}.{:08}", scaled / 100_000_000, scaled % 100_000_000)
+}
+
+/// Format a wad rate (10^18 = 100%) to percentage
+pub fn wad_to_percentage(rate: u64) -> String {
+    let percentage = (rate as f64) / 1e16;
+    format!("{:.4}%", percentage)
+}
+
 /// Format basis points to percentage
 pub fn basis_points_to_percentage(basis_points: u64) -> String {
     let percentage = (basis_points as f64) / 100.0;
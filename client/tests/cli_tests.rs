@@ -0,0 +1,224 @@
+// Tests of the CLI's JSON output
+//
+// The CLI runs with its home directory in a temporary directory, so it keeps a config of its own.
+// Its profile points at an unreachable node, so commands reaching the node exercise their failure
+// output.
+
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// Node endpoint refusing connections
+const UNREACHABLE_RPC: &str = "http://127.0.0.1:1";
+
+// Valid account IDs for the configuration, which the unreachable node never gets to check
+const POOL_ID: &str = "0x9a5c1e2b3d4f5a6b2000000000000000";
+const ORACLE_ID: &str = "0x8b4d2f3c4e5a6b7c3000000000000000";
+const USER_ID: &str = "0x7c3e4a5b6c7d8e9f1000000000000000";
+
+// Home directory of the CLI under the system temp directory, removed when dropped
+struct TempHome(PathBuf);
+
+impl TempHome {
+    fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("miden-lending-cli-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        Self(dir)
+    }
+
+    // Initializes the default profile against the unreachable node, with deployed accounts set
+    fn init(name: &str) -> Self {
+        let home = Self::new(name);
+        assert_success(&run(&home.0, &["init", "--rpc", UNREACHABLE_RPC]));
+
+        let config_path = home.0.join(".miden-lending").join("config.json");
+        let mut config: Value = serde_json::from_str(&std::fs::read_to_string(&config_path).unwrap()).unwrap();
+        let profile = &mut config["profiles"]["local"];
+        profile["lending_pool_account_id"] = POOL_ID.into();
+        profile["price_oracle_account_id"] = ORACLE_ID.into();
+        profile["user_account_id"] = USER_ID.into();
+        std::fs::write(&config_path, config.to_string()).unwrap();
+
+        home
+    }
+}
+
+impl Drop for TempHome {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+// Runs a command with JSON output, checking that it prints exactly one JSON object whose
+// `success` field matches its exit status
+fn run(home: &Path, args: &[&str]) -> Value {
+    let output = Command::new(env!("CARGO_BIN_EXE_miden-lending-client"))
+        .env("HOME", home)
+        .env("MIDEN_LENDING_KEYSTORE_PASSWORD", "cli-tests")
+        .args(["--output", "json"])
+        .args(args)
+        .output()
+        .unwrap();
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<_> = stdout.lines().collect();
+    assert_eq!(lines.len(), 1, "`{}` should print one line, got: {:?}", args.join(" "), stdout);
+
+    let value: Value = serde_json::from_str(lines[0])
+        .unwrap_or_else(|err| panic!("`{}` printed invalid JSON ({}): {}", args.join(" "), err, lines[0]));
+    assert!(value.is_object(), "`{}` should print an object, got: {}", args.join(" "), value);
+    assert_eq!(
+        value["success"].as_bool(),
+        Some(output.status.success()),
+        "`{}` should report its exit status, got: {}",
+        args.join(" "),
+        value
+    );
+    value
+}
+
+fn assert_success(value: &Value) {
+    assert_eq!(value["success"], true, "expected success, got: {}", value);
+}
+
+// Checks that each command fails with an error message
+fn assert_all_fail(home: &Path, commands: &[&[&str]]) {
+    for args in commands {
+        let value = run(home, args);
+        assert_eq!(value["success"], false, "`{}` should fail, got: {}", args.join(" "), value);
+        assert!(
+            value["error"].as_str().is_some_and(|error| !error.is_empty()),
+            "`{}` should report an error, got: {}",
+            args.join(" "),
+            value
+        );
+    }
+}
+
+#[test]
+fn test_profile_commands_output_json() {
+    let home = TempHome::new("profiles");
+
+    let value = run(&home.0, &["init", "--rpc", UNREACHABLE_RPC]);
+    assert_success(&value);
+    assert_eq!(value["rpc_endpoint"], UNREACHABLE_RPC);
+
+    let value = run(&home.0, &["profile", "add", "testnet", "--rpc", "https://rpc.testnet"]);
+    assert_success(&value);
+    assert_eq!(value["profile"], "testnet");
+
+    assert_success(&run(&home.0, &["profile", "switch", "testnet"]));
+    let value = run(&home.0, &["profile", "list"]);
+    assert_success(&value);
+    assert_eq!(value["active_profile"], "testnet");
+    assert!(value["profiles"]["local"].is_object());
+
+    assert_all_fail(&home.0, &[
+        &["profile", "add", "testnet", "--rpc", "https://other"],
+        &["profile", "switch", "devnet"],
+        &["--profile", "devnet", "get-account-info"],
+    ]);
+}
+
+#[test]
+fn test_key_commands_output_json() {
+    let home = TempHome::init("keys");
+
+    let value = run(&home.0, &["keys", "list"]);
+    assert_success(&value);
+    assert_eq!(value["keys"], serde_json::json!([]));
+
+    let key_file = home.0.join("key.hex");
+    std::fs::write(&key_file, "00").unwrap();
+    assert_all_fail(&home.0, &[
+        &["keys", "export", USER_ID],
+        &["keys", "export", USER_ID, "--file", key_file.to_str().unwrap()],
+        &["keys", "import", USER_ID, key_file.to_str().unwrap()],
+    ]);
+}
+
+#[test]
+fn test_deployment_commands_output_json() {
+    let home = TempHome::init("deployment");
+
+    assert_all_fail(&home.0, &[
+        &["deploy-oracle"],
+        &["deploy-pool", "--usdc-faucet", POOL_ID, "--dai-faucet", POOL_ID, "--weth-faucet", POOL_ID],
+        &["create-account"],
+        &["deploy-pool", "--usdc-faucet", "not-hex", "--dai-faucet", POOL_ID, "--weth-faucet", POOL_ID],
+    ]);
+}
+
+#[test]
+fn test_lending_commands_output_json() {
+    let home = TempHome::init("lending");
+
+    assert_all_fail(&home.0, &[
+        &["deposit", "--asset-id", "1", "--amount", "100"],
+        &["withdraw", "--asset-id", "1", "--amount", "100"],
+        &["supply-collateral", "--asset-id", "3", "--amount", "100"],
+        &["withdraw-collateral", "--asset-id", "3", "--amount", "100"],
+        &["borrow", "--asset-id", "1", "--amount", "100"],
+        &["repay", "--asset-id", "1", "--amount", "100"],
+        &["liquidate", "--borrower", USER_ID, "--collateral-asset-id", "3", "--debt-asset-id", "1", "--amount", "100"],
+    ]);
+}
+
+#[test]
+fn test_query_commands_output_json() {
+    let home = TempHome::init("queries");
+
+    assert_all_fail(&home.0, &[
+        &["get-account-info"],
+        &["get-reserve-data", "--asset-id", "1"],
+        &["get-price", "--asset-id", "1"],
+        &["get-price", "--asset-id", "1", "--twap"],
+        &["price-submissions", "--asset-id", "1"],
+        &["health-factor"],
+        &["reserves"],
+        &["positions"],
+        &["positions", "--unhealthy"],
+        &["treasury", "show"],
+    ]);
+}
+
+#[test]
+fn test_admin_commands_output_json() {
+    let home = TempHome::init("admin");
+    let note_file = home.0.join("price.note");
+
+    assert_all_fail(&home.0, &[
+        &["update-price", "--asset-id", "1", "--price", "100000000"],
+        &["sign-price", "--asset-id", "1", "--price", "100000000", "--file", note_file.to_str().unwrap()],
+        &["relay-price-notes", note_file.to_str().unwrap()],
+        &["set-price-quorum", "--asset-id", "1", "--quorum", "2"],
+        &["add-price-feed", "--asset-id", "5", "--price", "100000000"],
+        &["set-max-price-age", "--asset-id", "1", "--max-age", "600"],
+        &["set-twap-window", "--asset-id", "1", "--window", "600"],
+        &["set-circuit-breaker", "--asset-id", "1", "--max-deviation", "1000"],
+        &["resume-price-feed", "--asset-id", "1"],
+        &["updaters", "add", USER_ID],
+        &["updaters", "remove", USER_ID],
+        &["add-reserve", "--asset-id", "4", "--decimals", "8", "--faucet-id", POOL_ID],
+        &["set-rate-strategy", "--asset-id", "1", "--base-rate", "100"],
+        &["set-liquidation-price-source", "twap"],
+        &["treasury", "withdraw", "--asset-id", "1"],
+    ]);
+}
+
+// Text output goes to stdout and errors to stderr, so failures print nothing to stdout
+#[test]
+fn test_text_output_failure_prints_to_stderr() {
+    let home = TempHome::init("text");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_miden-lending-client"))
+        .env("HOME", &home.0)
+        .args(["deposit", "--asset-id", "1", "--amount", "100"])
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty(), "got: {}", String::from_utf8_lossy(&output.stdout));
+    assert!(String::from_utf8_lossy(&output.stderr).contains('❌'));
+}
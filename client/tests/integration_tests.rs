@@ -1,66 +1,164 @@
 // Integration tests for Miden Lending Protocol
 
 use miden_lending_client::*;
+use miden_lending_client::backend::LendingBackend;
 use anyhow::Result;
-use std::path::PathBuf;
 
 // Test helper to setup test environment
+//
+// Every test runs against its own mock chain, which executes the compiled lending contracts in
+// the VM without a node running.
 async fn setup_test_env() -> Result<TestEnvironment> {
-    let rpc_endpoint = "http://localhost:57291";
-    let store_path = PathBuf::from(".miden-lending-test");
+    let chain = mock_chain::MockLendingChain::new();
 
     // Initialize account manager
-    let mut account_manager = accounts::AccountManager::new(rpc_endpoint, &store_path).await?;
+    let mut account_manager = accounts::AccountManager::from_client(chain.clone());
 
-    // Deploy lending pool
-    let pool_result = account_manager.deploy_lending_pool().await?;
-    let pool_id = extract_account_id(&pool_result);
+    // Deploy price oracle, which the lending pool values positions with
+    let oracle_id = account_manager.deploy_price_oracle().await?;
 
-    // Deploy price oracle
-    let oracle_result = account_manager.deploy_price_oracle().await?;
-    let oracle_id = extract_account_id(&oracle_result);
+    // Deploy lending pool, with the USDC, DAI and WETH reserves backed by testing faucets
+    let faucet_ids = [1, 2, 3].map(|asset_id| faucet_id(asset_id).into());
+    let pool_id = account_manager.deploy_lending_pool(&oracle_id, &faucet_ids).await?;
 
     // Create test user account
-    let user_result = account_manager.create_user_account("private").await?;
-    let user_id = extract_account_id(&user_result);
+    let user_id = account_manager.create_user_account("private", &pool_id).await?;
 
     // Initialize transaction builder
-    let client = miden_client::LendingClient::new(rpc_endpoint, store_path).await?;
-    let tx_builder = transactions::TransactionBuilder::new(
-        client,
-        miden_client::AccountId(pool_id.clone()),
-        miden_client::AccountId(oracle_id.clone()),
-    );
-
-    Ok(TestEnvironment {
-        _pool_id: pool_id,
-        _oracle_id: oracle_id,
-        user_id,
+    let tx_builder =
+        transactions::TransactionBuilder::new(chain.clone(), pool_id.clone(), oracle_id.clone());
+
+    let mut env = TestEnvironment {
+        chain,
+        pool_id,
+        oracle_id,
+        user_id: user_id.clone(),
         tx_builder,
         account_manager,
-    })
-}
+    };
+    fund(&mut env, &user_id, &USER_FUNDS).await?;
 
-fn extract_account_id(result: &str) -> Vec<u8> {
-    // Extract account ID from result string like "Account deployed: 0000..."
-    result
-        .split(':')
-        .nth(1)
-        .and_then(|s| hex::decode(s.trim()).ok())
-        .unwrap_or(vec![0u8; 32])
+    Ok(env)
 }
 
 struct TestEnvironment {
-    _pool_id: Vec<u8>,
-    _oracle_id: Vec<u8>,
-    user_id: Vec<u8>,
-    tx_builder: transactions::TransactionBuilder,
-    account_manager: accounts::AccountManager,
+    chain: mock_chain::MockLendingChain,
+    pool_id: miden_client::AccountId,
+    oracle_id: miden_client::AccountId,
+    user_id: miden_client::AccountId,
+    tx_builder: transactions::TransactionBuilder<mock_chain::MockLendingChain>,
+    account_manager: accounts::AccountManager<mock_chain::MockLendingChain>,
+}
+
+// Returns the testing faucet issuing the asset of a reserve (1=USDC, 2=DAI, 3=WETH, 4=WBTC)
+fn faucet_id(asset_id: u64) -> miden_objects::account::AccountId {
+    use miden_objects::testing::account_id::{
+        ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET, ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1,
+        ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_2, ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_3,
+    };
+
+    let faucet_id = match asset_id {
+        1 => ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET,
+        2 => ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1,
+        3 => ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_2,
+        4 => ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_3,
+        _ => panic!("asset {} has no testing faucet", asset_id),
+    };
+    faucet_id.try_into().unwrap()
+}
+
+// Returns `amount` units of a reserve's asset
+fn asset(asset_id: u64, amount: u64) -> miden_objects::asset::FungibleAsset {
+    miden_objects::asset::FungibleAsset::new(faucet_id(asset_id), amount).unwrap()
+}
+
+// Mints amounts of reserve assets to an account's wallet
+async fn fund(
+    env: &mut TestEnvironment,
+    account_id: &miden_client::AccountId,
+    amounts: &[(u64, u64)],
+) -> Result<()> {
+    let assets = amounts.iter().map(|(asset_id, amount)| asset(*asset_id, *amount)).collect();
+    env.chain.mint(account_id, assets).await
+}
+
+// Assets test users hold: a million USDC, DAI and WETH
+const USER_FUNDS: [(u64, u64); 3] = [(1, 10u64.pow(12)), (2, 10u64.pow(14)), (3, 10u64.pow(14))];
+
+// Creates a user account holding the `USER_FUNDS`
+async fn create_funded_user(env: &mut TestEnvironment) -> Result<miden_client::AccountId> {
+    let user_id = env.account_manager.create_user_account("private", &env.pool_id).await?;
+    fund(env, &user_id, &USER_FUNDS).await?;
+
+    Ok(user_id)
+}
+
+// Has a new updater report the prices the oracle was deployed with, so they are fresh again
+async fn refresh_prices(env: &mut TestEnvironment) -> Result<()> {
+    let updater_id = env.account_manager.create_user_account("private", &env.pool_id).await?;
+    env.tx_builder.add_price_updater(&updater_id).await?;
+    for (asset_id, price) in [(1, 10u64.pow(8)), (2, 10u64.pow(8)), (3, 2500 * 10u64.pow(8))] {
+        env.tx_builder.update_price(&updater_id, asset_id, price).await?;
+    }
+
+    Ok(())
+}
+
+// Returns whether a transaction failed with the given contract error
+//
+// Errors raised in the shared modules are linked into the contracts without their messages, so
+// the VM reports them by code only.
+fn fails_with(err: &anyhow::Error, expected: &miden_lib::errors::MasmError) -> bool {
+    let err = format!("{:?}", err);
+    err.contains(expected.message()) || err.contains(&expected.code().to_string())
+}
+
+// Sends a request note from an account and has the pool consume it, valuing positions with the
+// oracle
+async fn submit_request(
+    env: &mut TestEnvironment,
+    sender_id: &miden_client::AccountId,
+    note: miden_objects::note::Note,
+) -> Result<miden_client::TransactionResult> {
+    env.chain.send_notes(vec![note.clone()], sender_id).await?;
+    env.chain
+        .consume_notes_with_foreign_accounts(
+            vec![note],
+            Default::default(),
+            &env.pool_id,
+            std::slice::from_ref(&env.oracle_id),
+        )
+        .await
+}
+
+// Creates a position note from an account to the test pool
+//
+// The note carries `asset` for operations the pool receives assets with, and the sender can
+// reclaim it at once.
+fn position_note(
+    env: &TestEnvironment,
+    sender_id: &miden_client::AccountId,
+    operation: components::position_note::PositionOperation,
+    asset_id: u64,
+    asset: miden_objects::asset::FungibleAsset,
+) -> Result<miden_objects::note::Note> {
+    use miden_objects::{account::AccountId as MidenAccountId, block::BlockNumber, Felt, Word};
+
+    Ok(components::position_note::create_position_note(
+        MidenAccountId::try_from(sender_id)?,
+        MidenAccountId::try_from(&env.pool_id)?,
+        operation,
+        asset_id,
+        asset,
+        Word::new(rand::random::<[u64; 4]>().map(Felt::new)),
+        BlockNumber::GENESIS,
+    )?)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use components::position_note::PositionOperation;
 
     // =============================================================================================
     // Deposit & Withdraw Flow Tests
@@ -82,7 +180,7 @@ mod tests {
         let deposit_amount = 1000 * 10u64.pow(6); // 1000 USDC (6 decimals)
         let asset_id: u64 = 1; // USDC
 
-        let user_account_id = miden_client::AccountId(env.user_id.clone());
+        let user_account_id = env.user_id.clone();
 
         // Execute deposit transaction
         env.tx_builder.deposit(&user_account_id, asset_id, deposit_amount).await?;
@@ -90,8 +188,13 @@ mod tests {
 
         // Verify pool state
         let reserve_data = env.tx_builder.get_reserve_data(asset_id).await?;
+        assert_eq!(reserve_data.total_liquidity, deposit_amount);
         println!("   Pool liquidity: {}", reserve_data.total_liquidity);
 
+        // The deposited USDC moved from the user's wallet to the pool's vault
+        let wallet_balance = env.chain.balance(&user_account_id, faucet_id(asset_id)).await?;
+        assert_eq!(env.chain.balance(&env.pool_id, faucet_id(asset_id)).await?, deposit_amount);
+
         // Step 2: Withdraw 500 USDC
         let withdraw_amount = 500 * 10u64.pow(6);
 
@@ -101,8 +204,46 @@ mod tests {
 
         // Verify pool state
         let reserve_data = env.tx_builder.get_reserve_data(asset_id).await?;
+        assert_eq!(reserve_data.total_liquidity, deposit_amount - withdraw_amount);
         println!("   Pool liquidity after withdraw: {}", reserve_data.total_liquidity);
 
+        // The withdrawal was paid out to the user's wallet in a P2ID note
+        assert_eq!(
+            env.chain.balance(&env.pool_id, faucet_id(asset_id)).await?,
+            deposit_amount - withdraw_amount
+        );
+        assert_eq!(
+            env.chain.balance(&user_account_id, faucet_id(asset_id)).await?,
+            wallet_balance + withdraw_amount
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pool_consumes_requests_unsigned() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        // Test scenario:
+        // 1. The pool's key is dropped
+        // 2. The pool still consumes the user's signed requests and pays out withdrawals
+        // 3. Its admin procedures can no longer be called
+
+        env.chain.forget_key(&env.pool_id).await?;
+
+        let user_account_id = env.user_id.clone();
+        let amount = 1000 * 10u64.pow(6); // 1000 USDC
+        env.tx_builder.deposit(&user_account_id, 1, amount).await?;
+        env.tx_builder.withdraw(&user_account_id, 1, amount / 2).await?;
+        assert_eq!(env.chain.balance(&env.pool_id, faucet_id(1)).await?, amount / 2);
+        println!("✅ Requests consumed without the pool's key");
+
+        assert!(env.tx_builder
+            .set_liquidation_price_source(components::lending_pool::PriceSource::Twap)
+            .await
+            .is_err());
+        println!("✅ Admin procedures still require the pool's key");
+
         Ok(())
     }
 
@@ -113,10 +254,11 @@ mod tests {
         // Test scenario:
         // 1. User deposits 1000 USDC
         // 2. Another user borrows 500 USDC (creates utilization)
-        // 3. Wait for interest accrual
-        // 4. Original user withdraws and receives more than deposited
+        // 3. A year passes, and a third user borrows at the grown borrow index
+        // 4. Both borrowers repay their whole debt, clearing the reserve's borrows
+        // 5. Original user withdraws and receives more than deposited
 
-        let user_account_id = miden_client::AccountId(env.user_id.clone());
+        let user_account_id = env.user_id.clone();
         let deposit_amount = 1000 * 10u64.pow(6);
         let asset_id: u64 = 1; // USDC
 
@@ -124,17 +266,101 @@ mod tests {
         env.tx_builder.deposit(&user_account_id, asset_id, deposit_amount).await?;
         println!("✅ Deposited 1000 USDC");
 
-        // Step 2: Get initial reserve data
         let initial_reserve = env.tx_builder.get_reserve_data(asset_id).await?;
-        println!("   Initial supply rate: {} bps", initial_reserve.liquidity_rate);
+        println!("   Initial supply rate: {}", utils::wad_to_percentage(initial_reserve.liquidity_rate));
+
+        // Step 2: Borrow against 1 WETH of collateral
+        let borrower_id = create_funded_user(&mut env).await?;
+        let borrow_amount = 500 * 10u64.pow(6);
+
+        env.tx_builder.supply_collateral(&borrower_id, 3, 10u64.pow(8)).await?;
+        env.tx_builder.borrow(&borrower_id, asset_id, borrow_amount).await?;
+        println!("✅ Borrowed 500 USDC");
+
+        // Step 3: Let a year of interest accrue. At 50% utilization the stablecoin curve charges
+        // borrowers 2.22%, compounded every second, and suppliers earn 1% after the 10% reserve
+        // factor. Blocks committing the transactions add a few seconds on top of the year.
+        env.chain.advance_time(31_536_000).await?;
+
+        let (liquidity_index, borrow_index) = env.tx_builder.get_reserve_indices(asset_id).await?;
+        assert!(liquidity_index.abs_diff(1_010_000_000_000_000_000) < 100_000_000_000);
+        assert!(borrow_index.abs_diff(1_022_470_974_965_596_984) < 100_000_000_000);
+
+        let supply_balance = env.tx_builder.get_supply_balance(&user_account_id, asset_id).await?;
+        assert_eq!(Some(supply_balance), fixed_point::wad_mul(deposit_amount, liquidity_index));
+        println!("   Supply balance after a year: {}", supply_balance);
+
+        // Prices are stale after the year, so they are refreshed before borrowing again
+        refresh_prices(&mut env).await?;
+        let late_borrower_id = create_funded_user(&mut env).await?;
+        env.tx_builder.supply_collateral(&late_borrower_id, 3, 10u64.pow(8)).await?;
+        env.tx_builder.borrow(&late_borrower_id, asset_id, 250 * 10u64.pow(6) + 1).await?;
+        println!("✅ Borrowed 250.000001 USDC at the grown borrow index");
+
+        // Step 4: Repay both debts in full. Accruing on repayment brings the stored total up to date
+        for borrower in [&borrower_id, &late_borrower_id] {
+            let repay_amount = env.tx_builder.get_debt(borrower, asset_id).await?;
+            env.tx_builder.repay(borrower, asset_id, repay_amount).await?;
+            println!("✅ Repaid {} USDC", repay_amount as f64 / 1e6);
+
+            let position = env.tx_builder.get_reserve_position(borrower, asset_id).await?;
+            assert_eq!(position.scaled_debt, 0);
+        }
+
+        let reserve_data = env.tx_builder.get_reserve_data(asset_id).await?;
+        assert_eq!(reserve_data.total_borrowed, 0);
+
+        // Step 5: Withdraw the full balance, interest included
+        let supply_balance = env.tx_builder.get_supply_balance(&user_account_id, asset_id).await?;
+        env.tx_builder.withdraw(&user_account_id, asset_id, supply_balance).await?;
+        println!("✅ Withdrew {} USDC", supply_balance as f64 / 1e6);
+
+        assert!(supply_balance > deposit_amount);
+        assert_eq!(env.tx_builder.get_supply_balance(&user_account_id, asset_id).await?, 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_borrow_after_interest_rounds_debt_up() -> Result<()> {
+        let mut env = setup_test_env().await?;
 
-        // Step 3: Simulate borrow creating utilization
-        let _borrow_amount = 500 * 10u64.pow(6);
-        println!("⚠️  Borrow simulation (creates 50% utilization)");
+        // Test scenario:
+        // 1. A year of interest grows the USDC borrow index past 1.0
+        // 2. Another user borrows a single unit, then an amount the index does not divide
+        // 3. The recorded debt is worth at least the amount borrowed
 
-        // Step 4: Check updated rates
-        let updated_reserve = env.tx_builder.get_reserve_data(asset_id).await?;
-        println!("   Updated supply rate: {} bps", updated_reserve.liquidity_rate);
+        let asset_id = 1; // USDC
+        let user_id = env.user_id.clone();
+        env.tx_builder.deposit(&user_id, asset_id, 1000 * 10u64.pow(6)).await?;
+        env.tx_builder.supply_collateral(&user_id, 3, 10u64.pow(8)).await?; // 1 WETH
+        env.tx_builder.borrow(&user_id, asset_id, 500 * 10u64.pow(6)).await?;
+
+        // Depositing accrues the reserve and stores its indices
+        env.chain.advance_time(31_536_000).await?;
+        env.tx_builder.deposit(&user_id, asset_id, 10u64.pow(6)).await?;
+        let (_, borrow_index) = env.tx_builder.get_reserve_indices(asset_id).await?;
+        assert!(borrow_index > fixed_point::WAD);
+        println!("   Borrow index after a year: {}", utils::format_index(borrow_index));
+
+        // Prices are stale after the year
+        refresh_prices(&mut env).await?;
+
+        let borrower_id = create_funded_user(&mut env).await?;
+        env.tx_builder.supply_collateral(&borrower_id, 3, 10u64.pow(8)).await?;
+
+        let mut borrowed = 0;
+        for amount in [1, 1_234_567] {
+            env.tx_builder.borrow(&borrower_id, asset_id, amount).await?;
+            borrowed += amount;
+
+            let position = env.tx_builder.get_reserve_position(&borrower_id, asset_id).await?;
+            let (_, borrow_index) = env.tx_builder.get_reserve_indices(asset_id).await?;
+            let debt = fixed_point::wad_mul(position.scaled_debt, borrow_index).unwrap();
+            assert!(debt >= borrowed, "debt of {} recorded for {} borrowed", debt, borrowed);
+            assert!(env.tx_builder.get_debt(&borrower_id, asset_id).await? >= borrowed);
+        }
+        println!("✅ Debt recorded after interest is worth at least the amount borrowed");
 
         Ok(())
     }
@@ -155,31 +381,47 @@ mod tests {
         // 5. Verify debt reduced and health factor increased
 
         // Step 1: Supply collateral
-        let collateral_amount = 10u64.pow(18); // 1 WETH (18 decimals)
+        let collateral_amount = 10u64.pow(8); // 1 WETH (8 decimals)
         let collateral_asset_id: u64 = 3; // WETH
 
-        let user_account_id = miden_client::AccountId(env.user_id.clone());
+        let user_account_id = env.user_id.clone();
 
         env.tx_builder.supply_collateral(&user_account_id, collateral_asset_id, collateral_amount).await?;
         println!("✅ Supplied 1 WETH as collateral");
 
+        let user_info = env.tx_builder.get_account_info(&user_account_id).await?;
+        assert_eq!(user_info.collateral(3), collateral_amount);
+
         // Step 2: Borrow DAI (using 8 decimals to avoid overflow - 1000 DAI)
         let borrow_amount = 1000 * 10u64.pow(8); // 1000 DAI with 8 decimals
         let borrow_asset_id: u64 = 2; // DAI
 
+        // Provide DAI liquidity to borrow from
+        env.tx_builder.deposit(&user_account_id, borrow_asset_id, 2 * borrow_amount).await?;
+
         env.tx_builder.borrow(&user_account_id, borrow_asset_id, borrow_amount).await?;
         println!("✅ Borrowed 1000 DAI");
 
+        let user_info = env.tx_builder.get_account_info(&user_account_id).await?;
+        assert_eq!(user_info.scaled_debt(2), borrow_amount);
+
         // Verify health factor
         let health_factor = env.tx_builder.calculate_health_factor(&user_account_id).await?;
         println!("   Health factor: {}", health_factor);
 
         // Step 3: Repay 500 DAI
         let repay_amount = 500 * 10u64.pow(8); // 500 DAI with 8 decimals
+        let (_, borrow_index) = env.tx_builder.get_reserve_indices(borrow_asset_id).await?;
 
         env.tx_builder.repay(&user_account_id, borrow_asset_id, repay_amount).await?;
         println!("✅ Repaid 500 DAI");
 
+        // Debts are stored scaled by the borrow index, which has grown since the borrow, and
+        // repayments are scaled rounding up
+        let user_info = env.tx_builder.get_account_info(&user_account_id).await?;
+        let scaled_repay_amount = fixed_point::wad_div_up(repay_amount, borrow_index).unwrap();
+        assert_eq!(user_info.scaled_debt(2), borrow_amount - scaled_repay_amount);
+
         // Verify health factor increased
         let new_health_factor = env.tx_builder.calculate_health_factor(&user_account_id).await?;
         println!("   New health factor: {}", new_health_factor);
@@ -187,34 +429,245 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_repay_above_debt_refunds_excess() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        // User borrows 1000 USDC against 1 WETH, and interest accrues for a year
+        let user_id = env.user_id.clone();
+        env.tx_builder.supply_collateral(&user_id, 3, 10u64.pow(8)).await?;
+        env.tx_builder.deposit(&user_id, 1, 2000 * 10u64.pow(6)).await?;
+        env.tx_builder.borrow(&user_id, 1, 1000 * 10u64.pow(6)).await?;
+        env.chain.advance_time(31_536_000).await?;
+
+        let debt = env.tx_builder.get_debt(&user_id, 1).await?;
+        assert!(debt > 1000 * 10u64.pow(6));
+        println!("   Debt after a year: {} USDC", debt as f64 / 1e6);
+
+        // Repaying 1100 USDC clears the debt, and the pool refunds what exceeds it
+        let balance_before = env.chain.balance(&user_id, faucet_id(1)).await?;
+        env.tx_builder.repay(&user_id, 1, 1100 * 10u64.pow(6)).await?;
+        let repaid = balance_before - env.chain.balance(&user_id, faucet_id(1)).await?;
+        println!("✅ Repaid {} USDC, the rest was refunded", repaid as f64 / 1e6);
+
+        // The blocks committing the repayment add a few seconds of interest
+        assert!(repaid >= debt && repaid - debt < 100, "repaid {} of a {} debt", repaid, debt);
+
+        // Prices are stale after the year, so read the position without valuing it
+        assert_eq!(env.tx_builder.get_reserve_position(&user_id, 1).await?.scaled_debt, 0);
+        assert_eq!(env.tx_builder.get_reserve_data(1).await?.total_borrowed, 0);
+        assert_eq!(env.tx_builder.get_debt(&user_id, 1).await?, 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_repay_without_debt_fails() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        let user_id = env.user_id.clone();
+        env.tx_builder.deposit(&user_id, 1, 2000 * 10u64.pow(6)).await?;
+
+        // The client rejects the repayment before sending the request note
+        let balance = env.chain.balance(&user_id, faucet_id(1)).await?;
+        let err = env.tx_builder.repay(&user_id, 1, 100 * 10u64.pow(6)).await.unwrap_err();
+        assert_eq!(err.downcast_ref::<errors::LendingError>(), Some(&errors::LendingError::NoDebt));
+        assert_eq!(env.chain.balance(&user_id, faucet_id(1)).await?, balance);
+
+        // Sending the note directly skips the client-side check, so the pool itself must reject it
+        let note = position_note(
+            &env,
+            &user_id,
+            components::position_note::PositionOperation::Repay,
+            1,
+            asset(1, 100 * 10u64.pow(6)),
+        )?;
+        let err = submit_request(&mut env, &user_id, note).await.unwrap_err();
+        assert!(fails_with(&err, &errors::ERR_LENDING_POOL_NO_DEBT), "got: {:?}", err);
+        println!("✅ Repayment without debt rejected");
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_borrow_fails_insufficient_collateral() -> Result<()> {
         let mut env = setup_test_env().await?;
 
         // Test scenario:
         // 1. User supplies 0.1 WETH as collateral ($250)
-        // 2. User attempts to borrow 1000 DAI (should fail)
-        // 3. Verify transaction reverts
+        // 2. User attempts to borrow 1000 USDC (should fail)
+        // 3. Verify nothing was borrowed
 
-        let collateral_amount = 10u64.pow(17); // 0.1 WETH
+        let collateral_amount = 10u64.pow(7); // 0.1 WETH
         let collateral_asset_id = 3;
 
-        let user_account_id = miden_client::AccountId(env.user_id.clone());
+        let user_account_id = env.user_id.clone();
 
         env.tx_builder.supply_collateral(&user_account_id, collateral_asset_id, collateral_amount).await?;
 
-        let borrow_amount = 1000 * 10u64.pow(8); // 1000 DAI with 8 decimals
-        let borrow_asset_id = 2;
+        let borrow_amount = 1000 * 10u64.pow(6); // 1000 USDC
+        let borrow_asset_id = 1;
 
-        // Verify this fails
+        // Provide enough USDC liquidity so only the collateral check can reject the borrow
+        env.tx_builder.deposit(&user_account_id, borrow_asset_id, 2 * borrow_amount).await?;
+
+        // $1000 exceeds the 75% LTV of $250
         let result = env.tx_builder.borrow(&user_account_id, borrow_asset_id, borrow_amount).await;
+        assert!(result.is_err(), "undercollateralized borrow should fail");
+        println!("✅ Borrow failed as expected: {}", result.unwrap_err());
 
-        // In the current mock implementation, this will succeed, but in production it should fail
-        // For now, we log the result
-        match result {
-            Ok(_) => println!("⚠️  Mock implementation: borrow succeeded (should fail in production)"),
-            Err(e) => println!("✅ Borrow failed as expected: {}", e),
-        }
+        let reserve_data = env.tx_builder.get_reserve_data(borrow_asset_id).await?;
+        assert_eq!(reserve_data.total_borrowed, 0);
+
+        let user_info = env.tx_builder.get_account_info(&user_account_id).await?;
+        assert_eq!(user_info.scaled_debt(1), 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_borrow_without_collateral_fails() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        let user_id = env.user_id.clone();
+        let asset_id = 1; // USDC
+        env.tx_builder.deposit(&user_id, asset_id, 1000 * 10u64.pow(6)).await?;
+
+        let result = env.tx_builder.borrow(&user_id, asset_id, 10u64.pow(6)).await;
+        assert!(result.is_err(), "borrow without collateral should fail");
+
+        let reserve_data = env.tx_builder.get_reserve_data(asset_id).await?;
+        assert_eq!(reserve_data.total_borrowed, 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_borrow_capped_at_ltv() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        // Test scenario:
+        // 1. User supplies 1 WETH ($2500, 75% LTV, 82.5% liquidation threshold)
+        // 2. Borrowing 1900 USDC fails: the health factor would be 1.09, but it exceeds the LTV
+        // 3. Borrowing 1800 USDC succeeds
+
+        let user_id = env.user_id.clone();
+        let usdc_asset_id = 1;
+
+        env.tx_builder.supply_collateral(&user_id, 3, 10u64.pow(8)).await?; // 1 WETH
+        env.tx_builder.deposit(&user_id, usdc_asset_id, 4000 * 10u64.pow(6)).await?;
+
+        let result = env.tx_builder.borrow(&user_id, usdc_asset_id, 1900 * 10u64.pow(6)).await;
+        assert!(result.is_err(), "borrow above the LTV should fail");
+        println!("✅ Borrow above the LTV failed as expected: {}", result.unwrap_err());
+
+        env.tx_builder.borrow(&user_id, usdc_asset_id, 1800 * 10u64.pow(6)).await?;
+        println!("✅ Borrowed 1800 USDC within the LTV");
+
+        let user_info = env.tx_builder.get_account_info(&user_id).await?;
+        assert_eq!(user_info.scaled_debt(1), 1800 * 10u64.pow(6));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pool_rejects_borrow_over_ltv() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        // The pool checks the borrower's position in the borrow transaction itself
+        let user_id = env.user_id.clone();
+        env.tx_builder.supply_collateral(&user_id, 3, 10u64.pow(7)).await?; // 0.1 WETH ($250)
+        env.tx_builder.deposit(&user_id, 1, 2000 * 10u64.pow(6)).await?;
+
+        // The transaction builder rejects the borrow before sending the request note
+        let err = env.tx_builder
+            .borrow(&user_id, 1, 1000 * 10u64.pow(6)) // 1000 USDC
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("LTV limit"), "got: {:?}", err);
+
+        let note = position_note(&env, &user_id, PositionOperation::Borrow, 1, asset(1, 1000 * 10u64.pow(6)))?;
+        let err = submit_request(&mut env, &user_id, note).await.unwrap_err();
+        assert!(
+            fails_with(&err, &errors::ERR_LENDING_POOL_BORROW_EXCEEDS_LTV),
+            "borrow above the LTV should be rejected, got: {:?}", err
+        );
+        println!("✅ Lending pool rejected borrow above the LTV");
+
+        let reserve_data = env.tx_builder.get_reserve_data(1).await?;
+        assert_eq!(reserve_data.total_borrowed, 0);
+
+        let user_info = env.tx_builder.get_account_info(&user_id).await?;
+        assert_eq!(user_info.scaled_debt(1), 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_withdraw_collateral_keeps_health_factor() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        // Test scenario:
+        // 1. User supplies 1 WETH ($2500) and borrows 1000 USDC
+        // 2. User withdraws 0.5 WETH (HF 1.03)
+        // 3. Withdrawing another 0.1 WETH (HF 0.83) fails
+
+        let user_id = env.user_id.clone();
+        let weth_asset_id = 3;
+        let usdc_asset_id = 1;
+
+        env.tx_builder.supply_collateral(&user_id, weth_asset_id, 10u64.pow(8)).await?;
+        env.tx_builder.deposit(&user_id, usdc_asset_id, 2000 * 10u64.pow(6)).await?;
+        env.tx_builder.borrow(&user_id, usdc_asset_id, 1000 * 10u64.pow(6)).await?;
+
+        env.tx_builder.withdraw_collateral(&user_id, weth_asset_id, 5 * 10u64.pow(7)).await?;
+        println!("✅ Withdrew 0.5 WETH of collateral");
+
+        let user_info = env.tx_builder.get_account_info(&user_id).await?;
+        assert_eq!(user_info.collateral(3), 5 * 10u64.pow(7));
+        assert!(user_info.health_factor >= transactions::HEALTH_FACTOR_ONE);
+
+        // The transaction builder rejects the withdrawal before sending the request note, and the
+        // pool rejects it too
+        let err = env.tx_builder
+            .withdraw_collateral(&user_id, weth_asset_id, 10u64.pow(7))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("below 1.0"), "got: {:?}", err);
+
+        let note = position_note(
+            &env,
+            &user_id,
+            PositionOperation::WithdrawCollateral,
+            weth_asset_id,
+            asset(weth_asset_id, 10u64.pow(7)),
+        )?;
+        let err = submit_request(&mut env, &user_id, note).await.unwrap_err();
+        assert!(
+            fails_with(&err, &errors::ERR_LENDING_POOL_HEALTH_FACTOR_TOO_LOW),
+            "unhealthy withdrawal should be rejected, got: {:?}", err
+        );
+        println!("✅ Withdrawal leaving HF below 1.0 rejected");
+
+        let user_info = env.tx_builder.get_account_info(&user_id).await?;
+        assert_eq!(user_info.collateral(3), 5 * 10u64.pow(7));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_withdraw_collateral_without_debt() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        let user_id = env.user_id.clone();
+        let collateral_amount = 10u64.pow(8); // 1 WETH
+
+        env.tx_builder.supply_collateral(&user_id, 3, collateral_amount).await?;
+        env.tx_builder.withdraw_collateral(&user_id, 3, collateral_amount).await?;
+
+        let user_info = env.tx_builder.get_account_info(&user_id).await?;
+        assert_eq!(user_info.collateral(3), 0);
+        assert_eq!(user_info.health_factor, transactions::NO_DEBT_HEALTH_FACTOR);
 
         Ok(())
     }
@@ -228,50 +681,164 @@ mod tests {
         let mut env = setup_test_env().await?;
 
         // Test scenario:
-        // 1. Borrower supplies 1 WETH, borrows max DAI
-        // 2. WETH price drops, health factor < 1.0
+        // 1. Borrower supplies 1 WETH, borrows 1500 USDC
+        // 2. WETH price drops to $1700, health factor < 1.0
         // 3. Liquidator liquidates 50% of debt
         // 4. Liquidator receives collateral + 5% bonus
         // 5. Borrower's debt reduced, health factor improved
 
         // Step 1: Setup borrower with collateral and debt
-        let borrower_id = miden_client::AccountId(env.user_id.clone());
-        let collateral_amount = 10u64.pow(18); // 1 WETH
+        let borrower_id = env.user_id.clone();
+        let collateral_amount = 10u64.pow(8); // 1 WETH
         let collateral_asset_id = 3; // WETH
 
         env.tx_builder.supply_collateral(&borrower_id, collateral_asset_id, collateral_amount).await?;
         println!("✅ Borrower supplied 1 WETH as collateral");
 
-        // Borrow DAI
-        let borrow_amount = 1500 * 10u64.pow(8); // 1500 DAI (using 8 decimals)
-        let debt_asset_id = 2; // DAI
+        // Borrow USDC
+        let borrow_amount = 1500 * 10u64.pow(6); // 1500 USDC
+        let debt_asset_id = 1; // USDC
+
+        // Provide USDC liquidity to borrow from
+        env.tx_builder.deposit(&borrower_id, debt_asset_id, 2 * borrow_amount).await?;
 
         env.tx_builder.borrow(&borrower_id, debt_asset_id, borrow_amount).await?;
-        println!("✅ Borrower borrowed 1500 DAI");
+        println!("✅ Borrower borrowed 1500 USDC");
 
-        // Step 2: Create liquidator account
-        let liquidator_result = env.account_manager.create_user_account("private").await?;
-        let liquidator_id = miden_client::AccountId(extract_account_id(&liquidator_result));
+        // Step 2: Create liquidator account, holding the USDC to cover the debt with
+        let debt_to_cover = 750 * 10u64.pow(6); // Liquidate 50% of debt
+        let liquidator_id = env.account_manager.create_user_account("private", &env.pool_id).await?;
+        fund(&mut env, &liquidator_id, &[(debt_asset_id, debt_to_cover)]).await?;
         println!("✅ Created liquidator account");
 
-        // Step 3: Simulate price drop (WETH price drops from $2500 to $1500)
-        // This would make health factor < 1.0
-        println!("⚠️  Simulating WETH price drop to $1500");
+        // WETH price drops from $2500 to $1700: $1700 * 82.5% / $1500 is below 1.0
+        let weth_price = 1700 * 10u64.pow(8);
+        env.tx_builder.add_price_updater(&liquidator_id).await?;
+        env.tx_builder.update_price(&liquidator_id, collateral_asset_id, weth_price).await?;
+        let health_factor_before = env.tx_builder.calculate_health_factor(&borrower_id).await?;
+        println!("⚠️  WETH price dropped to $1700, health factor {:.2}", health_factor_before as f64 / 10000.0);
+        assert!(health_factor_before < transactions::HEALTH_FACTOR_ONE);
 
-        // Step 4: Execute liquidation
-        let debt_to_cover = 750 * 10u64.pow(8); // Liquidate 50% of debt
+        // Step 3: Execute liquidation
+        let (_, borrow_index) = env.tx_builder.get_reserve_indices(debt_asset_id).await?;
 
-        env.tx_builder.liquidate(
+        let liquidation = env.tx_builder.liquidate(
             &liquidator_id,
             &borrower_id,
             collateral_asset_id,
             debt_asset_id,
             debt_to_cover
         ).await?;
-        println!("✅ Liquidation executed: covered {} DAI", debt_to_cover / 10u64.pow(8));
+        assert_eq!(liquidation.health_factor_before, health_factor_before);
+        assert_eq!(liquidation.debt_covered, debt_to_cover);
+        println!("✅ Liquidation executed: covered {} USDC", debt_to_cover / 10u64.pow(6));
+
+        // Step 4: Liquidator pays the USDC and receives $750 * 105% of WETH in its wallet
+        let debt_value = debt_to_cover as u128 * 10u128.pow(8) / 10u128.pow(6);
+        let seized_value = debt_value * 10500 / 10000;
+        let collateral_seized = (seized_value * 10u128.pow(8) / weth_price as u128) as u64;
+
+        assert_eq!(env.chain.balance(&liquidator_id, faucet_id(debt_asset_id)).await?, 0);
+        assert_eq!(
+            env.chain.balance(&liquidator_id, faucet_id(collateral_asset_id)).await?,
+            collateral_seized
+        );
+        assert_eq!(liquidation.collateral_seized, collateral_seized);
+        println!("   Liquidator received {:.4} WETH", collateral_seized as f64 / 1e18);
+
+        // Step 5: Verify borrower's debt was reduced, scaled by the current borrow index
+        let borrower_info = env.tx_builder.get_account_info(&borrower_id).await?;
+        let scaled_debt_to_cover = fixed_point::wad_div_up(debt_to_cover, borrow_index).unwrap();
+        assert_eq!(borrower_info.scaled_debt(1), borrow_amount - scaled_debt_to_cover);
+        assert_eq!(borrower_info.collateral(3), collateral_amount - collateral_seized);
+        assert!(borrower_info.health_factor > health_factor_before);
+        println!("   Borrower health factor after: {:.2}", borrower_info.health_factor as f64 / 10000.0);
+
+        Ok(())
+    }
+
+    // Sets up a borrower with 1 WETH of collateral and 1500 USDC of debt, and a liquidator holding
+    // USDC, authorized to update prices
+    async fn setup_liquidation(
+        env: &mut TestEnvironment,
+        liquidator_funds: u64,
+    ) -> Result<(miden_client::AccountId, miden_client::AccountId)> {
+        let borrower_id = env.user_id.clone();
+        env.tx_builder.supply_collateral(&borrower_id, 3, 10u64.pow(8)).await?;
+        env.tx_builder.deposit(&borrower_id, 1, 3000 * 10u64.pow(6)).await?;
+        env.tx_builder.borrow(&borrower_id, 1, 1500 * 10u64.pow(6)).await?;
+
+        let liquidator_id = env.account_manager.create_user_account("private", &env.pool_id).await?;
+        fund(env, &liquidator_id, &[(1, liquidator_funds)]).await?;
+        env.tx_builder.add_price_updater(&liquidator_id).await?;
+
+        Ok((borrower_id, liquidator_id))
+    }
+
+    #[tokio::test]
+    async fn test_liquidation_capped_by_close_factor() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        let debt_to_cover = 1500 * 10u64.pow(6);
+        let (borrower_id, liquidator_id) = setup_liquidation(&mut env, debt_to_cover).await?;
+
+        // WETH drops to $1700, and the liquidator tries to cover the whole debt
+        let weth_price = 1700 * 10u64.pow(8);
+        env.tx_builder.update_price(&liquidator_id, 3, weth_price).await?;
+        let liquidation = env.tx_builder.liquidate(&liquidator_id, &borrower_id, 3, 1, debt_to_cover).await?;
+
+        // Only half the debt, grown by a few seconds of interest, is covered, and the rest refunded
+        let half_debt = debt_to_cover / 2;
+        assert!(
+            liquidation.debt_covered >= half_debt && liquidation.debt_covered - half_debt < 100,
+            "covered {} of the debt", liquidation.debt_covered
+        );
+        assert_eq!(
+            env.chain.balance(&liquidator_id, faucet_id(1)).await?,
+            debt_to_cover - liquidation.debt_covered
+        );
+        println!("✅ Liquidation covered {} USDC, half the debt", liquidation.debt_covered as f64 / 1e6);
+
+        // Collateral is seized for the covered debt only
+        let seized_value = liquidation.debt_covered as u128 * 10u128.pow(2) * 10500 / 10000;
+        let collateral_seized = (seized_value * 10u128.pow(8) / weth_price as u128) as u64;
+        assert_eq!(liquidation.collateral_seized, collateral_seized);
+        assert_eq!(env.chain.balance(&liquidator_id, faucet_id(3)).await?, collateral_seized);
+
+        let borrower_info = env.tx_builder.get_account_info(&borrower_id).await?;
+        assert_eq!(borrower_info.collateral(3), 10u64.pow(8) - collateral_seized);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_liquidation_capped_by_collateral() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        let debt_to_cover = 750 * 10u64.pow(6);
+        let (borrower_id, liquidator_id) = setup_liquidation(&mut env, debt_to_cover).await?;
+
+        // WETH crashes to $500: half the debt with the bonus is worth more than the collateral
+        env.tx_builder.update_price(&liquidator_id, 3, 500 * 10u64.pow(8)).await?;
+        let debt_before = env.tx_builder.get_reserve_position(&borrower_id, 1).await?.scaled_debt;
+        let liquidation = env.tx_builder.liquidate(&liquidator_id, &borrower_id, 3, 1, debt_to_cover).await?;
 
-        // Step 5: Verify results (in production, would check actual state changes)
-        println!("⚠️  Mock implementation: verification skipped");
+        // All the collateral is seized, covering the $500 it is worth without the 5% bonus
+        let debt_covered = (500 * 10u64.pow(8) * 10000 / 10500) / 100;
+        assert_eq!(liquidation.collateral_seized, 10u64.pow(8));
+        assert_eq!(liquidation.debt_covered, debt_covered);
+        assert_eq!(env.chain.balance(&liquidator_id, faucet_id(3)).await?, 10u64.pow(8));
+        assert_eq!(env.chain.balance(&liquidator_id, faucet_id(1)).await?, debt_to_cover - debt_covered);
+        println!("✅ Liquidation seized all the collateral for {} USDC", debt_covered as f64 / 1e6);
+
+        let position = env.tx_builder.get_reserve_position(&borrower_id, 3).await?;
+        assert_eq!(position.collateral, 0);
+
+        // The index read now has grown a few seconds past the one the liquidation scaled with
+        let (_, borrow_index) = env.tx_builder.get_reserve_indices(1).await?;
+        let scaled_debt = env.tx_builder.get_reserve_position(&borrower_id, 1).await?.scaled_debt;
+        let scaled_covered = fixed_point::wad_div_up(debt_covered, borrow_index).unwrap();
+        assert!((debt_before - scaled_debt).abs_diff(scaled_covered) < 100);
 
         Ok(())
     }
@@ -286,8 +853,8 @@ mod tests {
         // 3. Transaction should fail
 
         // Step 1: Setup borrower with healthy position
-        let borrower_id = miden_client::AccountId(env.user_id.clone());
-        let collateral_amount = 10u64.pow(18); // 1 WETH ($2500)
+        let borrower_id = env.user_id.clone();
+        let collateral_amount = 10u64.pow(8); // 1 WETH ($2500)
         let collateral_asset_id = 3; // WETH
 
         env.tx_builder.supply_collateral(&borrower_id, collateral_asset_id, collateral_amount).await?;
@@ -296,14 +863,16 @@ mod tests {
         let borrow_amount = 500 * 10u64.pow(8); // 500 DAI (healthy HF > 1.5)
         let debt_asset_id = 2; // DAI
 
+        // Provide DAI liquidity to borrow from
+        env.tx_builder.deposit(&borrower_id, debt_asset_id, 2 * borrow_amount).await?;
+
         env.tx_builder.borrow(&borrower_id, debt_asset_id, borrow_amount).await?;
         println!("✅ Borrower has healthy position with HF > 1.5");
 
-        // Step 2: Create liquidator and attempt liquidation
-        let liquidator_result = env.account_manager.create_user_account("private").await?;
-        let liquidator_id = miden_client::AccountId(extract_account_id(&liquidator_result));
-
+        // Step 2: Create liquidator, holding the DAI to cover the debt with, and attempt liquidation
         let debt_to_cover = 250 * 10u64.pow(8);
+        let liquidator_id = env.account_manager.create_user_account("private", &env.pool_id).await?;
+        fund(&mut env, &liquidator_id, &[(debt_asset_id, debt_to_cover)]).await?;
 
         // Step 3: Attempt liquidation (should fail)
         let result = env.tx_builder.liquidate(
@@ -313,12 +882,239 @@ mod tests {
             debt_asset_id,
             debt_to_cover
         ).await;
+        // The client rejects it before sending the liquidation note
+        let err = result.unwrap_err();
+        assert!(err.to_string().contains("can only be liquidated below 1.0"), "got: {:?}", err);
+        assert_eq!(env.chain.balance(&liquidator_id, faucet_id(debt_asset_id)).await?, debt_to_cover);
+        println!("✅ Liquidation failed as expected: {}", err);
 
-        // In mock implementation, this succeeds, but in production it should fail
-        match result {
-            Ok(_) => println!("⚠️  Mock implementation: liquidation succeeded (should fail in production)"),
-            Err(e) => println!("✅ Liquidation failed as expected: {}", e),
+        let borrower_info = env.tx_builder.get_account_info(&borrower_id).await?;
+        assert_eq!(borrower_info.scaled_debt(2), borrow_amount);
+        assert_eq!(borrower_info.collateral(3), collateral_amount);
+
+        assert_eq!(env.chain.balance(&liquidator_id, faucet_id(collateral_asset_id)).await?, 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_positions_riskiest_first() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        // Three borrowers against 1 WETH ($2500) each: no debt, 1000 USDC and 1500 USDC
+        let lender_id = env.user_id.clone();
+        env.tx_builder.deposit(&lender_id, 1, 5000 * 10u64.pow(6)).await?;
+
+        let mut borrower_ids = Vec::new();
+        for debt in [0, 1000, 1500] {
+            let borrower_id = env.account_manager.create_user_account("private", &env.pool_id).await?;
+            fund(&mut env, &borrower_id, &[(3, 10u64.pow(8))]).await?;
+            env.tx_builder.supply_collateral(&borrower_id, 3, 10u64.pow(8)).await?;
+            if debt > 0 {
+                env.tx_builder.borrow(&borrower_id, 1, debt * 10u64.pow(6)).await?;
+            }
+            borrower_ids.push(borrower_id);
+        }
+        let [safe_id, borrower_id, risky_id] = <[_; 3]>::try_from(borrower_ids.clone()).unwrap();
+
+        let positions = env.tx_builder.get_positions(&borrower_ids, false).await?;
+        let account_ids: Vec<_> = positions.iter().map(|position| position.account_id.0.clone()).collect();
+        assert_eq!(account_ids, [risky_id.0.clone(), borrower_id.0, safe_id.0]);
+        assert_eq!(positions[2].health_factor, transactions::NO_DEBT_HEALTH_FACTOR);
+        assert!(positions.iter().all(|position| !position.is_liquidatable()));
+        assert!(env.tx_builder.get_positions(&borrower_ids, true).await?.is_empty());
+
+        // WETH drops to $1700: only the 1500 USDC borrower falls below 1.0
+        env.tx_builder.add_price_updater(&lender_id).await?;
+        env.tx_builder.update_price(&lender_id, 3, 1700 * 10u64.pow(8)).await?;
+
+        let positions = env.tx_builder.get_positions(&borrower_ids, true).await?;
+        assert_eq!(positions.len(), 1);
+        assert_eq!(positions[0].account_id.0, risky_id.0);
+        assert!(positions[0].health_factor < transactions::HEALTH_FACTOR_ONE);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pool_rejects_healthy_liquidation() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        // Sending the liquidation note directly skips the client-side check, so the pool itself
+        // must reject the liquidation
+        let user_id = env.user_id.clone();
+        env.tx_builder.supply_collateral(&user_id, 3, 10u64.pow(8)).await?; // 1 WETH ($2500)
+        env.tx_builder.deposit(&user_id, 1, 2000 * 10u64.pow(6)).await?;
+        env.tx_builder.borrow(&user_id, 1, 1000 * 10u64.pow(6)).await?; // 1000 USDC
+
+        let liquidator_id = env.account_manager.create_user_account("private", &env.pool_id).await?;
+        fund(&mut env, &liquidator_id, &[(1, 500 * 10u64.pow(6))]).await?;
+        let note = components::liquidation_note::create_liquidation_note(
+            (&liquidator_id).try_into()?,
+            (&env.pool_id).try_into()?,
+            (&user_id).try_into()?,
+            3, // WETH collateral
+            1, // USDC debt
+            asset(1, 500 * 10u64.pow(6)), // 500 USDC
+            miden_objects::Word::default(),
+            miden_objects::block::BlockNumber::GENESIS,
+        )?;
+
+        let err = submit_request(&mut env, &liquidator_id, note.clone()).await.unwrap_err();
+        assert!(
+            fails_with(&err, &errors::ERR_LENDING_POOL_POSITION_HEALTHY),
+            "healthy liquidation should be rejected, got: {:?}", err
+        );
+        println!("✅ Lending pool rejected liquidation of a healthy position");
+
+        let user_info = env.tx_builder.get_account_info(&user_id).await?;
+        assert_eq!(user_info.collateral(3), 10u64.pow(8));
+
+        // The liquidator takes the debt it meant to cover back from the rejected note
+        assert_eq!(env.chain.balance(&liquidator_id, faucet_id(1)).await?, 0);
+        env.chain.consume_notes(vec![note], Default::default(), &liquidator_id).await?;
+        assert_eq!(env.chain.balance(&liquidator_id, faucet_id(1)).await?, 500 * 10u64.pow(6));
+        println!("✅ Liquidator reclaimed the rejected liquidation note");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_same_asset_liquidation_rejected() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        let user_id = env.user_id.clone();
+        env.tx_builder.supply_collateral(&user_id, 1, 2000 * 10u64.pow(6)).await?; // 2000 USDC
+        env.tx_builder.deposit(&user_id, 1, 2000 * 10u64.pow(6)).await?;
+        env.tx_builder.borrow(&user_id, 1, 1000 * 10u64.pow(6)).await?; // 1000 USDC
+
+        let liquidator_id = env.account_manager.create_user_account("private", &env.pool_id).await?;
+        fund(&mut env, &liquidator_id, &[(1, 500 * 10u64.pow(6))]).await?;
+
+        // The transaction builder rejects the liquidation before sending the note
+        let err = env
+            .tx_builder
+            .liquidate(&liquidator_id, &user_id, 1, 1, 500 * 10u64.pow(6))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("different assets"), "got: {:?}", err);
+
+        // The pool rejects it too
+        let note = components::liquidation_note::create_liquidation_note(
+            (&liquidator_id).try_into()?,
+            (&env.pool_id).try_into()?,
+            (&user_id).try_into()?,
+            1, // USDC collateral
+            1, // USDC debt
+            asset(1, 500 * 10u64.pow(6)),
+            miden_objects::Word::default(),
+            miden_objects::block::BlockNumber::GENESIS,
+        )?;
+        let err = submit_request(&mut env, &liquidator_id, note).await.unwrap_err();
+        assert!(
+            fails_with(&err, &errors::ERR_LENDING_POOL_SAME_LIQUIDATION_ASSET),
+            "got: {:?}", err
+        );
+        println!("✅ Liquidation with the same collateral and debt asset rejected");
+
+        let user_info = env.tx_builder.get_account_info(&user_id).await?;
+        assert_eq!(user_info.collateral(1), 2000 * 10u64.pow(6));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_reclaim_rejected_repay_note() -> Result<()> {
+        use miden_objects::{account::AccountId as MidenAccountId, Word};
+
+        let mut env = setup_test_env().await?;
+        let user_id = env.account_manager.create_user_account("private", &env.pool_id).await?;
+        fund(&mut env, &user_id, &[(1, 1000 * 10u64.pow(6))]).await?;
+        let reclaim_height = env.chain.latest_block_num().await + 3;
+
+        // Repaying without debt is rejected by the pool, leaving the note unconsumed
+        let note = components::position_note::create_position_note(
+            MidenAccountId::try_from(&user_id)?,
+            MidenAccountId::try_from(&env.pool_id)?,
+            PositionOperation::Repay,
+            1,
+            asset(1, 1000 * 10u64.pow(6)),
+            Word::default(),
+            reclaim_height,
+        )?;
+        let err = submit_request(&mut env, &user_id, note.clone()).await.unwrap_err();
+        assert!(fails_with(&err, &errors::ERR_LENDING_POOL_NO_DEBT), "got: {:?}", err);
+
+        // Only the sender can reclaim the note, once the reclaim height is reached
+        let other_id = env.account_manager.create_user_account("private", &env.pool_id).await?;
+        assert!(env.chain.consume_notes(vec![note.clone()], Default::default(), &other_id).await.is_err());
+
+        let err = env
+            .chain
+            .consume_notes(vec![note.clone()], Default::default(), &user_id)
+            .await
+            .unwrap_err();
+        assert!(fails_with(&err, &errors::ERR_RECLAIM_HEIGHT_NOT_REACHED), "got: {:?}", err);
+
+        while env.chain.latest_block_num().await < reclaim_height {
+            env.chain.advance_time(1).await?;
+        }
+        env.chain.consume_notes(vec![note], Default::default(), &user_id).await?;
+        assert_eq!(env.chain.balance(&user_id, faucet_id(1)).await?, 1000 * 10u64.pow(6));
+        println!("✅ Sender reclaimed the rejected repay note after the reclaim height");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_other_pool_cannot_consume_request_notes() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        // A second pool deployed with the same component accepts request notes without a signature
+        let faucet_ids = [1, 2, 3].map(|asset_id| faucet_id(asset_id).into());
+        let oracle_id = env.oracle_id.clone();
+        let other_pool_id = env.account_manager.deploy_lending_pool(&oracle_id, &faucet_ids).await?;
+
+        let user_id = env.user_id.clone();
+        let deposit = position_note(&env, &user_id, PositionOperation::Deposit, 1, asset(1, 1000))?;
+        let liquidation = components::liquidation_note::create_liquidation_note(
+            (&user_id).try_into()?,
+            (&env.pool_id).try_into()?,
+            (&user_id).try_into()?,
+            3, // WETH collateral
+            1, // USDC debt
+            asset(1, 1000),
+            miden_objects::Word::default(),
+            miden_objects::block::BlockNumber::GENESIS,
+        )?;
+        env.chain.send_notes(vec![deposit.clone(), liquidation.clone()], &user_id).await?;
+
+        for note in [deposit.clone(), liquidation] {
+            let err = env
+                .chain
+                .consume_notes_with_foreign_accounts(
+                    vec![note],
+                    Default::default(),
+                    &other_pool_id,
+                    std::slice::from_ref(&env.oracle_id),
+                )
+                .await
+                .unwrap_err();
+            assert!(fails_with(&err, &errors::ERR_RECLAIM_WRONG_POOL), "got: {:?}", err);
         }
+        assert_eq!(env.chain.balance(&other_pool_id, faucet_id(1)).await?, 0);
+        println!("✅ Second pool failed to consume request notes addressed to the first");
+
+        // The pool the notes are addressed to still consumes them
+        env.chain
+            .consume_notes_with_foreign_accounts(
+                vec![deposit],
+                Default::default(),
+                &env.pool_id,
+                std::slice::from_ref(&env.oracle_id),
+            )
+            .await?;
+        assert_eq!(env.tx_builder.get_reserve_data(1).await?.total_liquidity, 1000);
 
         Ok(())
     }
@@ -327,6 +1123,58 @@ mod tests {
     // Interest Rate Tests
     // =============================================================================================
 
+    #[tokio::test]
+    async fn test_liquidation_at_twap_prices() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        // Borrower supplies 1 WETH and borrows 1500 USDC, as in test_liquidation_flow
+        let borrower_id = env.user_id.clone();
+        let (collateral_asset_id, debt_asset_id) = (3, 1);
+        env.tx_builder.supply_collateral(&borrower_id, collateral_asset_id, 10u64.pow(8)).await?;
+        env.tx_builder.deposit(&borrower_id, debt_asset_id, 3000 * 10u64.pow(6)).await?;
+        env.tx_builder.borrow(&borrower_id, debt_asset_id, 1500 * 10u64.pow(6)).await?;
+
+        let debt_to_cover = 750 * 10u64.pow(6);
+        let liquidator_id = env.account_manager.create_user_account("private", &env.pool_id).await?;
+        fund(&mut env, &liquidator_id, &[(debt_asset_id, debt_to_cover)]).await?;
+        env.tx_builder.add_price_updater(&liquidator_id).await?;
+
+        env.tx_builder.set_liquidation_price_source(components::lending_pool::PriceSource::Twap).await?;
+        assert_eq!(env.tx_builder.get_liquidation_price_source().await?, components::lending_pool::PriceSource::Twap);
+
+        // A sudden drop makes the position liquidatable at the spot price only
+        let weth_price = 1700 * 10u64.pow(8);
+        env.tx_builder.update_price(&liquidator_id, collateral_asset_id, weth_price).await?;
+        assert!(env.tx_builder.calculate_health_factor(&borrower_id).await? < transactions::HEALTH_FACTOR_ONE);
+        assert!(
+            env.tx_builder.calculate_liquidation_health_factor(&borrower_id).await?
+                >= transactions::HEALTH_FACTOR_ONE
+        );
+
+        let result = env.tx_builder
+            .liquidate(&liquidator_id, &borrower_id, collateral_asset_id, debt_asset_id, debt_to_cover)
+            .await;
+        assert!(result.is_err());
+        println!("✅ Liquidation at the TWAP ignores the sudden drop");
+
+        // Once the drop lasts the whole TWAP window, the TWAP is the new price
+        env.chain.advance_time(components::price_oracle::PriceOracleAccount::DEFAULT_TWAP_WINDOW as u32).await?;
+        assert_eq!(env.tx_builder.get_twap(collateral_asset_id).await?, weth_price);
+
+        env.tx_builder
+            .liquidate(&liquidator_id, &borrower_id, collateral_asset_id, debt_asset_id, debt_to_cover)
+            .await?;
+
+        let seized_value = debt_to_cover as u128 * 10u128.pow(2) * 10500 / 10000;
+        let collateral_seized = (seized_value * 10u128.pow(8) / weth_price as u128) as u64;
+        assert_eq!(
+            env.chain.balance(&liquidator_id, faucet_id(collateral_asset_id)).await?,
+            collateral_seized
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_interest_rate_updates() -> Result<()> {
         let mut env = setup_test_env().await?;
@@ -337,11 +1185,14 @@ mod tests {
         // 3. Increase utilization to 95% - verify slope 2 rates
 
         let asset_id = 1; // USDC
-        let user_id = miden_client::AccountId(env.user_id.clone());
+        let user_id = env.user_id.clone();
+
+        let strategy = components::lending_pool::RateStrategy::stablecoin();
 
         // Step 1: Check 0% utilization
         let reserve_data = env.tx_builder.get_reserve_data(asset_id).await?;
-        println!("📊 0% utilization - Borrow rate: {} bps", reserve_data.borrow_rate);
+        assert_eq!(Some(reserve_data.borrow_rate), fixed_point::bps_to_wad(strategy.base_rate));
+        println!("📊 0% utilization - Borrow rate: {}", utils::wad_to_percentage(reserve_data.borrow_rate));
 
         // Step 2: Create 50% utilization by depositing 1000 and borrowing 500
         let deposit_amount = 1000 * 10u64.pow(6); // 1000 USDC
@@ -349,7 +1200,7 @@ mod tests {
         println!("✅ Deposited 1000 USDC");
 
         // Supply collateral first
-        let collateral_amount = 10u64.pow(18); // 1 WETH
+        let collateral_amount = 10u64.pow(8); // 1 WETH
         env.tx_builder.supply_collateral(&user_id, 3, collateral_amount).await?;
 
         let borrow_amount = 500 * 10u64.pow(6); // 500 USDC (50% utilization)
@@ -357,111 +1208,324 @@ mod tests {
         println!("✅ Borrowed 500 USDC (50% utilization)");
 
         let reserve_data = env.tx_builder.get_reserve_data(asset_id).await?;
-        println!("📊 50% utilization - Borrow rate: {} bps", reserve_data.borrow_rate);
+        let utilization = fixed_point::bps_to_wad(5000).unwrap();
+        assert_eq!(Some((reserve_data.borrow_rate, reserve_data.liquidity_rate)), strategy.rates_at(utilization));
+        assert_eq!(reserve_data.borrow_rate, 22_222_222_222_222_222);
+        println!("📊 50% utilization - Borrow rate: {}", utils::wad_to_percentage(reserve_data.borrow_rate));
 
         // Step 3: Create 95% utilization by borrowing additional 450
         let additional_borrow = 450 * 10u64.pow(6); // 450 USDC
         env.tx_builder.borrow(&user_id, asset_id, additional_borrow).await?;
         println!("✅ Borrowed additional 450 USDC (95% utilization)");
 
+        // Debt grows faster than deposits, so utilization is slightly above 95% by now
         let reserve_data = env.tx_builder.get_reserve_data(asset_id).await?;
-        println!("📊 95% utilization - Borrow rate: {} bps (should be much higher)", reserve_data.borrow_rate);
+        let utilization = components::lending_pool::utilization(reserve_data.total_borrowed, reserve_data.total_liquidity);
+        assert_eq!(Some((reserve_data.borrow_rate, reserve_data.liquidity_rate)), utilization.and_then(|u| strategy.rates_at(u)));
+        assert!(reserve_data.borrow_rate.abs_diff(340_000_000_000_000_000) < 1_000_000_000_000);
+        println!("📊 95% utilization - Borrow rate: {} (should be much higher)", utils::wad_to_percentage(reserve_data.borrow_rate));
 
         Ok(())
     }
 
-    // =============================================================================================
-    // Edge Case Tests
-    // =============================================================================================
-
     #[tokio::test]
-    async fn test_zero_amount_deposit_fails() -> Result<()> {
+    async fn test_interest_accrues_for_years_at_max_utilization() -> Result<()> {
         let mut env = setup_test_env().await?;
 
-        // Attempt deposit with amount = 0
-        let user_id = miden_client::AccountId(env.user_id.clone());
-        let asset_id = 1; // USDC
+        // Fully borrowed, the USDC curve charges 64% a year, so the borrow index outgrows a wad in
+        // a field element within five years. Accrual must saturate at the cap instead of failing,
+        // and utilization stays capped at 100% as debts outgrow the liquidity.
+        use components::lending_pool::MAX_INDEX;
 
-        let result = env.tx_builder.deposit(&user_id, asset_id, 0).await;
+        let asset_id = 1; // USDC
+        let user_id = env.user_id.clone();
+        env.tx_builder.deposit(&user_id, asset_id, 1000 * 10u64.pow(6)).await?;
+        env.tx_builder.supply_collateral(&user_id, 3, 10u64.pow(8)).await?; // 1 WETH
+        env.tx_builder.borrow(&user_id, asset_id, 1000 * 10u64.pow(6)).await?;
 
-        // In mock implementation, this might succeed, but in production it should fail
-        match result {
-            Ok(_) => println!("⚠️  Mock implementation: zero deposit succeeded (should fail in production)"),
-            Err(e) => println!("✅ Zero deposit failed as expected: {}", e),
+        let reserve_data = env.tx_builder.get_reserve_data(asset_id).await?;
+        assert_eq!(reserve_data.borrow_rate, 640_000_000_000_000_000);
+
+        let mut last_borrow_index = 0;
+        for year in 1..=5 {
+            env.chain.advance_time(31_536_000).await?;
+
+            // Depositing accrues the reserve and stores its indices
+            env.tx_builder.deposit(&user_id, asset_id, 10u64.pow(6)).await?;
+
+            let (liquidity_index, borrow_index) = env.tx_builder.get_reserve_indices(asset_id).await?;
+            assert!(borrow_index > last_borrow_index || borrow_index == MAX_INDEX);
+            assert!(borrow_index <= MAX_INDEX && liquidity_index <= borrow_index);
+            let reserve_data = env.tx_builder.get_reserve_data(asset_id).await?;
+            assert_eq!(reserve_data.borrow_rate, 640_000_000_000_000_000);
+            println!(
+                "   Year {}: liquidity index {}, borrow index {}",
+                year,
+                utils::format_index(liquidity_index),
+                utils::format_index(borrow_index)
+            );
+            last_borrow_index = borrow_index;
         }
 
+        assert_eq!(last_borrow_index, MAX_INDEX);
+        println!("✅ Indices saturated at the cap after five years at max utilization");
+
         Ok(())
     }
 
     #[tokio::test]
-    async fn test_withdraw_exceeds_balance_fails() -> Result<()> {
+    async fn test_set_rate_strategy() -> Result<()> {
         let mut env = setup_test_env().await?;
 
-        // Deposit 100 USDC
-        let user_id = miden_client::AccountId(env.user_id.clone());
-        let asset_id = 1; // USDC
-        let deposit_amount = 100 * 10u64.pow(6);
+        // Test scenario:
+        // 1. Stablecoins and WETH are listed with different curves
+        // 2. Admin raises the USDC base rate
+        // 3. The new curve applies from the next rate update
+        // 4. Invalid strategies are rejected
 
-        env.tx_builder.deposit(&user_id, asset_id, deposit_amount).await?;
-        println!("✅ Deposited 100 USDC");
+        use components::lending_pool::RateStrategy;
 
-        // Attempt to withdraw 200 USDC (more than deposited)
-        let withdraw_amount = 200 * 10u64.pow(6);
+        let usdc_strategy = env.tx_builder.get_rate_strategy(1).await?;
+        let weth_strategy = env.tx_builder.get_rate_strategy(3).await?;
+        assert_eq!(usdc_strategy, RateStrategy::stablecoin());
+        assert_eq!(weth_strategy, RateStrategy::volatile());
 
-        let result = env.tx_builder.withdraw(&user_id, asset_id, withdraw_amount).await;
+        let strategy = RateStrategy { base_rate: 100, ..usdc_strategy };
+        env.tx_builder.set_rate_strategy(1, strategy).await?;
+        assert_eq!(env.tx_builder.get_rate_strategy(1).await?, strategy);
+        println!("✅ USDC base rate raised to 1%");
 
-        // In mock implementation, this might succeed, but in production it should fail
-        match result {
-            Ok(_) => println!("⚠️  Mock implementation: over-withdrawal succeeded (should fail in production)"),
-            Err(e) => println!("✅ Over-withdrawal failed as expected: {}", e),
-        }
+        // Setting the strategy recalculates rates, so an empty reserve now charges the base rate
+        let reserve_data = env.tx_builder.get_reserve_data(1).await?;
+        assert_eq!(Some(reserve_data.borrow_rate), fixed_point::bps_to_wad(100));
+        assert_eq!(reserve_data.liquidity_rate, 0);
+
+        let invalid = RateStrategy { optimal_utilization: 10000, ..usdc_strategy };
+        assert!(env.tx_builder.set_rate_strategy(1, invalid).await.is_err());
+        println!("✅ Invalid strategy rejected");
 
         Ok(())
     }
 
+    // =============================================================================================
+    // Treasury Tests
+    // =============================================================================================
+
     #[tokio::test]
-    async fn test_borrow_exceeds_liquidity_fails() -> Result<()> {
+    async fn test_treasury_accrues_reserve_factor() -> Result<()> {
         let mut env = setup_test_env().await?;
 
-        // Setup: Pool has 1000 USDC liquidity
-        let user_id = miden_client::AccountId(env.user_id.clone());
-        let asset_id = 1; // USDC
-        let deposit_amount = 1000 * 10u64.pow(6);
-
-        env.tx_builder.deposit(&user_id, asset_id, deposit_amount).await?;
-        println!("✅ Pool has 1000 USDC liquidity");
+        // Test scenario:
+        // 1. Nothing accrues to the treasury before anything is borrowed
+        // 2. User borrows 15000 USDC
+        // 3. The treasury accrues the reserve factor share of the borrow interest
 
-        // Borrower has sufficient collateral for 2000 USDC
-        let collateral_amount = 2 * 10u64.pow(18); // 2 WETH ($5000 worth)
-        let collateral_asset_id = 3; // WETH
+        let user_id = env.user_id.clone();
+        let asset_id = 1; // USDC
+        let borrow_amount = 15000 * 10u64.pow(6);
 
-        env.tx_builder.supply_collateral(&user_id, collateral_asset_id, collateral_amount).await?;
-        println!("✅ Borrower has sufficient collateral for 2000 USDC");
+        env.tx_builder.deposit(&user_id, asset_id, 20000 * 10u64.pow(6)).await?;
+        env.tx_builder.supply_collateral(&user_id, 3, 9 * 10u64.pow(8)).await?; // 9 WETH
+        assert_eq!(env.tx_builder.get_accrued_to_treasury(asset_id).await?, 0);
 
-        // Attempt to borrow 1500 USDC (exceeds pool liquidity of 1000)
-        let borrow_amount = 1500 * 10u64.pow(6);
+        // Read at the same reference block as the pool's borrow transaction
+        let (_, index_at_borrow) = env.tx_builder.get_reserve_indices(asset_id).await?;
+        env.tx_builder.borrow(&user_id, asset_id, borrow_amount).await?;
 
-        let result = env.tx_builder.borrow(&user_id, asset_id, borrow_amount).await;
+        let (_, borrow_index) = env.tx_builder.get_reserve_indices(asset_id).await?;
+        let accrued = env.tx_builder.get_accrued_to_treasury(asset_id).await?;
+        println!("📊 Accrued to treasury: {} USDC units", accrued);
 
-        // In mock implementation, this might succeed, but in production it should fail
-        match result {
-            Ok(_) => println!("⚠️  Mock implementation: over-borrow succeeded (should fail in production)"),
-            Err(e) => println!("✅ Borrow exceeding liquidity failed as expected: {}", e),
-        }
+        let total_borrowed = fixed_point::mul_div(borrow_amount, borrow_index, index_at_borrow).unwrap();
+        let reserve_factor = components::lending_pool::RateStrategy::stablecoin().reserve_factor;
+        assert!(accrued > 0);
+        assert_eq!(accrued, (total_borrowed - borrow_amount) * reserve_factor / 10000);
 
         Ok(())
     }
 
-    // =============================================================================================
-    // Multi-User Tests
-    // =============================================================================================
-
     #[tokio::test]
-    async fn test_multiple_users_deposit_withdraw() -> Result<()> {
+    async fn test_claim_reserves_to_treasury() -> Result<()> {
         let mut env = setup_test_env().await?;
 
         // Test scenario:
-        // 1. User A deposits 1000 USDC
+        // 1. Reserves accrue to the treasury from a USDC borrow
+        // 2. Claiming more than accrued fails
+        // 3. Claiming the accrued reserves sends a P2ID note to the treasury account
+
+        let user_id = env.user_id.clone();
+        let asset_id = 1; // USDC
+
+        env.tx_builder.deposit(&user_id, asset_id, 1000 * 10u64.pow(6)).await?;
+        env.tx_builder.supply_collateral(&user_id, 3, 10u64.pow(8)).await?; // 1 WETH
+        env.tx_builder.borrow(&user_id, asset_id, 800 * 10u64.pow(6)).await?;
+        env.chain.advance_time(86_400).await?;
+        env.tx_builder.deposit(&user_id, asset_id, 10u64.pow(6)).await?;
+
+        let treasury_id = env.account_manager.create_user_account("private", &env.pool_id).await?;
+
+        // Reads and transactions share the latest block, so this is exactly what the claim accrues
+        let accrued = env.tx_builder.get_accrued_to_treasury(asset_id).await?;
+        assert!(accrued > 0);
+
+        let err = env.tx_builder
+            .claim_reserves(asset_id, accrued + 1, &treasury_id)
+            .await
+            .unwrap_err();
+        assert!(
+            fails_with(&err, &errors::ERR_LENDING_POOL_CLAIM_EXCEEDS_TREASURY),
+            "claim above the accrued reserves should be rejected, got: {:?}", err
+        );
+        println!("✅ Claim above the accrued reserves rejected");
+
+        let accrued = env.tx_builder.get_accrued_to_treasury(asset_id).await?;
+        let pool_balance = env.chain.balance(&env.pool_id, faucet_id(asset_id)).await?;
+        env.tx_builder.claim_reserves(asset_id, accrued, &treasury_id).await?;
+        println!("✅ Claimed {} USDC units of reserves", accrued);
+
+        // The claimed USDC left the pool's vault in a P2ID note to the treasury
+        assert_eq!(env.chain.balance(&env.pool_id, faucet_id(asset_id)).await?, pool_balance - accrued);
+
+        let notes = env.chain.get_notes(&treasury_id).await?;
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].metadata().sender(), miden_objects::account::AccountId::try_from(&env.pool_id)?);
+        assert_eq!(notes[0].assets().iter().collect::<Vec<_>>(), [&asset(asset_id, accrued).into()]);
+
+        env.chain.consume_notes(notes, Default::default(), &treasury_id).await?;
+        assert_eq!(env.chain.balance(&treasury_id, faucet_id(asset_id)).await?, accrued);
+        assert!(env.chain.get_notes(&treasury_id).await?.is_empty());
+
+        // Only what accrued since the claim is left
+        let remaining = env.tx_builder.get_accrued_to_treasury(asset_id).await?;
+        assert!(remaining < accrued);
+
+        Ok(())
+    }
+
+    // =============================================================================================
+    // Edge Case Tests
+    // =============================================================================================
+
+    #[tokio::test]
+    async fn test_zero_amount_deposit_fails() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        // Attempt deposit with amount = 0
+        let user_id = env.user_id.clone();
+        let asset_id = 1; // USDC
+
+        let result = env.tx_builder.deposit(&user_id, asset_id, 0).await;
+
+        // The pool does not reject zero amounts yet, so we log the result
+        match result {
+            Ok(_) => println!("⚠️  Zero deposit succeeded: amounts are not validated yet"),
+            Err(e) => println!("✅ Zero deposit failed as expected: {}", e),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_withdraw_exceeds_balance_fails() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        // Deposit 100 USDC
+        let user_id = env.user_id.clone();
+        let asset_id = 1; // USDC
+        let deposit_amount = 100 * 10u64.pow(6);
+
+        env.tx_builder.deposit(&user_id, asset_id, deposit_amount).await?;
+        println!("✅ Deposited 100 USDC");
+
+        // Attempt to withdraw 200 USDC (more than deposited)
+        let withdraw_amount = 200 * 10u64.pow(6);
+
+        let result = env.tx_builder.withdraw(&user_id, asset_id, withdraw_amount).await;
+        assert!(result.is_err(), "over-withdrawal should fail");
+        println!("✅ Over-withdrawal failed as expected");
+
+        // Pool liquidity is unchanged
+        let reserve_data = env.tx_builder.get_reserve_data(asset_id).await?;
+        assert_eq!(reserve_data.total_liquidity, deposit_amount);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_borrow_exceeds_liquidity_fails() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        // Setup: Pool has 1000 USDC liquidity
+        let user_id = env.user_id.clone();
+        let asset_id = 1; // USDC
+        let deposit_amount = 1000 * 10u64.pow(6);
+
+        env.tx_builder.deposit(&user_id, asset_id, deposit_amount).await?;
+        println!("✅ Pool has 1000 USDC liquidity");
+
+        // Borrower has sufficient collateral for 2000 USDC
+        let collateral_amount = 2 * 10u64.pow(8); // 2 WETH ($5000 worth)
+        let collateral_asset_id = 3; // WETH
+
+        env.tx_builder.supply_collateral(&user_id, collateral_asset_id, collateral_amount).await?;
+        println!("✅ Borrower has sufficient collateral for 2000 USDC");
+
+        // Attempt to borrow 1500 USDC (exceeds pool liquidity of 1000)
+        let borrow_amount = 1500 * 10u64.pow(6);
+
+        let result = env.tx_builder.borrow(&user_id, asset_id, borrow_amount).await;
+        assert!(result.is_err(), "borrow exceeding liquidity should fail");
+        println!("✅ Borrow exceeding liquidity failed as expected");
+
+        // Nothing was borrowed from the pool or recorded as debt
+        let reserve_data = env.tx_builder.get_reserve_data(asset_id).await?;
+        assert_eq!(reserve_data.total_borrowed, 0);
+
+        let user_info = env.tx_builder.get_account_info(&user_id).await?;
+        assert_eq!(user_info.scaled_debt(1), 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_withdraw_from_fully_borrowed_reserve_fails() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        // Test scenario:
+        // 1. The pool's whole USDC liquidity is borrowed
+        // 2. A year of interest grows the borrows past the liquidity, the reserve factor share
+        //    going to the treasury
+        // 3. Withdrawing fails for lack of liquidity instead of underflowing
+
+        let user_id = env.user_id.clone();
+        let asset_id = 1; // USDC
+        let amount = 1000 * 10u64.pow(6);
+
+        env.tx_builder.deposit(&user_id, asset_id, amount).await?;
+        env.tx_builder.supply_collateral(&user_id, 3, 10u64.pow(8)).await?; // 1 WETH
+        env.tx_builder.borrow(&user_id, asset_id, amount).await?;
+        env.chain.advance_time(31_536_000).await?;
+
+        let err = env.tx_builder.withdraw(&user_id, asset_id, 1).await.unwrap_err();
+        assert!(
+            fails_with(&err, &errors::ERR_LENDING_POOL_INSUFFICIENT_LIQUIDITY),
+            "withdrawal should lack liquidity, got: {:?}", err
+        );
+        println!("✅ Withdrawal from a fully borrowed reserve rejected");
+
+        Ok(())
+    }
+
+    // =============================================================================================
+    // Multi-User Tests
+    // =============================================================================================
+
+    #[tokio::test]
+    async fn test_multiple_users_deposit_withdraw() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        // Test scenario:
+        // 1. User A deposits 1000 USDC
         // 2. User B deposits 500 USDC
         // 3. User C borrows 800 USDC (creates interest)
         // 4. User A withdraws - should receive interest
@@ -470,14 +1534,11 @@ mod tests {
         let asset_id = 1; // USDC
 
         // Create multiple user accounts
-        let user_a_result = env.account_manager.create_user_account("private").await?;
-        let user_a_id = miden_client::AccountId(extract_account_id(&user_a_result));
+        let user_a_id = create_funded_user(&mut env).await?;
 
-        let user_b_result = env.account_manager.create_user_account("private").await?;
-        let user_b_id = miden_client::AccountId(extract_account_id(&user_b_result));
+        let user_b_id = create_funded_user(&mut env).await?;
 
-        let user_c_result = env.account_manager.create_user_account("private").await?;
-        let user_c_id = miden_client::AccountId(extract_account_id(&user_c_result));
+        let user_c_id = create_funded_user(&mut env).await?;
 
         println!("✅ Created 3 user accounts");
 
@@ -490,25 +1551,700 @@ mod tests {
         println!("✅ User B deposited 500 USDC");
 
         // User C supplies collateral and borrows 800 USDC (creates interest)
-        env.tx_builder.supply_collateral(&user_c_id, 3, 10u64.pow(18)).await?; // 1 WETH collateral
+        env.tx_builder.supply_collateral(&user_c_id, 3, 10u64.pow(8)).await?; // 1 WETH collateral
         env.tx_builder.borrow(&user_c_id, asset_id, 800 * 10u64.pow(6)).await?;
         println!("✅ User C borrowed 800 USDC (creates interest for depositors)");
 
         // Check reserve data
         let reserve_data = env.tx_builder.get_reserve_data(asset_id).await?;
-        println!("📊 Reserve - Liquidity: {}, Borrowed: {}, Rate: {} bps",
+        println!("📊 Reserve - Liquidity: {}, Borrowed: {}, Rate: {}",
                  reserve_data.total_liquidity,
                  reserve_data.total_borrowed,
-                 reserve_data.borrow_rate);
+                 utils::wad_to_percentage(reserve_data.borrow_rate));
 
         // User A withdraws (should receive interest)
         env.tx_builder.withdraw(&user_a_id, asset_id, 500 * 10u64.pow(6)).await?;
         println!("✅ User A withdrew (with interest)");
 
-        // User B withdraws (should receive interest)
-        env.tx_builder.withdraw(&user_b_id, asset_id, 250 * 10u64.pow(6)).await?;
+        // User B withdraws most of the remaining available liquidity (should receive interest)
+        env.tx_builder.withdraw(&user_b_id, asset_id, 150 * 10u64.pow(6)).await?;
         println!("✅ User B withdrew (with interest)");
 
+        // Both totals have grown with interest since the borrow
+        let reserve_data = env.tx_builder.get_reserve_data(asset_id).await?;
+        assert!(reserve_data.total_liquidity > 850 * 10u64.pow(6));
+        assert!(reserve_data.total_borrowed > 800 * 10u64.pow(6));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_position_notes_act_on_sender() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        let user_id = env.user_id.clone();
+        env.tx_builder.deposit(&user_id, 1, 1000 * 10u64.pow(6)).await?;
+
+        // Another account requesting a withdrawal only withdraws from its own position
+        let other_id = env.account_manager.create_user_account("private", &env.pool_id).await?;
+        let note = position_note(&env, &other_id, PositionOperation::Withdraw, 1, asset(1, 1000 * 10u64.pow(6)))?;
+        let err = submit_request(&mut env, &other_id, note).await.unwrap_err();
+        assert!(
+            fails_with(&err, &errors::ERR_LENDING_POOL_INSUFFICIENT_SUPPLY),
+            "withdrawal from another position should fail, got: {:?}", err
+        );
+        println!("✅ Position note cannot withdraw another account's supply");
+
+        assert_eq!(env.tx_builder.get_supply_balance(&user_id, 1).await?, 1000 * 10u64.pow(6));
+        assert_eq!(env.tx_builder.get_supply_balance(&other_id, 1).await?, 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pool_positions_only_change_through_notes() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        // Position procedures act on the sender of the consumed note, so calling them from a
+        // transaction script fails
+        let script = miden_client::TransactionScriptBuilder::new()
+            .push(1)
+            .push(1000 * 10u64.pow(6))
+            .call_procedure("lending_pool", "deposit")
+            .build();
+
+        let err = env.chain.execute_transaction(&script, &env.pool_id).await.unwrap_err();
+        assert!(
+            fails_with(&err, &miden_lib::errors::tx_kernel_errors::ERR_NOTE_ATTEMPT_TO_ACCESS_NOTE_SENDER_FROM_INCORRECT_CONTEXT),
+            "deposit outside of a note should fail, got: {:?}", err
+        );
+        println!("✅ Lending pool rejected a position change outside of a note");
+
+        let reserve_data = env.tx_builder.get_reserve_data(1).await?;
+        assert_eq!(reserve_data.total_liquidity, 0);
+
+        Ok(())
+    }
+
+    // =============================================================================================
+    // Reserve Listing Tests
+    // =============================================================================================
+
+    #[tokio::test]
+    async fn test_add_reserve() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        // Test scenario:
+        // 1. Admin lists WBTC, which already has an oracle price
+        // 2. Users can deposit WBTC into the new reserve
+        // 3. Listing WBTC a second time fails
+
+        let wbtc_asset_id = 4; // WBTC
+        let wbtc = components::lending_pool::ReserveConfig::new(
+            wbtc_asset_id,
+            faucet_id(wbtc_asset_id),
+            8,
+            components::lending_pool::RateStrategy::volatile(),
+            components::lending_pool::RiskParameters {
+                ltv: 7000,
+                liquidation_threshold: 7500,
+                liquidation_bonus: 11000,
+            },
+        );
+        env.tx_builder.add_reserve(wbtc).await?;
+        println!("✅ Listed WBTC");
+
+        let reserves = env.tx_builder.get_reserves().await?;
+        assert_eq!(reserves.len(), 4);
+        assert_eq!(reserves[3], wbtc);
+
+        let deposit_amount = 2 * 10u64.pow(8); // 2 WBTC
+        let user_id = env.user_id.clone();
+        fund(&mut env, &user_id, &[(wbtc_asset_id, deposit_amount)]).await?;
+        env.tx_builder.deposit(&user_id, wbtc_asset_id, deposit_amount).await?;
+
+        let reserve_data = env.tx_builder.get_reserve_data(wbtc_asset_id).await?;
+        assert_eq!(reserve_data.total_liquidity, deposit_amount);
+        println!("   WBTC liquidity: {}", reserve_data.total_liquidity);
+
+        let result = env.tx_builder.add_reserve(wbtc).await;
+        assert!(result.is_err(), "Listing a reserve twice should fail");
+        println!("✅ Duplicate listing rejected");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_add_price_feed() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        // Test scenario:
+        // 1. The oracle has no feed for an asset it was not deployed with
+        // 2. Admin adds a price feed for the asset, submitting its initial price
+        // 3. The initial price ages like any submission, until an updater's replaces it
+        // 4. Updaters can submit prices for it, and its feed cannot be added twice
+
+        let link_asset_id = 5; // LINK
+        let link_price = 15 * 10u64.pow(8);
+
+        assert!(!env.tx_builder.has_price_feed(link_asset_id).await?);
+        assert!(env.tx_builder.get_price(link_asset_id).await.is_err());
+
+        env.tx_builder.add_price_feed(link_asset_id, link_price).await?;
+        assert!(env.tx_builder.has_price_feed(link_asset_id).await?);
+        assert_eq!(env.tx_builder.get_price(link_asset_id).await?, link_price);
+
+        let parameters = env.tx_builder.get_price_parameters(link_asset_id).await?;
+        assert_eq!(parameters.max_age, components::price_oracle::PriceOracleAccount::DEFAULT_MAX_PRICE_AGE);
+        assert_eq!(parameters.quorum, components::price_oracle::PriceOracleAccount::DEFAULT_QUORUM);
+        assert_eq!(parameters.twap_window, components::price_oracle::PriceOracleAccount::DEFAULT_TWAP_WINDOW);
+        assert!(!parameters.is_paused);
+        println!("✅ Added LINK price feed at {}", link_price);
+
+        // The initial price is the admin's submission, which reaches the default quorum alone
+        let oracle = env.chain.get_account(&env.oracle_id).await?;
+        let submissions = env.tx_builder.get_price_submissions(link_asset_id).await?;
+        assert_eq!(submissions.len(), 1);
+        assert!(submissions[0].is_initial && submissions[0].is_fresh);
+        assert_eq!(submissions[0].price, link_price);
+        assert_eq!(submissions[0].updater_key, oracle.public_key.to_hex());
+
+        env.chain.advance_time(3601).await?;
+        let err = env.tx_builder.get_price(link_asset_id).await.unwrap_err();
+        assert!(fails_with(&err, &errors::ERR_PRICE_ORACLE_STALE_PRICE));
+        assert!(!env.tx_builder.get_price_submissions(link_asset_id).await?[0].is_fresh);
+        println!("✅ Initial price expires like a submission");
+
+        // Updaters can submit prices for the new feed, replacing the initial submission
+        let user_id = env.user_id.clone();
+        env.tx_builder.add_price_updater(&user_id).await?;
+        env.tx_builder.update_price(&user_id, link_asset_id, 16 * 10u64.pow(8)).await?;
+        assert_eq!(env.tx_builder.get_price(link_asset_id).await?, 16 * 10u64.pow(8));
+
+        let submissions = env.tx_builder.get_price_submissions(link_asset_id).await?;
+        assert_eq!(submissions.len(), 1);
+        assert!(!submissions[0].is_initial);
+        assert_eq!(submissions[0].price, 16 * 10u64.pow(8));
+
+        // A feed can only be added once
+        assert!(env.tx_builder.add_price_feed(link_asset_id, link_price).await.is_err());
+        let script = miden_client::TransactionScriptBuilder::new()
+            .push(link_price)
+            .push(link_asset_id)
+            .call_procedure("price_oracle", "add_price_feed")
+            .build();
+        let err = env.chain.execute_transaction(&script, &env.oracle_id).await.unwrap_err();
+        assert!(
+            fails_with(&err, &errors::ERR_PRICE_ORACLE_FEED_ALREADY_LISTED),
+            "price_oracle::add_price_feed should fail for a listed asset, got: {:?}", err
+        );
+        println!("✅ Duplicate price feed rejected");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_add_reserve_requires_price_feed() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        // Test scenario:
+        // 1. Listing an asset without an oracle price feed fails
+        // 2. Once the oracle has a price feed for the asset, it can be listed
+
+        let link_asset_id = 5; // LINK
+        let link_price = 15 * 10u64.pow(8);
+        let link = components::lending_pool::ReserveConfig::new(
+            link_asset_id,
+            faucet_id(4),
+            18,
+            components::lending_pool::RateStrategy::volatile(),
+            components::lending_pool::RiskParameters::volatile(),
+        );
+
+        // The transaction builder rejects the asset before building a transaction
+        assert!(!env.tx_builder.has_price_feed(link_asset_id).await?);
+        assert!(env.tx_builder.add_reserve(link).await.is_err());
+
+        // The pool rejects it too
+        let strategy = link.strategy;
+        let script = miden_client::TransactionScriptBuilder::new()
+            .push(link.risk.liquidation_bonus)
+            .push(link.risk.liquidation_threshold)
+            .push(link.risk.ltv)
+            .push(strategy.reserve_factor)
+            .push(strategy.optimal_utilization)
+            .push(strategy.slope2)
+            .push(strategy.slope1)
+            .push(strategy.base_rate)
+            .push(link.faucet_id.suffix().as_int())
+            .push(link.faucet_id.prefix().as_u64())
+            .push(link_asset_id)
+            .push(link.decimals as u64)
+            .call_procedure("lending_pool", "add_reserve")
+            .build();
+        let err = env.chain
+            .execute_transaction_with_foreign_accounts(
+                &script,
+                Default::default(),
+                &env.pool_id,
+                std::slice::from_ref(&env.oracle_id),
+            )
+            .await
+            .unwrap_err();
+        assert!(
+            fails_with(&err, &errors::ERR_LENDING_POOL_NO_PRICE_FEED),
+            "lending_pool::add_reserve should fail without a price feed, got: {:?}", err
+        );
+        println!("✅ Asset without a price feed rejected");
+
+        env.tx_builder.add_price_feed(link_asset_id, link_price).await?;
+        env.tx_builder.add_reserve(link).await?;
+        assert!(env.tx_builder.is_reserve_listed(link_asset_id).await?);
+        println!("✅ Listed LINK");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_add_reserve_rejects_listed_faucet() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        // Test scenario:
+        // 1. Listing WBTC against the faucet already backing USDC fails
+        // 2. WBTC can be listed against its own faucet
+
+        let wbtc = components::lending_pool::ReserveConfig::new(
+            4, // WBTC
+            faucet_id(1),
+            8,
+            components::lending_pool::RateStrategy::volatile(),
+            components::lending_pool::RiskParameters::volatile(),
+        );
+
+        // The transaction builder rejects the faucet before building a transaction
+        let err = env.tx_builder.add_reserve(wbtc).await.unwrap_err();
+        assert!(err.to_string().contains("already backs reserve 1"), "got: {:?}", err);
+
+        // The pool rejects it too
+        let strategy = wbtc.strategy;
+        let script = miden_client::TransactionScriptBuilder::new()
+            .push(wbtc.risk.liquidation_bonus)
+            .push(wbtc.risk.liquidation_threshold)
+            .push(wbtc.risk.ltv)
+            .push(strategy.reserve_factor)
+            .push(strategy.optimal_utilization)
+            .push(strategy.slope2)
+            .push(strategy.slope1)
+            .push(strategy.base_rate)
+            .push(wbtc.faucet_id.suffix().as_int())
+            .push(wbtc.faucet_id.prefix().as_u64())
+            .push(wbtc.asset_id)
+            .push(wbtc.decimals as u64)
+            .call_procedure("lending_pool", "add_reserve")
+            .build();
+        let err = env.chain
+            .execute_transaction_with_foreign_accounts(
+                &script,
+                Default::default(),
+                &env.pool_id,
+                std::slice::from_ref(&env.oracle_id),
+            )
+            .await
+            .unwrap_err();
+        assert!(
+            fails_with(&err, &errors::ERR_LENDING_POOL_FAUCET_ALREADY_LISTED),
+            "lending_pool::add_reserve should fail for a listed faucet, got: {:?}", err
+        );
+        assert!(!env.tx_builder.is_reserve_listed(4).await?);
+        println!("✅ Faucet backing a listed reserve rejected");
+
+        let wbtc = components::lending_pool::ReserveConfig { faucet_id: faucet_id(4), ..wbtc };
+        env.tx_builder.add_reserve(wbtc).await?;
+        assert!(env.tx_builder.is_reserve_listed(4).await?);
+        println!("✅ Listed WBTC against its own faucet");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_add_reserve_rejects_too_many_decimals() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        let wbtc = components::lending_pool::ReserveConfig::new(
+            4, // WBTC
+            faucet_id(4),
+            20,
+            components::lending_pool::RateStrategy::volatile(),
+            components::lending_pool::RiskParameters::volatile(),
+        );
+
+        // The transaction builder rejects them before building a transaction
+        let err = env.tx_builder.add_reserve(wbtc).await.unwrap_err();
+        assert!(err.to_string().contains("at most 18 decimals"), "got: {:?}", err);
+
+        // The pool rejects them too, as 10^20 wraps in the field
+        let strategy = wbtc.strategy;
+        let script = miden_client::TransactionScriptBuilder::new()
+            .push(wbtc.risk.liquidation_bonus)
+            .push(wbtc.risk.liquidation_threshold)
+            .push(wbtc.risk.ltv)
+            .push(strategy.reserve_factor)
+            .push(strategy.optimal_utilization)
+            .push(strategy.slope2)
+            .push(strategy.slope1)
+            .push(strategy.base_rate)
+            .push(wbtc.faucet_id.suffix().as_int())
+            .push(wbtc.faucet_id.prefix().as_u64())
+            .push(wbtc.asset_id)
+            .push(wbtc.decimals as u64)
+            .call_procedure("lending_pool", "add_reserve")
+            .build();
+        let err = env.chain
+            .execute_transaction_with_foreign_accounts(
+                &script,
+                Default::default(),
+                &env.pool_id,
+                std::slice::from_ref(&env.oracle_id),
+            )
+            .await
+            .unwrap_err();
+        assert!(
+            fails_with(&err, &errors::ERR_LENDING_POOL_INVALID_DECIMALS),
+            "lending_pool::add_reserve should fail for 20 decimals, got: {:?}", err
+        );
+        assert!(!env.tx_builder.is_reserve_listed(4).await?);
+        println!("✅ Reserve with more than 18 decimals rejected");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_add_reserve_rejects_invalid_risk_parameters() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        let strategy = components::lending_pool::RateStrategy::volatile();
+        let invalid = [
+            (8500, 8000, 10500), // LTV above the liquidation threshold
+            (7000, 7500, 9500),  // Bonus below 100%
+            (9000, 9500, 10800), // Seizes more collateral than backs the debt
+            (7000, 7500, 20500), // Bonus above 200%
+            (7000, 7500, 2_459_565_875_921_945), // threshold * bonus wraps past the field modulus
+        ];
+        for (ltv, liquidation_threshold, liquidation_bonus) in invalid {
+            let risk = components::lending_pool::RiskParameters { ltv, liquidation_threshold, liquidation_bonus };
+
+            // The transaction builder rejects them before building a transaction
+            let reserve = components::lending_pool::ReserveConfig::new(4, faucet_id(4), 8, strategy, risk);
+            assert!(env.tx_builder.add_reserve(reserve).await.is_err());
+
+            // The pool rejects them too
+            let script = miden_client::TransactionScriptBuilder::new()
+                .push(liquidation_bonus)
+                .push(liquidation_threshold)
+                .push(ltv)
+                .push(strategy.reserve_factor)
+                .push(strategy.optimal_utilization)
+                .push(strategy.slope2)
+                .push(strategy.slope1)
+                .push(strategy.base_rate)
+                .push(faucet_id(4).suffix().as_int())
+                .push(faucet_id(4).prefix().as_u64())
+                .push(4) // WBTC
+                .push(8)
+                .call_procedure("lending_pool", "add_reserve")
+                .build();
+
+            let err = env.chain
+                .execute_transaction_with_foreign_accounts(
+                    &script,
+                    Default::default(),
+                    &env.pool_id,
+                    std::slice::from_ref(&env.oracle_id),
+                )
+                .await
+                .unwrap_err();
+            assert!(
+                fails_with(&err, &errors::ERR_LENDING_POOL_INVALID_RISK_PARAMETERS),
+                "{:?} should be rejected, got: {:?}", risk, err
+            );
+        }
+        println!("✅ Invalid risk parameters rejected");
+
+        assert!(!env.tx_builder.is_reserve_listed(4).await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_unknown_asset_rejected() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        let user_id = env.user_id.clone();
+        let unknown_asset_id = 9;
+
+        // The transaction builder rejects unknown assets before building a transaction
+        assert!(env.tx_builder.deposit(&user_id, unknown_asset_id, 1000).await.is_err());
+        assert!(env.tx_builder.supply_collateral(&user_id, unknown_asset_id, 1000).await.is_err());
+        assert!(env.tx_builder.update_price(&user_id, unknown_asset_id, 10u64.pow(8)).await.is_err());
+        println!("✅ Unknown asset rejected by the transaction builder");
+
+        // The contracts reject them too, instead of updating another asset's storage. Price
+        // updates are signed by an authorized updater, so they get past the updater check.
+        env.tx_builder.add_price_updater(&user_id).await?;
+        let updater_key = env.chain.get_account(&user_id).await?.public_key;
+
+        let script = miden_client::TransactionScriptBuilder::new()
+            .push(unknown_asset_id)
+            .push(1000)
+            .push_word(updater_key)
+            .call_procedure("price_oracle", "update_asset_price")
+            .build();
+        let err = env.chain.execute_transaction(&script, &env.oracle_id).await.unwrap_err();
+        assert!(
+            format!("{:?}", err).contains(errors::ERR_PRICE_ORACLE_UNKNOWN_ASSET.message()),
+            "price_oracle::update_asset_price should fail with unknown asset, got: {:?}", err
+        );
+
+        // The notes carry USDC, which the pool does not receive for the unknown asset
+        for operation in [PositionOperation::Deposit, PositionOperation::SupplyCollateral] {
+            let note = position_note(&env, &user_id, operation, unknown_asset_id, asset(1, 1000))?;
+            let err = submit_request(&mut env, &user_id, note).await.unwrap_err();
+            assert!(
+                format!("{:?}", err).contains(errors::ERR_LENDING_POOL_UNKNOWN_ASSET.message()),
+                "lending_pool::{} should fail with unknown asset, got: {:?}", operation.procedure(), err
+            );
+        }
+        println!("✅ Unknown asset rejected by the contracts");
+
+        Ok(())
+    }
+
+    // =============================================================================================
+    // Arithmetic Safety Tests
+    // =============================================================================================
+
+    #[tokio::test]
+    async fn test_large_weth_amounts() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        let user_id = env.user_id.clone();
+        let asset_id = 3; // WETH
+        let deposit_amount = 400_000 * 10u64.pow(8); // 400,000 WETH
+
+        env.tx_builder.deposit(&user_id, asset_id, deposit_amount).await?;
+        env.tx_builder.supply_collateral(&user_id, asset_id, deposit_amount).await?;
+        println!("✅ Deposited and supplied 400,000 WETH");
+
+        let withdraw_amount = 300_000 * 10u64.pow(8); // 300,000 WETH
+        env.tx_builder.withdraw(&user_id, asset_id, withdraw_amount).await?;
+        println!("✅ Withdrew 300,000 WETH");
+
+        let reserve_data = env.tx_builder.get_reserve_data(asset_id).await?;
+        assert_eq!(reserve_data.total_liquidity, deposit_amount - withdraw_amount);
+
+        let user_info = env.tx_builder.get_account_info(&user_id).await?;
+        assert_eq!(user_info.collateral(3), deposit_amount);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_large_amounts_with_18_decimals() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        // An 18-decimal token priced by the WBTC feed, whose unit is 10^18
+        let asset_id = 4;
+        let reserve = components::lending_pool::ReserveConfig::new(
+            asset_id,
+            faucet_id(asset_id),
+            18,
+            components::lending_pool::RateStrategy::volatile(),
+            components::lending_pool::RiskParameters::volatile(),
+        );
+        env.tx_builder.add_reserve(reserve).await?;
+
+        let user_id = env.user_id.clone();
+        let deposit_amount = 4 * 10u64.pow(18); // 4 tokens
+        let collateral_amount = 5 * 10u64.pow(18); // 5 tokens
+        fund(&mut env, &user_id, &[(asset_id, deposit_amount + collateral_amount)]).await?;
+
+        env.tx_builder.deposit(&user_id, asset_id, deposit_amount).await?;
+        env.tx_builder.supply_collateral(&user_id, asset_id, collateral_amount).await?;
+        println!("✅ Deposited 4 and supplied 5 tokens of 18 decimals");
+
+        let withdraw_amount = 3 * 10u64.pow(18); // 3 tokens
+        env.tx_builder.withdraw(&user_id, asset_id, withdraw_amount).await?;
+        println!("✅ Withdrew 3 tokens");
+
+        let reserve_data = env.tx_builder.get_reserve_data(asset_id).await?;
+        assert_eq!(reserve_data.total_liquidity, deposit_amount - withdraw_amount);
+
+        // The collateral is valued in whole tokens: 5 tokens at $45,000 back a $100,000 WETH loan
+        let borrow_amount = 40 * 10u64.pow(8); // 40 WETH
+        env.tx_builder.deposit(&user_id, 3, borrow_amount).await?;
+        env.tx_builder.borrow(&user_id, 3, borrow_amount).await?;
+        println!("✅ Borrowed 40 WETH against the collateral");
+
+        let user_info = env.tx_builder.get_account_info(&user_id).await?;
+        assert_eq!(user_info.collateral(asset_id), collateral_amount);
+        assert!(env.tx_builder.get_debt(&user_id, 3).await? >= borrow_amount);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_position_value_overflow_fails() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        let user_id = env.user_id.clone();
+        let asset_id = 3; // WETH
+        let amount = 10 * 10u64.pow(8); // 10 WETH
+
+        env.tx_builder.deposit(&user_id, asset_id, amount).await?;
+        env.tx_builder.supply_collateral(&user_id, asset_id, amount).await?;
+        env.tx_builder.borrow(&user_id, asset_id, 10u64.pow(8)).await?;
+        let scaled_debt = env.tx_builder.get_account_info(&user_id).await?.scaled_debt(asset_id);
+
+        // Borrow amounts are note inputs, not assets, so they are not capped by the vault. At $2500,
+        // 100 million WETH is worth more than a field element holds.
+        let note = position_note(
+            &env,
+            &user_id,
+            PositionOperation::Borrow,
+            asset_id,
+            asset(asset_id, 10u64.pow(16)),
+        )?;
+        let err = submit_request(&mut env, &user_id, note).await.unwrap_err();
+        assert!(
+            fails_with(&err, &errors::ERR_FIXED_POINT_OVERFLOW),
+            "valuing the borrow should overflow, got: {:?}", err
+        );
+        println!("✅ Borrow value overflow rejected");
+
+        // The largest amount whose value fits, which the debt already held takes past it
+        let note = position_note(
+            &env,
+            &user_id,
+            PositionOperation::Borrow,
+            asset_id,
+            asset(asset_id, 7_378_697_627_765_833),
+        )?;
+        let err = submit_request(&mut env, &user_id, note).await.unwrap_err();
+        assert!(
+            fails_with(&err, &errors::ERR_SAFE_MATH_OVERFLOW),
+            "adding the borrow to the debt value should overflow, got: {:?}", err
+        );
+        println!("✅ Debt value overflow rejected");
+
+        let user_info = env.tx_builder.get_account_info(&user_id).await?;
+        assert_eq!(user_info.scaled_debt(asset_id), scaled_debt);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_balance_overflow_fails() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        let user_id = env.account_manager.create_user_account("private", &env.pool_id).await?;
+        let asset_id = 3; // WETH
+        // 50 billion WETH. Amounts are capped like fungible assets at 2^63 - 1 units, about 92
+        // billion tokens of an 8-decimal asset (see the README notes). Pool liquidity and
+        // collateral share the pool's vault, so twice this overflows it.
+        let amount = 5 * 10u64.pow(18);
+
+        // Pool liquidity
+        fund(&mut env, &user_id, &[(asset_id, amount)]).await?;
+        env.tx_builder.deposit(&user_id, asset_id, amount).await?;
+        fund(&mut env, &user_id, &[(asset_id, amount)]).await?;
+        let err = env.tx_builder.deposit(&user_id, asset_id, amount).await.unwrap_err();
+        assert!(
+            fails_with(&err, &miden_lib::errors::tx_kernel_errors::ERR_VAULT_FUNGIBLE_MAX_AMOUNT_EXCEEDED),
+            "second deposit should overflow, got: {:?}", err
+        );
+        println!("✅ Pool liquidity overflow rejected");
+
+        let reserve_data = env.tx_builder.get_reserve_data(asset_id).await?;
+        assert_eq!(reserve_data.total_liquidity, amount);
+
+        // User collateral, from the amount reclaimed from the rejected deposit
+        assert_eq!(env.chain.balance(&user_id, faucet_id(asset_id)).await?, amount);
+        let err = env.tx_builder.supply_collateral(&user_id, asset_id, amount).await.unwrap_err();
+        assert!(
+            fails_with(&err, &miden_lib::errors::tx_kernel_errors::ERR_VAULT_FUNGIBLE_MAX_AMOUNT_EXCEEDED),
+            "collateral supply should overflow, got: {:?}", err
+        );
+        println!("✅ Collateral overflow rejected");
+
+        let user_info = env.tx_builder.get_account_info(&user_id).await?;
+        assert_eq!(user_info.collateral(3), 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pool_only_receives_reserve_assets() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        // A deposit note carrying DAI cannot be credited as USDC
+        let user_id = env.user_id.clone();
+        let note = position_note(&env, &user_id, PositionOperation::Deposit, 1, asset(2, 1000))?;
+        let err = submit_request(&mut env, &user_id, note).await.unwrap_err();
+        assert!(
+            fails_with(&err, &errors::ERR_LENDING_POOL_WRONG_NOTE_ASSET),
+            "deposit of another asset should fail, got: {:?}", err
+        );
+        println!("✅ Note carrying another reserve's asset rejected");
+
+        assert_eq!(env.tx_builder.get_supply_balance(&user_id, 1).await?, 0);
+        assert_eq!(env.chain.balance(&env.pool_id, faucet_id(1)).await?, 0);
+
+        // Reserves only hold assets issued by fungible faucets
+        let reserve = components::lending_pool::ReserveConfig::new(
+            4,
+            (&user_id).try_into()?,
+            8,
+            components::lending_pool::RateStrategy::volatile(),
+            components::lending_pool::RiskParameters::volatile(),
+        );
+        let err = env.tx_builder.add_reserve(reserve).await.unwrap_err();
+        assert!(
+            fails_with(&err, &errors::ERR_LENDING_POOL_INVALID_FAUCET),
+            "reserve of a regular account should be rejected, got: {:?}", err
+        );
+        assert!(!env.tx_builder.is_reserve_listed(4).await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_collateral_underflow_fails() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        let user_id = env.user_id.clone();
+        let asset_id = 3; // WETH
+        let collateral_amount = 10u64.pow(8); // 1 WETH
+
+        env.tx_builder.supply_collateral(&user_id, asset_id, collateral_amount).await?;
+
+        // Withdrawing more than supplied is rejected before it can wrap around to a huge balance
+        let note = position_note(
+            &env,
+            &user_id,
+            PositionOperation::WithdrawCollateral,
+            asset_id,
+            asset(asset_id, 2 * collateral_amount),
+        )?;
+        let err = submit_request(&mut env, &user_id, note).await.unwrap_err();
+        assert!(
+            fails_with(&err, &errors::ERR_LENDING_POOL_INSUFFICIENT_COLLATERAL),
+            "over-withdrawal should exceed the collateral, got: {:?}", err
+        );
+        println!("✅ Collateral over-withdrawal rejected");
+
+        let user_info = env.tx_builder.get_account_info(&user_id).await?;
+        assert_eq!(user_info.collateral(3), collateral_amount);
+
         Ok(())
     }
 
@@ -523,20 +2259,347 @@ mod tests {
         let asset_id = 3; // WETH
         let new_price = 3000 * 10u64.pow(8); // $3000 with 8 decimals
 
-        // Create admin account to update price
-        let admin_result = env.account_manager.create_user_account("private").await?;
-        let admin_id = miden_client::AccountId(extract_account_id(&admin_result));
+        // Create an updater account, authorized by the oracle admin
+        let updater_id = env.account_manager.create_user_account("private", &env.pool_id).await?;
 
         // Update price
-        env.tx_builder.update_price(&admin_id, asset_id, new_price).await?;
+        env.tx_builder.add_price_updater(&updater_id).await?;
+        env.tx_builder.update_price(&updater_id, asset_id, new_price).await?;
         println!("✅ Updated WETH price to $3000");
 
         // Verify price updated
         let price = env.tx_builder.get_price(asset_id).await?;
+        assert_eq!(price, new_price);
         println!("📊 Current WETH price: ${}", price / 10u64.pow(8));
 
-        // In mock implementation, price might not actually change
-        println!("⚠️  Mock implementation: price verification is simulated");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_price_update_requires_authorized_updater() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        let asset_id = 3; // WETH
+        let initial_price = env.tx_builder.get_price(asset_id).await?;
+        let new_price = 3000 * 10u64.pow(8);
+
+        let updater_id = env.account_manager.create_user_account("private", &env.pool_id).await?;
+
+        // Accounts are not authorized until the oracle admin adds them
+        let err = env.tx_builder.update_price(&updater_id, asset_id, new_price).await.unwrap_err();
+        assert_eq!(err.downcast_ref(), Some(&errors::LendingError::UnauthorizedPriceUpdater));
+
+        env.tx_builder.add_price_updater(&updater_id).await?;
+        env.tx_builder.update_price(&updater_id, asset_id, new_price).await?;
+        assert_eq!(env.tx_builder.get_price(asset_id).await?, new_price);
+
+        // Removed updaters can no longer publish prices
+        env.tx_builder.remove_price_updater(&updater_id).await?;
+        let err = env.tx_builder.update_price(&updater_id, asset_id, initial_price).await.unwrap_err();
+        assert_eq!(err.downcast_ref(), Some(&errors::LendingError::UnauthorizedPriceUpdater));
+        assert_eq!(env.tx_builder.get_price(asset_id).await?, new_price);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_zero_price_rejected() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        let asset_id = 3; // WETH
+        let initial_price = env.tx_builder.get_price(asset_id).await?;
+
+        let updater_id = env.account_manager.create_user_account("private", &env.pool_id).await?;
+        env.tx_builder.add_price_updater(&updater_id).await?;
+
+        let err = env.tx_builder.update_price(&updater_id, asset_id, 0).await.unwrap_err();
+        assert!(fails_with(&err, &errors::ERR_PRICE_ORACLE_ZERO_PRICE));
+
+        // Signed prices go through the same check
+        let timestamp = env.chain.latest_block_timestamp().await as u64;
+        let price_note = env.tx_builder.sign_price(&updater_id, asset_id, 0, timestamp).await?;
+        let err = env.tx_builder.relay_price_notes(&[price_note]).await.unwrap_err();
+        assert!(fails_with(&err, &errors::ERR_PRICE_ORACLE_ZERO_PRICE));
+
+        assert_eq!(env.tx_builder.get_price(asset_id).await?, initial_price);
+        println!("✅ Zero prices rejected");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_price_is_median_of_submissions() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        let weth_asset_id = 3;
+        let initial_price = env.tx_builder.get_price(weth_asset_id).await?;
+
+        let mut updater_ids = Vec::new();
+        for _ in 0..3 {
+            let updater_id = env.account_manager.create_user_account("private", &env.pool_id).await?;
+            env.tx_builder.add_price_updater(&updater_id).await?;
+            updater_ids.push(updater_id);
+        }
+        env.tx_builder.set_price_quorum(weth_asset_id, 3).await?;
+        assert!(env.tx_builder.set_price_quorum(weth_asset_id, 0).await.is_err());
+
+        // Submissions below the quorum leave the reported price unchanged
+        env.tx_builder.update_price(&updater_ids[0], weth_asset_id, 2400 * 10u64.pow(8)).await?;
+        env.tx_builder.update_price(&updater_ids[1], weth_asset_id, 2600 * 10u64.pow(8)).await?;
+        assert_eq!(env.tx_builder.get_price(weth_asset_id).await?, initial_price);
+
+        // A single compromised updater cannot move the median
+        env.tx_builder.update_price(&updater_ids[2], weth_asset_id, 10u64.pow(8)).await?;
+        assert_eq!(env.tx_builder.get_price(weth_asset_id).await?, 2400 * 10u64.pow(8));
+        println!("✅ Outlier submission ignored by the median");
+
+        let submissions = env.tx_builder.get_price_submissions(weth_asset_id).await?;
+        assert_eq!(submissions.len(), 3);
+        assert!(submissions.iter().all(|submission| submission.is_fresh));
+        for (submission, updater_id) in submissions.iter().zip(&updater_ids) {
+            let updater = env.chain.get_account(updater_id).await?;
+            assert_eq!(submission.updater_key, updater.public_key.to_hex());
+        }
+        assert_eq!(
+            submissions.iter().map(|submission| submission.price).collect::<Vec<_>>(),
+            [2400 * 10u64.pow(8), 2600 * 10u64.pow(8), 10u64.pow(8)]
+        );
+
+        // Removing the compromised updater discards its submission, and the last updater takes
+        // its place. The median of an even number of submissions is the mean of the middle two.
+        env.tx_builder.remove_price_updater(&updater_ids[2]).await?;
+        env.tx_builder.set_price_quorum(weth_asset_id, 2).await?;
+        env.tx_builder.remove_price_updater(&updater_ids[0]).await?;
+        assert_eq!(env.tx_builder.get_price_submissions(weth_asset_id).await?.len(), 1);
+
+        env.tx_builder.add_price_updater(&updater_ids[0]).await?;
+        env.tx_builder.update_price(&updater_ids[0], weth_asset_id, 2500 * 10u64.pow(8)).await?;
+        assert_eq!(env.tx_builder.get_price(weth_asset_id).await?, 2550 * 10u64.pow(8));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_price_quorum_counts_fresh_submissions() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        let weth_asset_id = 3;
+        let first_id = env.account_manager.create_user_account("private", &env.pool_id).await?;
+        let second_id = env.account_manager.create_user_account("private", &env.pool_id).await?;
+        env.tx_builder.add_price_updater(&first_id).await?;
+        env.tx_builder.add_price_updater(&second_id).await?;
+        env.tx_builder.set_price_quorum(weth_asset_id, 2).await?;
+
+        env.tx_builder.update_price(&first_id, weth_asset_id, 2000 * 10u64.pow(8)).await?;
+        env.chain.advance_time(3601).await?;
+
+        // The first submission is stale, so the second one does not reach the quorum
+        env.tx_builder.update_price(&second_id, weth_asset_id, 2200 * 10u64.pow(8)).await?;
+        let err = env.tx_builder.get_price(weth_asset_id).await.unwrap_err();
+        assert!(fails_with(&err, &errors::ERR_PRICE_ORACLE_STALE_PRICE));
+
+        let submissions = env.tx_builder.get_price_submissions(weth_asset_id).await?;
+        assert_eq!(
+            submissions.iter().map(|submission| submission.is_fresh).collect::<Vec<_>>(),
+            [false, true]
+        );
+
+        env.tx_builder.update_price(&first_id, weth_asset_id, 2100 * 10u64.pow(8)).await?;
+        assert_eq!(env.tx_builder.get_price(weth_asset_id).await?, 2150 * 10u64.pow(8));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_signed_price_notes() -> Result<()> {
+        use components::price_note::{PriceNote, PriceNoteBuilder};
+        use miden_objects::{account::AccountId as MidenAccountId, utils::{Deserializable, Serializable}};
+
+        let mut env = setup_test_env().await?;
+
+        let weth_asset_id = 3;
+        let updater_id = env.account_manager.create_user_account("private", &env.pool_id).await?;
+        env.tx_builder.add_price_updater(&updater_id).await?;
+
+        // The updater signs a price off-chain, and the note can be relayed by anyone
+        let timestamp = env.chain.latest_block_timestamp().await as u64;
+        let price_note = env.tx_builder
+            .sign_price(&updater_id, weth_asset_id, 2600 * 10u64.pow(8), timestamp)
+            .await?;
+        let price_note = PriceNote::read_from_bytes(&price_note.to_bytes())?;
+        assert_eq!(price_note.asset_id(), weth_asset_id);
+        assert_eq!(price_note.timestamp(), timestamp);
+
+        env.chain.advance_time(60).await?;
+        env.tx_builder.relay_price_notes(&[price_note]).await?;
+        assert_eq!(env.tx_builder.get_price(weth_asset_id).await?, 2600 * 10u64.pow(8));
+
+        let submissions = env.tx_builder.get_price_submissions(weth_asset_id).await?;
+        assert_eq!(submissions[0].timestamp, timestamp);
+        println!("✅ Signed price submitted by a relayed note");
+
+        // Each signed price counts once, so older prices cannot be replayed
+        let outdated_note = env.tx_builder
+            .sign_price(&updater_id, weth_asset_id, 2000 * 10u64.pow(8), timestamp)
+            .await?;
+        let err = env.tx_builder.relay_price_notes(&[outdated_note]).await.unwrap_err();
+        assert!(fails_with(&err, &errors::ERR_PRICE_ORACLE_OUTDATED_PRICE));
+
+        let future_note = env.tx_builder
+            .sign_price(&updater_id, weth_asset_id, 2000 * 10u64.pow(8), timestamp + 3600)
+            .await?;
+        let err = env.tx_builder.relay_price_notes(&[future_note]).await.unwrap_err();
+        assert!(fails_with(&err, &errors::ERR_PRICE_ORACLE_FUTURE_PRICE));
+
+        // Prices signed by other keys are rejected
+        let unauthorized_note = env.tx_builder
+            .sign_price(&env.user_id, weth_asset_id, 2000 * 10u64.pow(8), timestamp + 30)
+            .await?;
+        let err = env.tx_builder.relay_price_notes(&[unauthorized_note]).await.unwrap_err();
+        assert_eq!(err.downcast_ref(), Some(&errors::LendingError::UnauthorizedPriceUpdater));
+
+        // A note claiming another price than the signed one fails signature verification
+        let oracle_id = MidenAccountId::try_from(&env.oracle_id)?;
+        let signed = PriceNoteBuilder::new(oracle_id, weth_asset_id, 2700 * 10u64.pow(8), timestamp + 30);
+        let signature = env.chain.sign_message(&updater_id, signed.message()).await?;
+        let updater = env.chain.get_account(&updater_id).await?;
+        let tampered_note = PriceNoteBuilder::new(oracle_id, weth_asset_id, 10u64.pow(8), timestamp + 30)
+            .build(MidenAccountId::try_from(&updater_id)?, updater.public_key, signature)?;
+        assert!(env.tx_builder.relay_price_notes(&[tampered_note]).await.is_err());
+        assert_eq!(env.tx_builder.get_price(weth_asset_id).await?, 2600 * 10u64.pow(8));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_twap_weights_prices_by_time() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        let weth_asset_id = 3;
+        let updater_id = env.account_manager.create_user_account("private", &env.pool_id).await?;
+        env.tx_builder.add_price_updater(&updater_id).await?;
+        env.tx_builder.set_twap_window(weth_asset_id, 1000).await?;
+        assert!(env.tx_builder.set_twap_window(weth_asset_id, 0).await.is_err());
+
+        // Only the initial price was reported within the window
+        env.chain.advance_time(2000).await?;
+        assert_eq!(env.tx_builder.get_twap(weth_asset_id).await?, 2500 * 10u64.pow(8));
+
+        env.tx_builder.update_price(&updater_id, weth_asset_id, 3000 * 10u64.pow(8)).await?;
+        let reported_at = env.tx_builder.get_price_submissions(weth_asset_id).await?[0].timestamp;
+        env.chain.advance_time(400).await?;
+
+        // The new price weighs in for the time it has been reported within the window
+        let held = env.chain.latest_block_timestamp().await as u64 - reported_at;
+        let expected_twap = 3 * 10u64.pow(8) * held + 25 * 10u64.pow(7) * (1000 - held);
+        assert_eq!(env.tx_builder.get_twap(weth_asset_id).await?, expected_twap);
+        assert_eq!(env.tx_builder.get_price(weth_asset_id).await?, 3000 * 10u64.pow(8));
+        println!("✅ TWAP {} after {}s at the new price", expected_twap, held);
+
+        // Newer observations overwrite the oldest ones, and the TWAP averages over the time the
+        // remaining observations cover
+        for _ in 0..components::price_oracle::PriceOracleAccount::OBSERVATION_BUFFER_SIZE {
+            env.tx_builder.update_price(&updater_id, weth_asset_id, 2000 * 10u64.pow(8)).await?;
+        }
+        assert_eq!(env.tx_builder.get_twap(weth_asset_id).await?, 2000 * 10u64.pow(8));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_pauses_large_moves() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        let weth_asset_id = 3;
+        let updater_id = env.account_manager.create_user_account("private", &env.pool_id).await?;
+        env.tx_builder.add_price_updater(&updater_id).await?;
+
+        // Allow 10% moves per hour
+        env.tx_builder.set_circuit_breaker(weth_asset_id, 1000, 3600).await?;
+        assert!(env.tx_builder.set_circuit_breaker(weth_asset_id, 1000, 0).await.is_err());
+
+        env.tx_builder.update_price(&updater_id, weth_asset_id, 2700 * 10u64.pow(8)).await?;
+        assert_eq!(env.tx_builder.get_price(weth_asset_id).await?, 2700 * 10u64.pow(8));
+
+        // 20% above the price an hour ago: the submission is kept but the feed is paused
+        env.tx_builder.update_price(&updater_id, weth_asset_id, 3000 * 10u64.pow(8)).await?;
+        assert!(env.tx_builder.get_price_parameters(weth_asset_id).await?.is_paused);
+        let err = env.tx_builder.get_price(weth_asset_id).await.unwrap_err();
+        assert!(fails_with(&err, &errors::ERR_PRICE_ORACLE_PAUSED));
+        let err = env.tx_builder.get_twap(weth_asset_id).await.unwrap_err();
+        assert!(fails_with(&err, &errors::ERR_PRICE_ORACLE_PAUSED));
+        println!("✅ Circuit breaker paused the WETH feed");
+
+        // Other feeds are unaffected
+        assert_eq!(env.tx_builder.get_price(1).await?, 10u64.pow(8));
+
+        // The admin accepts the move
+        env.tx_builder.resume_price_feed(weth_asset_id).await?;
+        assert!(!env.tx_builder.get_price_parameters(weth_asset_id).await?.is_paused);
+        assert_eq!(env.tx_builder.get_price(weth_asset_id).await?, 3000 * 10u64.pow(8));
+
+        // Without a circuit breaker any move is reported
+        env.tx_builder.set_circuit_breaker(weth_asset_id, 0, 0).await?;
+        env.tx_builder.update_price(&updater_id, weth_asset_id, 1000 * 10u64.pow(8)).await?;
+        assert_eq!(env.tx_builder.get_price(weth_asset_id).await?, 1000 * 10u64.pow(8));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_stale_prices_block_borrow_and_withdrawal() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        let user_id = env.user_id.clone();
+        env.tx_builder.deposit(&user_id, 1, 2000 * 10u64.pow(6)).await?;
+        env.tx_builder.supply_collateral(&user_id, 3, 10u64.pow(8)).await?;
+
+        // Prices published when the oracle was deployed expire after the default hour
+        let max_age = components::price_oracle::PriceOracleAccount::DEFAULT_MAX_PRICE_AGE;
+        env.chain.advance_time(max_age as u32 + 1).await?;
+
+        let err = env.tx_builder.borrow(&user_id, 1, 1000 * 10u64.pow(6)).await.unwrap_err();
+        assert!(fails_with(&err, &errors::ERR_PRICE_ORACLE_STALE_PRICE));
+        let err = env.tx_builder.withdraw_collateral(&user_id, 3, 10u64.pow(7)).await.unwrap_err();
+        assert!(fails_with(&err, &errors::ERR_PRICE_ORACLE_STALE_PRICE));
+        println!("✅ Stale prices rejected");
+
+        // Positions are valued with all user asset prices, so all of them are refreshed
+        let updater_id = env.account_manager.create_user_account("private", &env.pool_id).await?;
+        env.tx_builder.add_price_updater(&updater_id).await?;
+        for (asset_id, price) in [(1, 10u64.pow(8)), (2, 10u64.pow(8)), (3, 2500 * 10u64.pow(8))] {
+            env.tx_builder.update_price(&updater_id, asset_id, price).await?;
+        }
+
+        env.tx_builder.borrow(&user_id, 1, 1000 * 10u64.pow(6)).await?;
+        env.tx_builder.withdraw_collateral(&user_id, 3, 10u64.pow(7)).await?;
+        println!("✅ Borrowed and withdrew collateral with fresh prices");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_set_max_price_age() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        let user_id = env.user_id.clone();
+        env.tx_builder.deposit(&user_id, 1, 2000 * 10u64.pow(6)).await?;
+        env.tx_builder.supply_collateral(&user_id, 3, 10u64.pow(8)).await?;
+
+        let max_age = 2 * 3600;
+        for asset_id in [1, 2, 3] {
+            env.tx_builder.set_max_price_age(asset_id, max_age).await?;
+        }
+        assert!(env.tx_builder.set_max_price_age(1, 0).await.is_err());
+
+        // Prices more than an hour old are still valid
+        env.chain.advance_time(3601).await?;
+        env.tx_builder.borrow(&user_id, 1, 500 * 10u64.pow(6)).await?;
+
+        // Until they are older than the new maximum age
+        env.chain.advance_time(3600).await?;
+        let err = env.tx_builder.borrow(&user_id, 1, 500 * 10u64.pow(6)).await.unwrap_err();
+        assert!(fails_with(&err, &errors::ERR_PRICE_ORACLE_STALE_PRICE));
 
         Ok(())
     }
@@ -551,16 +2614,17 @@ mod tests {
         // 3. Verify health factor decreased
         // 4. If HF < 1.0, position becomes liquidatable
 
-        let user_id = miden_client::AccountId(env.user_id.clone());
+        let user_id = env.user_id.clone();
         let weth_asset_id = 3; // WETH
         let usdc_asset_id = 1; // USDC
 
         // Step 1: Setup position with WETH collateral at $2500
-        let collateral_amount = 10u64.pow(18); // 1 WETH
+        let collateral_amount = 10u64.pow(8); // 1 WETH
         env.tx_builder.supply_collateral(&user_id, weth_asset_id, collateral_amount).await?;
 
         // Borrow USDC (safe at $2500 WETH price)
         let borrow_amount = 1000 * 10u64.pow(6); // 1000 USDC
+        env.tx_builder.deposit(&user_id, usdc_asset_id, 2 * borrow_amount).await?;
         env.tx_builder.borrow(&user_id, usdc_asset_id, borrow_amount).await?;
         println!("✅ User borrowed 1000 USDC with 1 WETH collateral at $2500");
 
@@ -568,12 +2632,15 @@ mod tests {
         let initial_hf = env.tx_builder.calculate_health_factor(&user_id).await?;
         println!("📊 Initial health factor: {:.2}", initial_hf as f64 / 10000.0);
 
+        // $2500 * 82.5% / $1000, minus the interest accrued since the borrow
+        assert!((20615..=20625).contains(&initial_hf));
+
         // Step 2: WETH price drops to $2000
-        let admin_result = env.account_manager.create_user_account("private").await?;
-        let admin_id = miden_client::AccountId(extract_account_id(&admin_result));
+        let updater_id = env.account_manager.create_user_account("private", &env.pool_id).await?;
 
         let new_price = 2000 * 10u64.pow(8); // $2000
-        env.tx_builder.update_price(&admin_id, weth_asset_id, new_price).await?;
+        env.tx_builder.add_price_updater(&updater_id).await?;
+        env.tx_builder.update_price(&updater_id, weth_asset_id, new_price).await?;
         println!("⚠️  WETH price dropped to $2000");
 
         // Step 3: Verify health factor decreased
@@ -587,8 +2654,93 @@ mod tests {
             println!("✅ Position is still healthy");
         }
 
-        // In mock implementation, HF might not actually change
-        println!("⚠️  Mock implementation: health factor calculation is simulated");
+        // $2000 * 82.5% / $1000, minus the interest accrued since the borrow
+        assert!((16490..=16500).contains(&new_hf));
+        assert!(new_hf < initial_hf);
+
+        Ok(())
+    }
+
+    // =============================================================================================
+    // On-chain Health Factor Tests
+    // =============================================================================================
+
+    #[tokio::test]
+    async fn test_on_chain_health_factor_matches_local() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        // Test scenario:
+        // 1. User supplies USDC and WETH collateral
+        // 2. User borrows USDC and DAI
+        // 3. User account reads its health factor from the pool, valued at the oracle prices
+        // 4. Stored health factor matches the local calculation
+
+        let user_id = env.user_id.clone();
+
+        env.tx_builder.supply_collateral(&user_id, 1, 1000 * 10u64.pow(6)).await?; // 1000 USDC
+        env.tx_builder.supply_collateral(&user_id, 3, 10u64.pow(8)).await?; // 1 WETH
+
+        env.tx_builder.deposit(&user_id, 1, 2000 * 10u64.pow(6)).await?;
+        env.tx_builder.deposit(&user_id, 2, 8 * 10u64.pow(8)).await?;
+        env.tx_builder.borrow(&user_id, 1, 1000 * 10u64.pow(6)).await?; // 1000 USDC
+        env.tx_builder.borrow(&user_id, 2, 5 * 10u64.pow(8)).await?; // 5 DAI
+        println!("✅ User borrowed USDC and DAI against USDC and WETH");
+
+        // Computed at the same reference block as the transaction below
+        let local_hf = env.tx_builder.calculate_health_factor(&user_id).await?;
+
+        let on_chain_hf = env.tx_builder.update_health_factor(&user_id).await?;
+        println!("📊 On-chain health factor: {:.2}", on_chain_hf as f64 / 10000.0);
+
+        assert_eq!(on_chain_hf, local_hf);
+
+        // ($1000 * 85% + $2500 * 82.5%) / $1005, minus the interest accrued since the borrows
+        assert!((28968..=28980).contains(&on_chain_hf));
+
+        // The user account caches the health factor the pool reported
+        let user_account = env.chain.get_account(&user_id).await?;
+        assert_eq!(user_account.storage[0], on_chain_hf);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_on_chain_health_factor_follows_price() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        let user_id = env.user_id.clone();
+
+        env.tx_builder.supply_collateral(&user_id, 3, 10u64.pow(8)).await?; // 1 WETH
+        env.tx_builder.deposit(&user_id, 1, 2000 * 10u64.pow(6)).await?;
+        env.tx_builder.borrow(&user_id, 1, 1000 * 10u64.pow(6)).await?; // 1000 USDC
+
+        let initial_hf = env.tx_builder.update_health_factor(&user_id).await?;
+
+        // WETH price drops to $1000
+        let updater_id = env.account_manager.create_user_account("private", &env.pool_id).await?;
+        env.tx_builder.add_price_updater(&updater_id).await?;
+        env.tx_builder.update_price(&updater_id, 3, 1000 * 10u64.pow(8)).await?;
+
+        let local_hf = env.tx_builder.calculate_health_factor(&user_id).await?;
+        let new_hf = env.tx_builder.update_health_factor(&user_id).await?;
+        println!("📊 Health factor after price drop: {:.2}", new_hf as f64 / 10000.0);
+
+        assert_eq!(new_hf, local_hf);
+        assert!(new_hf < transactions::HEALTH_FACTOR_ONE);
+        assert!(new_hf < initial_hf);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_on_chain_health_factor_without_debt() -> Result<()> {
+        let mut env = setup_test_env().await?;
+
+        let user_id = env.user_id.clone();
+        env.tx_builder.supply_collateral(&user_id, 3, 10u64.pow(8)).await?; // 1 WETH
+
+        let health_factor = env.tx_builder.update_health_factor(&user_id).await?;
+        assert_eq!(health_factor, transactions::NO_DEBT_HEALTH_FACTOR);
 
         Ok(())
     }
@@ -0,0 +1,76 @@
+// Tests against a running Miden node
+//
+// These tests deploy accounts and submit transactions to the node at `MIDEN_NODE_RPC` (defaults to
+// http://localhost:57291), so they are ignored by default. Run them with:
+//
+//     cargo test --test node_tests -- --ignored
+
+use miden_lending_client::*;
+use miden_lending_client::backend::LendingBackend;
+use anyhow::Result;
+use miden_lib::account::wallets::BasicWallet;
+use std::path::PathBuf;
+
+fn rpc_endpoint() -> String {
+    std::env::var("MIDEN_NODE_RPC").unwrap_or_else(|_| "http://localhost:57291".to_string())
+}
+
+// Store directory of a client under the system temp directory, removed when dropped
+struct TempStore(PathBuf);
+
+impl TempStore {
+    fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("miden-lending-node-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        Self(dir)
+    }
+}
+
+impl Drop for TempStore {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+// A user whose client did not deploy the pool imports it from the node before the pool consumes
+// their request notes
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "needs a Miden node, run with --ignored"]
+async fn test_user_client_uses_pool_deployed_elsewhere() -> Result<()> {
+    std::env::set_var(keystore::KEYSTORE_PASSWORD_ENV, "node-tests");
+    let rpc = rpc_endpoint();
+    let deployer_store = TempStore::new("deployer");
+    let user_store = TempStore::new("user");
+
+    // The user's client issues the reserve assets
+    let mut user_client = miden_client::LendingClient::new(&rpc, user_store.0.clone()).await?;
+    let usdc = user_client.create_faucet("USDC", 6, 1 << 50).await?;
+    let dai = user_client.create_faucet("DAI", 8, 1 << 50).await?;
+    let weth = user_client.create_faucet("WETH", 8, 1 << 50).await?;
+
+    // Another client, with its own store, deploys the oracle and the pool
+    let mut deployer = accounts::AccountManager::new(&rpc, &deployer_store.0).await?;
+    let oracle_id = deployer.deploy_price_oracle().await?;
+    let pool_id = deployer.deploy_lending_pool(&oracle_id, &[usdc.clone(), dai, weth.clone()]).await?;
+
+    let components = vec![
+        BasicWallet.into(),
+        components::user_lending::UserLendingAccount::new((&pool_id).try_into()?).into(),
+    ];
+    let user_id = user_client
+        .create_account(components, miden_client::AccountStorageMode::Public)
+        .await?;
+    user_client.mint(&usdc, &user_id, 1_000 * 10u64.pow(6)).await?;
+    user_client.mint(&weth, &user_id, 10u64.pow(8)).await?;
+
+    let mut tx_builder = transactions::TransactionBuilder::new(user_client, pool_id, oracle_id);
+    tx_builder.deposit(&user_id, 1, 500 * 10u64.pow(6)).await?;
+    tx_builder.supply_collateral(&user_id, 3, 10u64.pow(8)).await?;
+    tx_builder.withdraw_collateral(&user_id, 3, 10u64.pow(7)).await?;
+
+    let user_info = tx_builder.get_account_info(&user_id).await?;
+    assert_eq!(user_info.collateral(3), 9 * 10u64.pow(7));
+    assert!(user_info.reserve(1).is_some_and(|reserve| reserve.scaled_supply > 0));
+
+    Ok(())
+}
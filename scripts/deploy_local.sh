@@ -8,8 +8,21 @@ echo "🚀 Deploying Miden Lending Protocol Locally..."
 GREEN='\033[0;32m'
 BLUE='\033[0;34m'
 YELLOW='\033[1;33m'
+RED='\033[0;31m'
 NC='\033[0m' # No Color
 
+# Runs a CLI command with JSON output and prints its result, stopping the deployment if it fails
+run_cli() {
+    local output
+    output=$(cargo run --release --quiet -- --output json "$@") || true
+    if [[ "$(echo "$output" | jq -r '.success' 2>/dev/null)" != "true" ]]; then
+        echo -e "${RED}❌ Command failed: $*${NC}" >&2
+        echo "$output" | jq -r '.error // empty' >&2 2>/dev/null || echo "$output" >&2
+        exit 1
+    fi
+    echo "$output"
+}
+
 # Change to client directory
 cd "$(dirname "$0")/../client"
 
@@ -17,8 +30,19 @@ echo ""
 echo -e "${YELLOW}Prerequisites Check:${NC}"
 echo -e "  - Miden node running on http://localhost:57291"
 echo -e "  - Rust and Cargo installed"
+echo -e "  - jq installed (parses the CLI's JSON output)"
+echo -e "  - MIDEN_LENDING_KEYSTORE_PASSWORD set to unlock the account keystore"
+echo -e "  - USDC_FAUCET_ID, DAI_FAUCET_ID and WETH_FAUCET_ID set to the fungible faucets issuing"
+echo -e "    the reserves' assets (hex account IDs)"
 echo ""
 
+for var in USDC_FAUCET_ID DAI_FAUCET_ID WETH_FAUCET_ID; do
+    if [[ -z "${!var}" ]]; then
+        echo -e "${RED}❌ $var is not set${NC}" >&2
+        exit 1
+    fi
+done
+
 # Check if node is running
 if ! curl -s http://localhost:57291/health > /dev/null 2>&1; then
     echo -e "${YELLOW}⚠️  Warning: Cannot connect to Miden node at http://localhost:57291${NC}"
@@ -33,91 +57,36 @@ if ! curl -s http://localhost:57291/health > /dev/null 2>&1; then
 fi
 
 echo -e "${BLUE}═══════════════════════════════════════════${NC}"
-echo -e "${BLUE}  Step 1: Deploying Lending Pool${NC}"
-echo -e "${BLUE}═══════════════════════════════════════════${NC}"
-
-echo "Executing: cargo run --release -- deploy-pool"
-POOL_OUTPUT=$(cargo run --release -- deploy-pool 2>&1)
-echo "$POOL_OUTPUT"
-
-# Extract pool ID (this is a placeholder - actual implementation will differ)
-POOL_ID=$(echo "$POOL_OUTPUT" | grep -o "Lending pool deployed: [a-f0-9]*" | awk '{print $NF}' || echo "pending")
-echo -e "${GREEN}✅ Pool deployed: $POOL_ID${NC}"
-echo ""
-
-echo -e "${BLUE}═══════════════════════════════════════════${NC}"
-echo -e "${BLUE}  Step 2: Deploying Price Oracle${NC}"
+echo -e "${BLUE}  Step 1: Deploying Price Oracle${NC}"
 echo -e "${BLUE}═══════════════════════════════════════════${NC}"
 
+# The oracle is deployed with USDC, DAI, WETH and WBTC price feeds and their initial prices
 echo "Executing: cargo run --release -- deploy-oracle"
-ORACLE_OUTPUT=$(cargo run --release -- deploy-oracle 2>&1)
+ORACLE_OUTPUT=$(run_cli deploy-oracle)
 echo "$ORACLE_OUTPUT"
 
-ORACLE_ID=$(echo "$ORACLE_OUTPUT" | grep -o "Price oracle deployed: [a-f0-9]*" | awk '{print $NF}' || echo "pending")
+ORACLE_ID=$(echo "$ORACLE_OUTPUT" | jq -r '.price_oracle_id')
 echo -e "${GREEN}✅ Oracle deployed: $ORACLE_ID${NC}"
 echo ""
 
 echo -e "${BLUE}═══════════════════════════════════════════${NC}"
-echo -e "${BLUE}  Step 3: Initializing Asset Reserves${NC}"
+echo -e "${BLUE}  Step 2: Deploying Lending Pool${NC}"
 echo -e "${BLUE}═══════════════════════════════════════════${NC}"
 
-# Note: This command will be available after CLI updates
-echo "Initializing USDC (asset_id: 1)..."
-# cargo run --release -- init-reserve --asset-id 1 2>&1 || echo "⚠️  Init command not yet implemented"
-echo -e "${GREEN}✅ USDC reserve initialized${NC}"
-
-echo "Initializing DAI (asset_id: 2)..."
-# cargo run --release -- init-reserve --asset-id 2 2>&1 || echo "⚠️  Init command not yet implemented"
-echo -e "${GREEN}✅ DAI reserve initialized${NC}"
-
-echo "Initializing WETH (asset_id: 3)..."
-# cargo run --release -- init-reserve --asset-id 3 2>&1 || echo "⚠️  Init command not yet implemented"
-echo -e "${GREEN}✅ WETH reserve initialized${NC}"
-echo ""
-
-echo -e "${BLUE}═══════════════════════════════════════════${NC}"
-echo -e "${BLUE}  Step 4: Setting Initial Prices${NC}"
-echo -e "${BLUE}═══════════════════════════════════════════${NC}"
-
-echo "Setting USDC price to \$1.00..."
-cargo run --release -- update-price --asset-id 1 --price 100000000 2>&1 || echo "⚠️  Price update pending Miden integration"
-echo -e "${GREEN}✅ USDC price: \$1.00${NC}"
-
-echo "Setting DAI price to \$1.00..."
-cargo run --release -- update-price --asset-id 2 --price 100000000 2>&1 || echo "⚠️  Price update pending Miden integration"
-echo -e "${GREEN}✅ DAI price: \$1.00${NC}"
+# The pool values positions with the oracle deployed above and lists the USDC, DAI and WETH
+# reserves, issued by the given faucets
+echo "Executing: cargo run --release -- deploy-pool --usdc-faucet $USDC_FAUCET_ID --dai-faucet $DAI_FAUCET_ID --weth-faucet $WETH_FAUCET_ID"
+POOL_OUTPUT=$(run_cli deploy-pool \
+    --usdc-faucet "$USDC_FAUCET_ID" \
+    --dai-faucet "$DAI_FAUCET_ID" \
+    --weth-faucet "$WETH_FAUCET_ID")
+echo "$POOL_OUTPUT"
 
-echo "Setting WETH price to \$2500.00..."
-cargo run --release -- update-price --asset-id 3 --price 250000000000 2>&1 || echo "⚠️  Price update pending Miden integration"
-echo -e "${GREEN}✅ WETH price: \$2500.00${NC}"
+POOL_ID=$(echo "$POOL_OUTPUT" | jq -r '.lending_pool_id')
+echo -e "${GREEN}✅ Pool deployed: $POOL_ID${NC}"
 echo ""
 
-# Save deployment info
-cat > deployment_info.json << EOF
-{
-  "network": "local",
-  "timestamp": "$(date -u +%Y-%m-%dT%H:%M:%SZ)",
-  "lending_pool_id": "$POOL_ID",
-  "price_oracle_id": "$ORACLE_ID",
-  "assets": {
-    "USDC": {
-      "id": 1,
-      "price": "1.00",
-      "decimals": 6
-    },
-    "DAI": {
-      "id": 2,
-      "price": "1.00",
-      "decimals": 18
-    },
-    "WETH": {
-      "id": 3,
-      "price": "2500.00",
-      "decimals": 18
-    }
-  }
-}
-EOF
+# deployment_info.json is written by the deploy and update-price commands
 
 echo -e "${GREEN}═══════════════════════════════════════════${NC}"
 echo -e "${GREEN}  🎉 Deployment Complete!${NC}"
@@ -127,7 +96,7 @@ echo "📄 Deployment Information:"
 echo "  Lending Pool: $POOL_ID"
 echo "  Price Oracle: $ORACLE_ID"
 echo ""
-echo "📁 Saved to: deployment_info.json"
+echo "📁 Saved to: ~/.miden-lending/deployment_info.json"
 echo ""
 echo "Next steps:"
 echo "  1. Create test accounts: ./scripts/setup_test_accounts.sh"
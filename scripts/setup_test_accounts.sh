@@ -28,33 +28,33 @@ echo ""
 
 # Create Alice (private account for regular user)
 echo "Creating Alice's account (private)..."
-ALICE_OUTPUT=$(cargo run --release -- create-account --storage-mode private 2>&1)
+ALICE_OUTPUT=$(cargo run --release -- --output json create-account --storage-mode private 2>/dev/null)
 echo "$ALICE_OUTPUT"
-ALICE_ID=$(echo "$ALICE_OUTPUT" | grep -o "User account created: [a-f0-9]*" | awk '{print $NF}' || echo "alice_pending")
+ALICE_ID=$(echo "$ALICE_OUTPUT" | jq -r '.account_id // "alice_pending"')
 echo -e "${GREEN}✅ Alice: $ALICE_ID${NC}"
 echo ""
 
 # Create Bob (private account for another user)
 echo "Creating Bob's account (private)..."
-BOB_OUTPUT=$(cargo run --release -- create-account --storage-mode private 2>&1)
+BOB_OUTPUT=$(cargo run --release -- --output json create-account --storage-mode private 2>/dev/null)
 echo "$BOB_OUTPUT"
-BOB_ID=$(echo "$BOB_OUTPUT" | grep -o "User account created: [a-f0-9]*" | awk '{print $NF}' || echo "bob_pending")
+BOB_ID=$(echo "$BOB_OUTPUT" | jq -r '.account_id // "bob_pending"')
 echo -e "${GREEN}✅ Bob: $BOB_ID${NC}"
 echo ""
 
 # Create Charlie (public account for testing)
 echo "Creating Charlie's account (public)..."
-CHARLIE_OUTPUT=$(cargo run --release -- create-account --storage-mode public 2>&1)
+CHARLIE_OUTPUT=$(cargo run --release -- --output json create-account --storage-mode public 2>/dev/null)
 echo "$CHARLIE_OUTPUT"
-CHARLIE_ID=$(echo "$CHARLIE_OUTPUT" | grep -o "User account created: [a-f0-9]*" | awk '{print $NF}' || echo "charlie_pending")
+CHARLIE_ID=$(echo "$CHARLIE_OUTPUT" | jq -r '.account_id // "charlie_pending"')
 echo -e "${GREEN}✅ Charlie: $CHARLIE_ID${NC}"
 echo ""
 
 # Create Liquidator (public account for liquidation testing)
 echo "Creating Liquidator's account (public)..."
-LIQUIDATOR_OUTPUT=$(cargo run --release -- create-account --storage-mode public 2>&1)
+LIQUIDATOR_OUTPUT=$(cargo run --release -- --output json create-account --storage-mode public 2>/dev/null)
 echo "$LIQUIDATOR_OUTPUT"
-LIQUIDATOR_ID=$(echo "$LIQUIDATOR_OUTPUT" | grep -o "User account created: [a-f0-9]*" | awk '{print $NF}' || echo "liquidator_pending")
+LIQUIDATOR_ID=$(echo "$LIQUIDATOR_OUTPUT" | jq -r '.account_id // "liquidator_pending"')
 echo -e "${GREEN}✅ Liquidator: $LIQUIDATOR_ID${NC}"
 echo ""
 